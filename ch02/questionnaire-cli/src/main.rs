@@ -1,3 +1,4 @@
+use std::num::IntErrorKind;
 use std::str::FromStr;
 
 #[derive(Debug)]
@@ -12,16 +13,52 @@ impl std::fmt::Display for QuestionId {
   }
 }
 
+/// Represents an error for parsing the identifier (ID) of a question.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum IdParseError {
+  /// No ID was given.
+  Empty,
+  /// The ID is not an integer number.
+  NotANumber(String),
+  /// The ID is a negative number.
+  Negative(i32),
+  /// The ID is a number too large to be an ID.
+  Overflow(String),
+}
+
+impl std::fmt::Display for IdParseError {
+  fn fmt(
+    &self,
+    f: &mut std::fmt::Formatter<'_>,
+  ) -> std::fmt::Result {
+    match self {
+      IdParseError::Empty => write!(f, "No ID provided."),
+      IdParseError::NotANumber(id) => write!(f, "ID '{}' is not an integer.", id),
+      IdParseError::Negative(id) => write!(f, "ID {} is negative.", id),
+      IdParseError::Overflow(id) => write!(f, "ID {} is out of range.", id),
+    }
+  }
+}
+
+impl std::error::Error for IdParseError {}
+
 impl std::str::FromStr for QuestionId {
-  type Err = std::io::Error;
+  type Err = IdParseError;
 
   fn from_str(id: &str) -> Result<Self, Self::Err> {
-    match id.is_empty() {
-      false => Ok(QuestionId(id.to_string())),
-      true => Err(std::io::Error::new(
-        std::io::ErrorKind::InvalidInput,
-        "No ID provided",
-      )),
+    if id.is_empty() {
+      return Err(IdParseError::Empty);
+    }
+
+    match id.parse::<i32>() {
+      Ok(value) if value < 0 => Err(IdParseError::Negative(value)),
+      Ok(_) => Ok(QuestionId(id.to_string())),
+      Err(err) => match err.kind() {
+        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+          Err(IdParseError::Overflow(id.to_string()))
+        }
+        _ => Err(IdParseError::NotANumber(id.to_string())),
+      },
     }
   }
 }
@@ -81,3 +118,43 @@ fn main() {
   );
   println!("{:?}", &question);
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_a_question_id() {
+    let id = QuestionId::from_str("42").expect("valid ID");
+    assert_eq!(id.0, "42");
+  }
+
+  #[test]
+  fn rejects_an_empty_id() {
+    assert_eq!(QuestionId::from_str("").unwrap_err(), IdParseError::Empty);
+  }
+
+  #[test]
+  fn rejects_a_non_numeric_id() {
+    assert_eq!(
+      QuestionId::from_str("abc").unwrap_err(),
+      IdParseError::NotANumber("abc".to_string())
+    );
+  }
+
+  #[test]
+  fn rejects_a_negative_id() {
+    assert_eq!(
+      QuestionId::from_str("-3").unwrap_err(),
+      IdParseError::Negative(-3)
+    );
+  }
+
+  #[test]
+  fn rejects_an_overflowing_id() {
+    assert_eq!(
+      QuestionId::from_str("99999999999").unwrap_err(),
+      IdParseError::Overflow("99999999999".to_string())
+    );
+  }
+}
@@ -1,9 +1,11 @@
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use serde::{Deserialize, Serialize};
 use warp::filters::body::BodyDeserializeError;
 use warp::filters::cors::CorsForbidden;
+use warp::http::header::LOCATION;
 use warp::http::Method;
 use warp::http::StatusCode;
-use warp::reject::Reject;
+use warp::reject::{LengthRequired, PayloadTooLarge, Reject};
 use warp::Filter;
 use warp::Rejection;
 use warp::Reply;
@@ -12,6 +14,13 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// Characters encoded in a segment of a path, all but the letters, digits and `-._~`.
+const PATH_SEGMENT: &AsciiSet =
+  &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
+/// Largest size in bytes of the request bodies, if `REQUEST_BODY_MAX_BYTES` is not set.
+const DEFAULT_BODY_MAX_BYTES: u64 = 64 * 1024;
+
 /* #[derive(Debug)]
 struct InvalidId;
 impl Reject for InvalidId {} */
@@ -84,6 +93,13 @@ async fn get_questions(
     // Clone each question because collect() requires owernship of each question value.
     let data: Vec<Question> = store.questions.read().await.values().cloned().collect();
     // Check a valid range of results
+    // A page that starts right after the last question is empty, like a slice.
+    if pagination.start > data.len() {
+      return Err(warp::reject::custom(Error::StartOutOfRange(
+        pagination.start,
+        data.len(),
+      )));
+    }
     if pagination.end > data.len() {
       pagination.end = data.len();
     }
@@ -110,9 +126,16 @@ async fn add_question(
     .questions
     .write()
     .await
-    .insert(question.id.clone(), question);
-
-  Ok(warp::reply::with_status("Question added", StatusCode::OK))
+    .insert(question.id.clone(), question.clone());
+
+  Ok(warp::reply::with_header(
+    warp::reply::with_status(warp::reply::json(&question), StatusCode::CREATED),
+    LOCATION,
+    format!(
+      "/questions/{}",
+      utf8_percent_encode(&question.id.0, PATH_SEGMENT)
+    ),
+  ))
 }
 
 /// Updates an existing question with the given the ID and data store.
@@ -214,8 +237,14 @@ enum Error {
   ParseError(std::num::ParseIntError),
   /// A kind of error for missing parameters.
   MissingParameters,
+  /// A kind of error for request bodies larger than the limit, with the largest size
+  /// allowed in bytes.
+  PayloadTooLarge(u64),
   /// A kind of error for questions not found.
   QuestionNotFound,
+  /// A kind of error for pages that start past the end of the questions, with the
+  /// start index and the amount of questions.
+  StartOutOfRange(usize, usize),
 }
 
 impl std::fmt::Display for Error {
@@ -228,7 +257,17 @@ impl std::fmt::Display for Error {
         write!(f, "Cannot parse the parameter: {}", err)
       }
       Error::MissingParameters => write!(f, "Missing parameter."),
+      Error::PayloadTooLarge(max_bytes) => write!(
+        f,
+        "Payload too large: the body must have at most {} bytes.",
+        max_bytes
+      ),
       Error::QuestionNotFound => write!(f, "Question not found."),
+      Error::StartOutOfRange(start, amount) => write!(
+        f,
+        "Range not satisfiable: start must be from 0 to {}, found {}.",
+        amount, start
+      ),
     }
   }
 }
@@ -256,10 +295,14 @@ async fn return_error(rej: Rejection) -> Result<impl Reply, Rejection> {
         error.to_string(),
         StatusCode::BAD_REQUEST,
       )),
-      _ => Ok(warp::reply::with_status(
+      Error::StartOutOfRange(_, _) => Ok(warp::reply::with_status(
         error.to_string(),
         StatusCode::RANGE_NOT_SATISFIABLE,
       )),
+      Error::PayloadTooLarge(_) => Ok(warp::reply::with_status(
+        error.to_string(),
+        StatusCode::PAYLOAD_TOO_LARGE,
+      )),
     }
   }
   // Handle CORS errors
@@ -276,6 +319,13 @@ async fn return_error(rej: Rejection) -> Result<impl Reply, Rejection> {
       StatusCode::UNPROCESSABLE_ENTITY,
     ))
   }
+  // Handle bodies sent without their length, which cannot be limited
+  else if let Some(error) = rej.find::<LengthRequired>() {
+    Ok(warp::reply::with_status(
+      error.to_string(),
+      StatusCode::LENGTH_REQUIRED,
+    ))
+  }
   // At this point, the possible rejection is that a path not found
   else {
     Ok(warp::reply::with_status(
@@ -285,6 +335,22 @@ async fn return_error(rej: Rejection) -> Result<impl Reply, Rejection> {
   }
 }
 
+/// Creates a filter that rejects the requests whose body is larger than the given
+/// size with a `Error::PayloadTooLarge` error, and the ones without a
+/// `Content-Length` header, since their size is not known beforehand.
+///
+/// # Arguments
+///
+/// * `max_bytes`: Largest size of the body allowed, in bytes.
+fn body_limit(max_bytes: u64) -> impl Filter<Extract = (), Error = Rejection> + Copy {
+  warp::body::content_length_limit(max_bytes).or_else(move |rej: Rejection| async move {
+    match rej.find::<PayloadTooLarge>() {
+      Some(_) => Err(warp::reject::custom(Error::PayloadTooLarge(max_bytes))),
+      None => Err(rej),
+    }
+  })
+}
+
 /// Represents the data store for the application.
 #[derive(Clone)]
 struct Store {
@@ -312,6 +378,13 @@ async fn main() {
   let store = Store::new();
   let store_filter = warp::any().map(move || store.clone());
 
+  // Largest size of the request bodies, e.g. `REQUEST_BODY_MAX_BYTES=65536`
+  let body_max_bytes: u64 = std::env::var("REQUEST_BODY_MAX_BYTES")
+    .ok()
+    .and_then(|bytes| bytes.parse().ok())
+    .filter(|bytes| *bytes >= 1)
+    .unwrap_or(DEFAULT_BODY_MAX_BYTES);
+
   let cors = warp::cors()
     .allow_any_origin()
     .allow_header("content-type")
@@ -328,6 +401,7 @@ async fn main() {
     .and(warp::path("questions"))
     .and(warp::path::end())
     .and(store_filter.clone())
+    .and(body_limit(body_max_bytes))
     .and(warp::body::json())
     .and_then(add_question);
 
@@ -336,6 +410,7 @@ async fn main() {
     .and(warp::path::param::<String>())
     .and(warp::path::end())
     .and(store_filter.clone())
+    .and(body_limit(body_max_bytes))
     .and(warp::body::json())
     .and_then(update_question);
 
@@ -355,3 +430,64 @@ async fn main() {
 
   warp::serve(routes).run(([127, 0, 0, 1], 3030)).await;
 }
+
+#[cfg(test)]
+mod tests {
+  use warp::hyper::body::to_bytes;
+
+  use super::*;
+
+  /// Gets the questions of the page between the given indexes.
+  ///
+  /// # Arguments
+  ///
+  /// * `store`: Data store that contains all the questions.
+  /// * `start`: Index of the first question of the page.
+  /// * `end`: Index after the last question of the page.
+  async fn page(
+    store: Store,
+    start: usize,
+    end: usize,
+  ) -> Result<warp::reply::Response, Rejection> {
+    let params = HashMap::from([
+      ("start".to_string(), start.to_string()),
+      ("end".to_string(), end.to_string()),
+    ]);
+    get_questions(params, store).await.map(Reply::into_response)
+  }
+
+  #[tokio::test]
+  async fn replies_an_empty_page_at_the_end() {
+    let store = Store::new();
+    let len = store.questions.read().await.len();
+
+    let reply = page(store, len, len + 5).await.unwrap();
+    let body = to_bytes(reply.into_body()).await.unwrap();
+    let questions: Vec<Question> = serde_json::from_slice(&body).unwrap();
+    assert!(questions.is_empty());
+  }
+
+  #[tokio::test]
+  async fn replies_416_to_a_start_past_the_end() {
+    let store = Store::new();
+    let len = store.questions.read().await.len();
+
+    let rej = page(store, len + 1, len + 5).await.err().unwrap();
+    let reply = return_error(rej).await.unwrap().into_response();
+    assert_eq!(reply.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+  }
+
+  #[tokio::test]
+  async fn encodes_the_id_in_the_location_of_a_new_question() {
+    let question = Question {
+      id: QuestionId("a b/c".to_string()),
+      title: "Title".to_string(),
+      content: "Content".to_string(),
+      tags: None,
+    };
+
+    let reply = add_question(Store::new(), question).await.unwrap().into_response();
+    assert_eq!(reply.status(), StatusCode::CREATED);
+    assert_eq!(reply.headers()[LOCATION], "/questions/a%20b%2Fc");
+  }
+}
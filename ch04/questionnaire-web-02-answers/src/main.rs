@@ -1,17 +1,27 @@
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use serde::{Deserialize, Serialize};
 use warp::filters::body::BodyDeserializeError;
 use warp::filters::cors::CorsForbidden;
+use warp::http::header::LOCATION;
 use warp::http::Method;
 use warp::http::StatusCode;
-use warp::reject::Reject;
+use warp::reject::{LengthRequired, PayloadTooLarge, Reject};
 use warp::Filter;
 use warp::Rejection;
 use warp::Reply;
 
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+/// Characters encoded in a segment of a path, all but the letters, digits and `-._~`.
+const PATH_SEGMENT: &AsciiSet =
+  &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
+/// Largest size in bytes of the request bodies, if `REQUEST_BODY_MAX_BYTES` is not set.
+const DEFAULT_BODY_MAX_BYTES: u64 = 64 * 1024;
+
 /* #[derive(Debug)]
 struct InvalidId;
 impl Reject for InvalidId {} */
@@ -84,12 +94,19 @@ async fn get_questions(
     // Clone each question because collect() requires owernship of each question value.
     let data: Vec<Question> = store.questions.read().await.values().cloned().collect();
     // Check a valid range of results
+    if pagination.start < 1 {
+      pagination.start = 1;
+    }
+    // A page that starts right after the last question is empty, like a slice.
+    if pagination.start > data.len() + 1 {
+      return Err(warp::reject::custom(QError::StartOutOfRange(
+        pagination.start,
+        data.len(),
+      )));
+    }
     if pagination.end > data.len() {
       pagination.end = data.len();
     }
-    if pagination.start < 1  {
-      pagination.start = 1;
-    }
     // Retrieve the result set as a slice of elements between the start and end indexes.
     let result_set: &[Question] = &data[(pagination.start - 1)..pagination.end];
     Ok(warp::reply::json(&result_set))
@@ -113,9 +130,16 @@ async fn add_question(
     .questions
     .write()
     .await
-    .insert(question.id.clone(), question);
-
-  Ok(warp::reply::with_status("Question added", StatusCode::OK))
+    .insert(question.id.clone(), question.clone());
+
+  Ok(warp::reply::with_header(
+    warp::reply::with_status(warp::reply::json(&question), StatusCode::CREATED),
+    LOCATION,
+    format!(
+      "/questions/{}",
+      utf8_percent_encode(&question.id.0, PATH_SEGMENT)
+    ),
+  ))
 } // end fn add_question()
 
 /// Updates an existing question with the given the ID and data store.
@@ -177,29 +201,66 @@ struct Answer {
   question_id: QuestionId,
 } // end struct Answer
 
+/// Represents the form fields of a new answer, which are checked before adding it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct NewAnswer {
+  /// Text contents of the answer.
+  content: Option<String>,
+  /// Unique identifier (ID) of the question this answer belongs to.
+  question_id: Option<String>,
+} // end struct NewAnswer
+
+impl NewAnswer {
+  /// Gets the content and the question ID of the answer, or a `MissingFields` error
+  /// listing the fields that were not given.
+  fn into_fields(self) -> Result<(String, QuestionId), QError> {
+    match (self.content, self.question_id) {
+      (Some(content), Some(question_id)) => Ok((content, QuestionId(question_id))),
+      (content, question_id) => {
+        let mut missing = Vec::new();
+        if content.is_none() {
+          missing.push("content".to_string());
+        }
+        if question_id.is_none() {
+          missing.push("question_id".to_string());
+        }
+        Err(QError::MissingFields(missing))
+      }
+    }
+  } // end fn into_fields()
+}
+
 /// Adds a new answer with the given parameters to a data store.
 ///
 /// # Arguments
 ///
 /// * `store`: Data store for where answer will be saved.
-/// * `params`: Set of parameters with data for adding a new answer.
+/// * `answer`: Form fields of the new answer.
 async fn add_answer(
   store: Store,
-  params: HashMap<String, String>,
+  answer: NewAnswer,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+  let (content, question_id) = match answer.into_fields() {
+    Ok(fields) => fields,
+    Err(err) => return Err(warp::reject::custom(err)),
+  };
+
   let answer = Answer {
-    id: AnswerId("1".to_string()),
-    content: params.get("content").unwrap().to_string(),
-    question_id: QuestionId(params.get("question_id").unwrap().to_string()),
+    id: store.next_answer_id(),
+    content,
+    question_id,
   };
 
   store
     .answers
     .write()
     .await
-    .insert(answer.id.clone(), answer);
+    .insert(answer.id.clone(), answer.clone());
 
-  Ok(warp::reply::with_status("Answer added", StatusCode::OK))
+  Ok(warp::reply::with_status(
+    warp::reply::json(&answer),
+    StatusCode::CREATED,
+  ))
 } // end fn add_answer()
 
 /// Represents the start and end index of a set of results.
@@ -257,8 +318,16 @@ enum QError {
   ParseError(std::num::ParseIntError),
   /// A kind of error for missing parameters.
   MissingParameters,
+  /// A kind of error for form fields that were not given, with their names.
+  MissingFields(Vec<String>),
+  /// A kind of error for request bodies larger than the limit, with the largest size
+  /// allowed in bytes.
+  PayloadTooLarge(u64),
   /// A kind of error for questions not found.
   QuestionNotFound,
+  /// A kind of error for pages that start past the end of the questions, with the
+  /// start index and the amount of questions.
+  StartOutOfRange(usize, usize),
 } // end enum QError
 
 impl std::fmt::Display for QError {
@@ -271,7 +340,21 @@ impl std::fmt::Display for QError {
         write!(f, "Cannot parse the parameter: {}", err)
       }
       QError::MissingParameters => write!(f, "Missing parameter."),
+      QError::MissingFields(ref fields) => {
+        write!(f, "Missing fields: {}.", fields.join(", "))
+      }
+      QError::PayloadTooLarge(max_bytes) => write!(
+        f,
+        "Payload too large: the body must have at most {} bytes.",
+        max_bytes
+      ),
       QError::QuestionNotFound => write!(f, "Question not found."),
+      QError::StartOutOfRange(start, amount) => write!(
+        f,
+        "Range not satisfiable: start must be from 1 to {}, found {}.",
+        amount + 1,
+        start
+      ),
     }
   }
 }
@@ -299,6 +382,18 @@ async fn return_error(rej: Rejection) -> Result<impl Reply, Rejection> {
         error.to_string(),
         StatusCode::BAD_REQUEST,
       )),
+      QError::MissingFields(_) => Ok(warp::reply::with_status(
+        error.to_string(),
+        StatusCode::BAD_REQUEST,
+      )),
+      QError::StartOutOfRange(_, _) => Ok(warp::reply::with_status(
+        error.to_string(),
+        StatusCode::RANGE_NOT_SATISFIABLE,
+      )),
+      QError::PayloadTooLarge(_) => Ok(warp::reply::with_status(
+        error.to_string(),
+        StatusCode::PAYLOAD_TOO_LARGE,
+      )),
       // _ => Ok(warp::reply::with_status(
       //   error.to_string(),
       //   StatusCode::RANGE_NOT_SATISFIABLE,
@@ -323,6 +418,13 @@ async fn return_error(rej: Rejection) -> Result<impl Reply, Rejection> {
       StatusCode::UNPROCESSABLE_ENTITY,
     ))
   }
+  // Handle bodies sent without their length, which cannot be limited
+  else if let Some(error) = rej.find::<LengthRequired>() {
+    Ok(warp::reply::with_status(
+      error.to_string(),
+      StatusCode::LENGTH_REQUIRED,
+    ))
+  }
   // At this point, the possible rejection is that a path not found
   else {
     Ok(warp::reply::with_status(
@@ -332,6 +434,22 @@ async fn return_error(rej: Rejection) -> Result<impl Reply, Rejection> {
   }
 } // end fn return_error()
 
+/// Creates a filter that rejects the requests whose body is larger than the given
+/// size with a `QError::PayloadTooLarge` error, and the ones without a
+/// `Content-Length` header, since their size is not known beforehand.
+///
+/// # Arguments
+///
+/// * `max_bytes`: Largest size of the body allowed, in bytes.
+fn body_limit(max_bytes: u64) -> impl Filter<Extract = (), Error = Rejection> + Copy {
+  warp::body::content_length_limit(max_bytes).or_else(move |rej: Rejection| async move {
+    match rej.find::<PayloadTooLarge>() {
+      Some(_) => Err(warp::reject::custom(QError::PayloadTooLarge(max_bytes))),
+      None => Err(rej),
+    }
+  })
+} // end fn body_limit()
+
 /// Represents the data store for the application.
 #[derive(Clone)]
 struct Store {
@@ -339,6 +457,8 @@ struct Store {
   questions: Arc<RwLock<HashMap<QuestionId, Question>>>,
   /// Collection of answers in the data store.
   answers: Arc<RwLock<HashMap<AnswerId, Answer>>>,
+  /// Last identifier (ID) given to an answer.
+  answer_sequence: Arc<AtomicUsize>,
 } // end struct Store
 
 impl Store {
@@ -347,9 +467,16 @@ impl Store {
     Self {
       questions: Arc::new(RwLock::new(Self::init())),
       answers: Arc::new(RwLock::new(HashMap::new())),
+      answer_sequence: Arc::new(AtomicUsize::new(0)),
     }
   } // end fn new()
 
+  /// Generates a new unique identifier (ID) for an answer.
+  fn next_answer_id(&self) -> AnswerId {
+    let id = self.answer_sequence.fetch_add(1, Ordering::SeqCst) + 1;
+    AnswerId(id.to_string())
+  } // end fn next_answer_id()
+
   /// Initializes the data store with available data.
   fn init() -> HashMap<QuestionId, Question> {
     let file: &str = include_str!("../questions.json");
@@ -362,6 +489,13 @@ async fn main() {
   let store = Store::new();
   let store_filter = warp::any().map(move || store.clone());
 
+  // Largest size of the request bodies, e.g. `REQUEST_BODY_MAX_BYTES=65536`
+  let body_max_bytes: u64 = std::env::var("REQUEST_BODY_MAX_BYTES")
+    .ok()
+    .and_then(|bytes| bytes.parse().ok())
+    .filter(|bytes| *bytes >= 1)
+    .unwrap_or(DEFAULT_BODY_MAX_BYTES);
+
   let cors = warp::cors()
     .allow_any_origin()
     .allow_header("content-type")
@@ -378,6 +512,7 @@ async fn main() {
     .and(warp::path("questions"))
     .and(warp::path::end())
     .and(store_filter.clone())
+    .and(body_limit(body_max_bytes))
     .and(warp::body::json())
     .and_then(add_question);
 
@@ -386,6 +521,7 @@ async fn main() {
     .and(warp::path::param::<String>())
     .and(warp::path::end())
     .and(store_filter.clone())
+    .and(body_limit(body_max_bytes))
     .and(warp::body::json()) // JSON Body with the question data.
     .and_then(update_question);
 
@@ -400,6 +536,7 @@ async fn main() {
     .and(warp::path("answers"))
     .and(warp::path::end())
     .and(store_filter.clone())
+    .and(body_limit(body_max_bytes))
     .and(warp::body::form())
     .and_then(add_answer);
 
@@ -413,3 +550,77 @@ async fn main() {
 
   warp::serve(routes).run(([127, 0, 0, 1], 3030)).await;
 }
+
+#[cfg(test)]
+mod tests {
+  use warp::hyper::body::to_bytes;
+
+  use super::*;
+
+  /// Gets the questions of the page between the given positions, counted from 1.
+  ///
+  /// # Arguments
+  ///
+  /// * `store`: Data store that contains all the questions.
+  /// * `start`: Position of the first question of the page.
+  /// * `end`: Position of the last question of the page.
+  async fn page(
+    store: Store,
+    start: usize,
+    end: usize,
+  ) -> Result<warp::reply::Response, Rejection> {
+    let params = HashMap::from([
+      ("start".to_string(), start.to_string()),
+      ("end".to_string(), end.to_string()),
+    ]);
+    get_questions(params, store).await.map(Reply::into_response)
+  }
+
+  #[tokio::test]
+  async fn replies_an_empty_page_at_the_end() {
+    let store = Store::new();
+    let len = store.questions.read().await.len();
+
+    let reply = page(store, len + 1, len + 5).await.unwrap();
+    let body = to_bytes(reply.into_body()).await.unwrap();
+    let questions: Vec<Question> = serde_json::from_slice(&body).unwrap();
+    assert!(questions.is_empty());
+  }
+
+  #[tokio::test]
+  async fn replies_416_to_a_start_past_the_end() {
+    let store = Store::new();
+    let len = store.questions.read().await.len();
+
+    let rej = page(store, len + 2, len + 5).await.err().unwrap();
+    let reply = return_error(rej).await.unwrap().into_response();
+    assert_eq!(reply.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+  }
+  #[tokio::test]
+  async fn replies_400_listing_the_missing_fields() {
+    let answer = NewAnswer {
+      content: None,
+      question_id: None,
+    };
+
+    let rej = add_answer(Store::new(), answer).await.err().unwrap();
+    let reply = return_error(rej).await.unwrap().into_response();
+    assert_eq!(reply.status(), StatusCode::BAD_REQUEST);
+    let body = to_bytes(reply.into_body()).await.unwrap();
+    assert_eq!(body, "Missing fields: content, question_id.");
+  }
+
+  #[tokio::test]
+  async fn encodes_the_id_in_the_location_of_a_new_question() {
+    let question = Question {
+      id: QuestionId("a b/c".to_string()),
+      title: "Title".to_string(),
+      content: "Content".to_string(),
+      tags: None,
+    };
+
+    let reply = add_question(Store::new(), question).await.unwrap().into_response();
+    assert_eq!(reply.status(), StatusCode::CREATED);
+    assert_eq!(reply.headers()[LOCATION], "/questions/a%20b%2Fc");
+  }
+}
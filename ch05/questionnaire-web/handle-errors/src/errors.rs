@@ -1,7 +1,7 @@
 use warp::filters::body::BodyDeserializeError;
 use warp::filters::cors::CorsForbidden;
 use warp::hyper::StatusCode;
-use warp::reject::Reject;
+use warp::reject::{LengthRequired, Reject};
 use warp::{Rejection, Reply};
 
 /// Represents an error for processing query parameters.
@@ -11,8 +11,16 @@ pub enum QError {
   ParseError(std::num::ParseIntError),
   /// A kind of error for missing parameters.
   MissingParameters,
+  /// A kind of error for form fields that were not given, with their names.
+  MissingFields(Vec<String>),
+  /// A kind of error for request bodies larger than the limit, with the largest size
+  /// allowed in bytes.
+  PayloadTooLarge(u64),
   /// A kind of error for questions not found.
   QuestionNotFound,
+  /// A kind of error for pages that start past the end of the questions, with the
+  /// start index and the amount of questions.
+  StartOutOfRange(usize, usize),
 } // end enum QError
 
 impl std::fmt::Display for QError {
@@ -25,7 +33,21 @@ impl std::fmt::Display for QError {
         write!(f, "Cannot parse the parameter: {}", err)
       }
       QError::MissingParameters => write!(f, "Missing parameter."),
+      QError::MissingFields(ref fields) => {
+        write!(f, "Missing fields: {}.", fields.join(", "))
+      }
+      QError::PayloadTooLarge(max_bytes) => write!(
+        f,
+        "Payload too large: the body must have at most {} bytes.",
+        max_bytes
+      ),
       QError::QuestionNotFound => write!(f, "Question not found."),
+      QError::StartOutOfRange(start, amount) => write!(
+        f,
+        "Range not satisfiable: start must be from 1 to {}, found {}.",
+        amount + 1,
+        start
+      ),
     }
   }
 }
@@ -53,6 +75,18 @@ pub async fn return_error(rej: Rejection) -> Result<impl Reply, Rejection> {
         error.to_string(),
         StatusCode::BAD_REQUEST,
       )),
+      QError::MissingFields(_) => Ok(warp::reply::with_status(
+        error.to_string(),
+        StatusCode::BAD_REQUEST,
+      )),
+      QError::StartOutOfRange(_, _) => Ok(warp::reply::with_status(
+        error.to_string(),
+        StatusCode::RANGE_NOT_SATISFIABLE,
+      )),
+      QError::PayloadTooLarge(_) => Ok(warp::reply::with_status(
+        error.to_string(),
+        StatusCode::PAYLOAD_TOO_LARGE,
+      )),
       // _ => Ok(warp::reply::with_status(
       //   error.to_string(),
       //   StatusCode::RANGE_NOT_SATISFIABLE,
@@ -77,6 +111,13 @@ pub async fn return_error(rej: Rejection) -> Result<impl Reply, Rejection> {
       StatusCode::UNPROCESSABLE_ENTITY,
     ))
   }
+  // Handle bodies sent without their length, which cannot be limited
+  else if let Some(error) = rej.find::<LengthRequired>() {
+    Ok(warp::reply::with_status(
+      error.to_string(),
+      StatusCode::LENGTH_REQUIRED,
+    ))
+  }
   // At this point, the possible rejection is that a path not found
   else {
     Ok(warp::reply::with_status(
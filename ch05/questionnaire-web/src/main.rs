@@ -6,16 +6,27 @@ use warp::http::Method;
 use warp::Filter;
 
 use crate::routes::answer::add_answer;
+use crate::routes::body::body_limit;
 use crate::routes::question::{
   add_question, delete_question, get_questions, update_question,
 };
 use crate::store::Store;
 
+/// Largest size in bytes of the request bodies, if `REQUEST_BODY_MAX_BYTES` is not set.
+const DEFAULT_BODY_MAX_BYTES: u64 = 64 * 1024;
+
 #[tokio::main]
 async fn main() {
   let store = Store::new();
   let store_filter = warp::any().map(move || store.clone());
 
+  // Largest size of the request bodies, e.g. `REQUEST_BODY_MAX_BYTES=65536`
+  let body_max_bytes: u64 = std::env::var("REQUEST_BODY_MAX_BYTES")
+    .ok()
+    .and_then(|bytes| bytes.parse().ok())
+    .filter(|bytes| *bytes >= 1)
+    .unwrap_or(DEFAULT_BODY_MAX_BYTES);
+
   let cors = warp::cors()
     .allow_any_origin()
     .allow_header("content-type")
@@ -32,6 +43,7 @@ async fn main() {
     .and(warp::path("questions"))
     .and(warp::path::end())
     .and(store_filter.clone())
+    .and(body_limit(body_max_bytes))
     .and(warp::body::json())
     .and_then(add_question);
 
@@ -40,6 +52,7 @@ async fn main() {
     .and(warp::path::param::<String>())
     .and(warp::path::end())
     .and(store_filter.clone())
+    .and(body_limit(body_max_bytes))
     .and(warp::body::json()) // JSON Body with the question data.
     .and_then(update_question);
 
@@ -54,6 +67,7 @@ async fn main() {
     .and(warp::path("answers"))
     .and(warp::path::end())
     .and(store_filter.clone())
+    .and(body_limit(body_max_bytes))
     .and(warp::body::form())
     .and_then(add_answer);
 
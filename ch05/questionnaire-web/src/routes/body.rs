@@ -0,0 +1,19 @@
+use handle_errors::errors::QError;
+use warp::reject::PayloadTooLarge;
+use warp::{Filter, Rejection};
+
+/// Creates a filter that rejects the requests whose body is larger than the given
+/// size with a `QError::PayloadTooLarge` error, and the ones without a
+/// `Content-Length` header, since their size is not known beforehand.
+///
+/// # Arguments
+///
+/// * `max_bytes`: Largest size of the body allowed, in bytes.
+pub fn body_limit(max_bytes: u64) -> impl Filter<Extract = (), Error = Rejection> + Copy {
+  warp::body::content_length_limit(max_bytes).or_else(move |rej: Rejection| async move {
+    match rej.find::<PayloadTooLarge>() {
+      Some(_) => Err(warp::reject::custom(QError::PayloadTooLarge(max_bytes))),
+      None => Err(rej),
+    }
+  })
+} // end fn body_limit()
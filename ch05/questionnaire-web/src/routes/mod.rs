@@ -1,2 +1,3 @@
 pub mod answer;
+pub mod body;
 pub mod question;
@@ -1,4 +1,6 @@
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use std::collections::HashMap;
+use warp::http::header::LOCATION;
 use warp::hyper::StatusCode;
 
 use handle_errors::errors::QError;
@@ -11,6 +13,10 @@ use crate::{
   },
 };
 
+/// Characters encoded in a segment of a path, all but the letters, digits and `-._~`.
+const PATH_SEGMENT: &AsciiSet =
+  &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
 /// Gets a set of questions from the given parameters and data store.
 ///
 /// # Arguments
@@ -26,12 +32,19 @@ pub async fn get_questions(
     // Clone each question because collect() requires owernship of each question value.
     let data: Vec<Question> = store.questions.read().await.values().cloned().collect();
     // Check a valid range of results
-    if pagination.end > data.len() {
-      pagination.end = data.len();
-    }
     if pagination.start < 1 {
       pagination.start = 1;
     }
+    // A page that starts right after the last question is empty, like a slice.
+    if pagination.start > data.len() + 1 {
+      return Err(warp::reject::custom(QError::StartOutOfRange(
+        pagination.start,
+        data.len(),
+      )));
+    }
+    if pagination.end > data.len() {
+      pagination.end = data.len();
+    }
     // Retrieve the result set as a slice of elements between the start and end indexes.
     let result_set: &[Question] = &data[(pagination.start - 1)..pagination.end];
     Ok(warp::reply::json(&result_set))
@@ -55,9 +68,16 @@ pub async fn add_question(
     .questions
     .write()
     .await
-    .insert(question.id.clone(), question);
+    .insert(question.id.clone(), question.clone());
 
-  Ok(warp::reply::with_status("Question added", StatusCode::OK))
+  Ok(warp::reply::with_header(
+    warp::reply::with_status(warp::reply::json(&question), StatusCode::CREATED),
+    LOCATION,
+    format!(
+      "/questions/{}",
+      utf8_percent_encode(&question.id.0, PATH_SEGMENT)
+    ),
+  ))
 } // end fn add_question()
 
 /// Updates an existing question with the given the ID and data store.
@@ -99,3 +119,96 @@ pub async fn delete_question(
     None => Err(warp::reject::custom(QError::QuestionNotFound)),
   }
 } // fn delete_question()
+
+#[cfg(test)]
+mod tests {
+  use warp::hyper::body::to_bytes;
+  use warp::Reply;
+
+  use super::*;
+
+  #[tokio::test]
+  async fn lists_disjoint_pages_of_all_the_questions() {
+    let store = Store::new();
+    *store.questions.write().await = (1..=12)
+      .map(|id| {
+        let question = Question {
+          id: QuestionId(id.to_string()),
+          title: format!("Question {}", id),
+          content: "Content".to_string(),
+          tags: None,
+        };
+        (question.id.clone(), question)
+      })
+      .collect();
+
+    let mut ids: Vec<String> = Vec::new();
+    for (start, end) in [(1, 5), (6, 10), (11, 15)] {
+      let params = HashMap::from([
+        ("start".to_string(), start.to_string()),
+        ("end".to_string(), end.to_string()),
+      ]);
+      let reply = get_questions(params, store.clone()).await.unwrap();
+      let body = to_bytes(reply.into_response().into_body()).await.unwrap();
+      let page: Vec<Question> = serde_json::from_slice(&body).unwrap();
+      ids.extend(page.into_iter().map(|question| question.id.0));
+    }
+
+    let expected: Vec<String> = (1..=12).map(|id| id.to_string()).collect();
+    assert_eq!(ids, expected);
+  }
+
+  /// Gets the questions of the page between the given positions, counted from 1.
+  ///
+  /// # Arguments
+  ///
+  /// * `store`: Data store that contains all the questions.
+  /// * `start`: Position of the first question of the page.
+  /// * `end`: Position of the last question of the page.
+  async fn page(
+    store: Store,
+    start: usize,
+    end: usize,
+  ) -> Result<warp::reply::Response, warp::Rejection> {
+    let params = HashMap::from([
+      ("start".to_string(), start.to_string()),
+      ("end".to_string(), end.to_string()),
+    ]);
+    get_questions(params, store).await.map(Reply::into_response)
+  }
+
+  #[tokio::test]
+  async fn replies_an_empty_page_at_the_end() {
+    let store = Store::new();
+    let len = store.questions.read().await.len();
+
+    let reply = page(store, len + 1, len + 5).await.unwrap();
+    let body = to_bytes(reply.into_body()).await.unwrap();
+    let questions: Vec<Question> = serde_json::from_slice(&body).unwrap();
+    assert!(questions.is_empty());
+  }
+
+  #[tokio::test]
+  async fn replies_416_to_a_start_past_the_end() {
+    let store = Store::new();
+    let len = store.questions.read().await.len();
+
+    let rej = page(store, len + 2, len + 5).await.err().unwrap();
+    let reply = handle_errors::errors::return_error(rej).await.unwrap().into_response();
+    assert_eq!(reply.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+  }
+
+  #[tokio::test]
+  async fn encodes_the_id_in_the_location_of_a_new_question() {
+    let question = Question {
+      id: QuestionId("a b/c".to_string()),
+      title: "Title".to_string(),
+      content: "Content".to_string(),
+      tags: None,
+    };
+
+    let reply = add_question(Store::new(), question).await.unwrap().into_response();
+    assert_eq!(reply.status(), StatusCode::CREATED);
+    assert_eq!(reply.headers()[LOCATION], "/questions/a%20b%2Fc");
+  }
+}
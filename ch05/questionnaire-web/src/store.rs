@@ -1,4 +1,7 @@
-use std::{collections::HashMap, sync::Arc};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
 use tokio::sync::RwLock;
 
 use crate::types::answer::{Answer, AnswerId};
@@ -7,10 +10,13 @@ use crate::types::question::{Question, QuestionId};
 /// Represents the data store for the application.
 #[derive(Clone)]
 pub struct Store {
-  /// Collection of questions in the data store.
-  pub questions: Arc<RwLock<HashMap<QuestionId, Question>>>,
+  /// Collection of questions in the data store, ordered by their ID so the pages of
+  /// the listing are the same on every request.
+  pub questions: Arc<RwLock<BTreeMap<QuestionId, Question>>>,
   /// Collection of answers in the data store.
   pub answers: Arc<RwLock<HashMap<AnswerId, Answer>>>,
+  /// Last identifier (ID) given to an answer.
+  answer_sequence: Arc<AtomicUsize>,
 } // end struct Store
 
 impl Store {
@@ -19,11 +25,18 @@ impl Store {
     Self {
       questions: Arc::new(RwLock::new(Self::init())),
       answers: Arc::new(RwLock::new(HashMap::new())),
+      answer_sequence: Arc::new(AtomicUsize::new(0)),
     }
   } // end fn new()
 
+  /// Generates a new unique identifier (ID) for an answer.
+  pub fn next_answer_id(&self) -> AnswerId {
+    let id = self.answer_sequence.fetch_add(1, Ordering::SeqCst) + 1;
+    AnswerId(id.to_string())
+  } // end fn next_answer_id()
+
   /// Initializes the data store with available data.
-  fn init() -> HashMap<QuestionId, Question> {
+  fn init() -> BTreeMap<QuestionId, Question> {
     let file: &str = include_str!("../questions.json");
     serde_json::from_str(file).expect("cannot read the questions.json file.")
   } // end fn init()
@@ -1,13 +1,10 @@
-use std::collections::HashMap;
-
 use warp::hyper::StatusCode;
 
+use handle_errors::errors::QError;
+
 use crate::{
   store::Store,
-  types::{
-    answer::{Answer, AnswerId},
-    question::QuestionId,
-  },
+  types::answer::{Answer, NewAnswer},
 };
 
 /// Adds a new answer with the given parameters to a data store.
@@ -15,22 +12,59 @@ use crate::{
 /// # Arguments
 ///
 /// * `store`: Data store for where answer will be saved.
-/// * `params`: Set of parameters with data for adding a new answer.
+/// * `answer`: Form fields of the new answer.
 pub async fn add_answer(
   store: Store,
-  params: HashMap<String, String>,
+  answer: NewAnswer,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+  let (content, question_id) = match answer.into_fields() {
+    Ok(fields) => fields,
+    Err(err) => return Err(warp::reject::custom(err)),
+  };
+
+  // Keep the questions locked while inserting, so the question cannot be deleted
+  // before the answer is added.
+  let questions = store.questions.read().await;
+  if !questions.contains_key(&question_id) {
+    return Err(warp::reject::custom(QError::QuestionNotFound));
+  }
+
   let answer = Answer {
-    id: AnswerId("1".to_string()),
-    content: params.get("content").unwrap().to_string(),
-    question_id: QuestionId(params.get("question_id").unwrap().to_string()),
+    id: store.next_answer_id(),
+    content,
+    question_id,
   };
 
   store
     .answers
     .write()
     .await
-    .insert(answer.id.clone(), answer);
+    .insert(answer.id.clone(), answer.clone());
 
-  Ok(warp::reply::with_status("Answer added", StatusCode::OK))
+  Ok(warp::reply::with_status(
+    warp::reply::json(&answer),
+    StatusCode::CREATED,
+  ))
 } // end fn add_answer()
+
+#[cfg(test)]
+mod tests {
+  use warp::hyper::body::to_bytes;
+  use warp::Reply;
+
+  use super::*;
+
+  #[tokio::test]
+  async fn replies_400_listing_the_missing_fields() {
+    let answer = NewAnswer {
+      content: None,
+      question_id: None,
+    };
+
+    let rej = add_answer(Store::new(), answer).await.err().unwrap();
+    let reply = handle_errors::errors::return_error(rej).await.unwrap().into_response();
+    assert_eq!(reply.status(), StatusCode::BAD_REQUEST);
+    let body = to_bytes(reply.into_body()).await.unwrap();
+    assert_eq!(body, "Missing fields: content, question_id.");
+  }
+}
@@ -13,6 +13,31 @@ impl std::fmt::Display for QuestionId {
   }
 }
 
+impl PartialOrd for QuestionId {
+  fn partial_cmp(
+    &self,
+    other: &Self,
+  ) -> Option<std::cmp::Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+/// Orders the IDs by their length first and then by their text, so numeric IDs are
+/// ordered by their value, e.g. "2" before "10", as long as they have no leading
+/// zeros.
+impl Ord for QuestionId {
+  fn cmp(
+    &self,
+    other: &Self,
+  ) -> std::cmp::Ordering {
+    self
+      .0
+      .len()
+      .cmp(&other.0.len())
+      .then_with(|| self.0.cmp(&other.0))
+  }
+}
+
 impl std::str::FromStr for QuestionId {
   type Err = std::io::Error;
 
@@ -52,3 +77,21 @@ impl std::fmt::Display for Question {
     )
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn orders_numeric_ids_by_value() {
+    let mut ids: Vec<QuestionId> = ["10", "2", "1", "11", "9"]
+      .iter()
+      .map(|id| QuestionId(id.to_string()))
+      .collect();
+    ids.sort();
+
+    let ids: Vec<&str> = ids.iter().map(|id| id.0.as_str()).collect();
+    assert_eq!(ids, vec!["1", "2", "9", "10", "11"]);
+    assert!(QuestionId("2".to_string()) < QuestionId("10".to_string()));
+  }
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use handle_errors::errors::QError;
+
 use crate::types::question::QuestionId;
 
 /// Represents the unique identifier (ID) of an answer.
@@ -16,3 +18,32 @@ pub struct Answer {
   /// Unique identifier (ID) of the question this answer belongs to.
   pub question_id: QuestionId,
 } // end struct Answer
+
+/// Represents the form fields of a new answer, which are checked before adding it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewAnswer {
+  /// Text contents of the answer.
+  pub content: Option<String>,
+  /// Unique identifier (ID) of the question this answer belongs to.
+  pub question_id: Option<String>,
+} // end struct NewAnswer
+
+impl NewAnswer {
+  /// Gets the content and the question ID of the answer, or a `MissingFields` error
+  /// listing the fields that were not given.
+  pub fn into_fields(self) -> Result<(String, QuestionId), QError> {
+    match (self.content, self.question_id) {
+      (Some(content), Some(question_id)) => Ok((content, QuestionId(question_id))),
+      (content, question_id) => {
+        let mut missing = Vec::new();
+        if content.is_none() {
+          missing.push("content".to_string());
+        }
+        if question_id.is_none() {
+          missing.push("question_id".to_string());
+        }
+        Err(QError::MissingFields(missing))
+      }
+    }
+  } // end fn into_fields()
+}
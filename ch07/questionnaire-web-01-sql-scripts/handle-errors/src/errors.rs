@@ -2,7 +2,7 @@ use tracing::{event, instrument, Level};
 use warp::filters::body::BodyDeserializeError;
 use warp::filters::cors::CorsForbidden;
 use warp::hyper::StatusCode;
-use warp::reject::Reject;
+use warp::reject::{LengthRequired, Reject};
 use warp::{Rejection, Reply};
 
 /// Represents an error for processing query parameters.
@@ -12,6 +12,11 @@ pub enum QError {
   ParseError(std::num::ParseIntError),
   /// A kind of error for missing parameters.
   MissingParameters,
+  /// A kind of error for parameters with an invalid value.
+  InvalidParameter(String),
+  /// A kind of error for request bodies larger than the limit, with the largest size
+  /// allowed in bytes.
+  PayloadTooLarge(u64),
   /// A kind of error for questions not found.
   QuestionNotFound,
   // A kind of error for database errors.
@@ -28,6 +33,12 @@ impl std::fmt::Display for QError {
         write!(f, "Cannot parse the parameter: {}", err)
       }
       QError::MissingParameters => write!(f, "Missing parameter."),
+      QError::InvalidParameter(message) => write!(f, "Invalid parameter: {}", message),
+      QError::PayloadTooLarge(max_bytes) => write!(
+        f,
+        "Payload too large: the body must have at most {} bytes.",
+        max_bytes
+      ),
       QError::QuestionNotFound => write!(f, "Question not found."),
       QError::DatabaseQueryError(err) => write!(f, "Database error. {}", err),
     }
@@ -58,6 +69,14 @@ pub async fn return_error(rej: Rejection) -> Result<impl Reply, Rejection> {
         error.to_string(),
         StatusCode::BAD_REQUEST,
       )),
+      QError::InvalidParameter(_) => Ok(warp::reply::with_status(
+        error.to_string(),
+        StatusCode::BAD_REQUEST,
+      )),
+      QError::PayloadTooLarge(_) => Ok(warp::reply::with_status(
+        error.to_string(),
+        StatusCode::PAYLOAD_TOO_LARGE,
+      )),
       QError::DatabaseQueryError(err) => {
         event!(Level::ERROR, "{}", format!("Database query error. {}", error));
         Ok(warp::reply::with_status(
@@ -89,6 +108,13 @@ pub async fn return_error(rej: Rejection) -> Result<impl Reply, Rejection> {
       StatusCode::UNPROCESSABLE_ENTITY,
     ))
   }
+  // Handle bodies sent without their length, which cannot be limited
+  else if let Some(error) = rej.find::<LengthRequired>() {
+    Ok(warp::reply::with_status(
+      error.to_string(),
+      StatusCode::LENGTH_REQUIRED,
+    ))
+  }
   // At this point, the possible rejection is that a path not found
   else {
     Ok(warp::reply::with_status(
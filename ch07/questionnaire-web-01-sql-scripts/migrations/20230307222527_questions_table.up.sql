@@ -1,9 +1,9 @@
-
-
+-- Add up migration script here
 CREATE TABLE questions (
   id serial PRIMARY KEY,
   title varchar (255) NOT NULL,
   content TEXT NOT NULL,
   tags TEXT [],
   created_on TIMESTAMP NOT NULL DEFAULT now()
-);
\ No newline at end of file
+);
+
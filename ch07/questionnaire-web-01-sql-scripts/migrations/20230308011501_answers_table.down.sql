@@ -0,0 +1,2 @@
+/* Drop tables if they already exist */
+DROP TABLE IF EXISTS answers;
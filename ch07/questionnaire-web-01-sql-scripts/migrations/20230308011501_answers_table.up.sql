@@ -1,4 +1,3 @@
-
 CREATE TABLE answers (
   id serial PRIMARY KEY,
   content TEXT NOT NULL,
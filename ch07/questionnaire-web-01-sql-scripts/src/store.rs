@@ -1,10 +1,8 @@
-use sqlx::postgres::{PgPoolOptions, PgRow};
+use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
-// This trait allows working with row results
-use sqlx::Row;
 
-use crate::types::answer::{Answer, AnswerId, NewAnswer};
-use crate::types::question::{NewQuestion, Question, QuestionId};
+use crate::types::answer::{Answer, NewAnswer};
+use crate::types::question::{NewQuestion, Question};
 
 use handle_errors::errors::QError;
 
@@ -18,10 +16,18 @@ pub struct Store {
 impl Store {
   /// Creates a new data store.
   ///
+  /// The migrations embedded from the `migrations` directory are run on the database
+  /// unless `run_migrations` is `false`, e.g. in production where they are applied
+  /// separately.
+  ///
   /// # Arguments
   ///
   /// * `db_url`: URL of the database server.
-  pub async fn new(db_url: &str) -> Self {
+  /// * `run_migrations`: Whether to run the pending migrations on the database.
+  pub async fn new(
+    db_url: &str,
+    run_migrations: bool,
+  ) -> Self {
     let db_pool = match PgPoolOptions::new()
       .max_connections(5)
       .connect(db_url)
@@ -31,6 +37,15 @@ impl Store {
       Err(err) => panic!("Database connection failed. {}", err),
     };
 
+    if run_migrations {
+      if let Err(err) = sqlx::migrate!().run(&db_pool).await {
+        panic!("Database migration failed. {}", err);
+      }
+      tracing::event!(tracing::Level::INFO, "Database migrations are up to date");
+    } else {
+      tracing::event!(tracing::Level::INFO, "Database migrations are disabled");
+    }
+
     Self {
       connection: db_pool,
     }
@@ -47,17 +62,12 @@ impl Store {
     offset: i32,
     limit: Option<i32>,
   ) -> Result<Vec<Question>, QError> {
-    let db_query_set = sqlx::query("SELECT * from questions LIMIT $1 OFFSET $2")
-      .bind(limit)
-      .bind(offset)
-      .map(|row: PgRow| Question {
-        id: QuestionId(row.get("id")),
-        title: row.get("title"),
-        content: row.get("content"),
-        tags: row.get("tags"),
-      })
-      .fetch_all(&self.connection)
-      .await;
+    let db_query_set =
+      sqlx::query_as::<_, Question>("SELECT * from questions LIMIT $1 OFFSET $2")
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.connection)
+        .await;
 
     match db_query_set {
       Ok(questions) => Ok(questions),
@@ -77,7 +87,7 @@ impl Store {
     &self,
     question: NewQuestion,
   ) -> Result<Vec<Question>, QError> {
-    match sqlx::query(
+    match sqlx::query_as::<_, Question>(
       r#"INSERT INTO questions (title, content, tags) 
       VALUES ($1, $2, $3) 
       RETURNING id, title, content, tags"#,
@@ -85,12 +95,6 @@ impl Store {
     .bind(question.title)
     .bind(question.content)
     .bind(question.tags)
-    .map(|row: PgRow| Question {
-      id: QuestionId(row.get("id")),
-      title: row.get("title"),
-      content: row.get("content"),
-      tags: row.get("tags"),
-    })
     .fetch_all(&self.connection)
     .await
     {
@@ -113,7 +117,7 @@ impl Store {
     question: Question,
     id: i32,
   ) -> Result<Vec<Question>, QError> {
-    match sqlx::query(
+    match sqlx::query_as::<_, Question>(
       r#"UPDATE questions 
       SET title = $1, content = $2, tags = $3 
       WHERE id = $4 
@@ -123,12 +127,6 @@ impl Store {
     .bind(question.content)
     .bind(question.tags)
     .bind(id)
-    .map(|row: PgRow| Question {
-      id: QuestionId(row.get("id")),
-      title: row.get("content"),
-      content: row.get("content"),
-      tags: row.get("tags"),
-    })
     .fetch_all(&self.connection)
     .await
     {
@@ -167,6 +165,9 @@ impl Store {
 
   /// Adds a new answer to the datastore.
   ///
+  /// The question is locked while the answer is added, so it cannot be deleted in
+  /// between.
+  ///
   /// # Arguments
   ///
   /// * `answer`: Answer to be added.
@@ -174,21 +175,51 @@ impl Store {
     &self,
     answer: NewAnswer,
   ) -> Result<Vec<Answer>, QError> {
+    let mut transaction = match self.connection.begin().await {
+      Ok(transaction) => transaction,
+      Err(err) => {
+        tracing::event!(tracing::Level::ERROR, "{:?}", err);
+        return Err(QError::DatabaseQueryError(err));
+      }
+    };
+
     match sqlx::query(
-      r#"INSERT INTO answers (content, question_id) 
-      VALUES ($1, $2)"#,
+      r#"SELECT id 
+      FROM questions 
+      WHERE id = $1 
+      FOR SHARE"#,
+    )
+    .bind(&answer.question_id)
+    .fetch_optional(&mut transaction)
+    .await
+    {
+      Ok(Some(_)) => (),
+      Ok(None) => return Err(QError::QuestionNotFound),
+      Err(err) => {
+        tracing::event!(tracing::Level::ERROR, "{:?}", err);
+        return Err(QError::DatabaseQueryError(err));
+      }
+    }
+
+    let answers = match sqlx::query_as::<_, Answer>(
+      r#"INSERT INTO answers (content, corresponding_question) 
+      VALUES ($1, $2) 
+      RETURNING id, content, corresponding_question"#,
     )
     .bind(answer.content)
-    .bind(answer.question_id.0)
-    .map(|row: PgRow| Answer {
-      id: AnswerId(row.get("id")),
-      content: row.get("content"),
-      question_id: QuestionId(row.get("question_id")),
-    })
-    .fetch_all(&self.connection)
+    .bind(answer.question_id)
+    .fetch_all(&mut transaction)
     .await
     {
-      Ok(answers) => Ok(answers),
+      Ok(answers) => answers,
+      Err(err) => {
+        tracing::event!(tracing::Level::ERROR, "{:?}", err);
+        return Err(QError::DatabaseQueryError(err));
+      }
+    };
+
+    match transaction.commit().await {
+      Ok(()) => Ok(answers),
       Err(err) => {
         tracing::event!(tracing::Level::ERROR, "{:?}", err);
         Err(QError::DatabaseQueryError(err))
@@ -1,19 +1,41 @@
 use serde::{Deserialize, Serialize};
+use sqlx::error::BoxDynError;
+use sqlx::postgres::{PgTypeInfo, PgValueRef};
+use sqlx::{Decode, Postgres, Type};
 
 use crate::types::question::QuestionId;
 
 /// Represents the unique identifier (ID) of an answer.
+///
+/// It is stored as a plain integer.
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
 pub struct AnswerId(pub i32);
 
+impl Type<Postgres> for AnswerId {
+  fn type_info() -> PgTypeInfo {
+    <i32 as Type<Postgres>>::type_info()
+  }
+
+  fn compatible(ty: &PgTypeInfo) -> bool {
+    <i32 as Type<Postgres>>::compatible(ty)
+  }
+}
+
+impl<'r> Decode<'r, Postgres> for AnswerId {
+  fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+    Ok(AnswerId(<i32 as Decode<Postgres>>::decode(value)?))
+  }
+}
+
 /// Represents an answer to a given question.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Answer {
   /// Unique identifier (ID) of the answer.
   pub id: AnswerId,
   /// Text contents of the answer.
   pub content: String,
   /// Unique identifier (ID) of the question this answer belongs to.
+  #[sqlx(rename = "corresponding_question")]
   pub question_id: QuestionId,
 } // end struct Answer
 
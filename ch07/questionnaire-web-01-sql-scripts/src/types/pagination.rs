@@ -13,6 +13,9 @@ pub struct Pagination {
 
 /// Gets a pagination object from the given set of parameters.
 ///
+/// The offset must be at least 0 and the limit at least 1, so invalid values are
+/// rejected before they reach the database.
+///
 /// # Arguments
 ///
 /// * `params`: Parameters to limit the set of results to retrieve.
@@ -30,16 +33,8 @@ pub struct Pagination {
 /// ```
 pub fn extract_pagination(params: HashMap<String, String>) -> Result<Pagination, QError> {
   if params.contains_key("offset") && params.contains_key("limit") {
-    let offset_value: i32 = params
-      .get("offset")
-      .unwrap()
-      .parse::<i32>()
-      .map_err(QError::ParseError)?;
-    let limit_value: i32 = params
-      .get("limit")
-      .unwrap()
-      .parse::<i32>()
-      .map_err(QError::ParseError)?;
+    let offset_value: i32 = parse_parameter(&params, "offset", 0)?;
+    let limit_value: i32 = parse_parameter(&params, "limit", 1)?;
 
     let pagination = Pagination {
       offset: offset_value,
@@ -51,3 +46,31 @@ pub fn extract_pagination(params: HashMap<String, String>) -> Result<Pagination,
 
   Err(QError::MissingParameters)
 } // end fn extract_pagination()
+
+/// Gets the value of a whole number parameter, which must be at least the given
+/// minimum.
+///
+/// # Arguments
+///
+/// * `params`: Parameters to limit the set of results to retrieve.
+/// * `name`: Name of the parameter.
+/// * `min`: Smallest value allowed.
+fn parse_parameter(
+  params: &HashMap<String, String>,
+  name: &str,
+  min: i32,
+) -> Result<i32, QError> {
+  let value = params.get(name).ok_or(QError::MissingParameters)?;
+  let number: i32 = value.parse::<i32>().map_err(|_| {
+    let message = format!("{} must be a whole number, found '{}'", name, value);
+    QError::InvalidParameter(message)
+  })?;
+
+  match number >= min {
+    true => Ok(number),
+    false => Err(QError::InvalidParameter(format!(
+      "{} must be at least {}, found {}",
+      name, min, number
+    ))),
+  }
+} // end fn parse_parameter()
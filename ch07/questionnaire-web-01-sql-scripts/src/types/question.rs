@@ -1,6 +1,11 @@
 use serde::{Deserialize, Serialize};
+use sqlx::error::BoxDynError;
+use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef};
+use sqlx::{Decode, Encode, Postgres, Type};
 
 /// Represents a valid identifier (ID) for a question.
+///
+/// It is stored as a plain integer.
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
 pub struct QuestionId(pub i32);
 
@@ -33,8 +38,36 @@ impl std::str::FromStr for QuestionId {
   }
 }
 
+impl Type<Postgres> for QuestionId {
+  fn type_info() -> PgTypeInfo {
+    <i32 as Type<Postgres>>::type_info()
+  }
+
+  fn compatible(ty: &PgTypeInfo) -> bool {
+    <i32 as Type<Postgres>>::compatible(ty)
+  }
+}
+
+impl<'q> Encode<'q, Postgres> for QuestionId {
+  fn encode_by_ref(
+    &self,
+    buf: &mut PgArgumentBuffer,
+  ) -> sqlx::encode::IsNull {
+    <i32 as Encode<Postgres>>::encode_by_ref(&self.0, buf)
+  }
+}
+
+impl<'r> Decode<'r, Postgres> for QuestionId {
+  fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+    Ok(QuestionId(<i32 as Decode<Postgres>>::decode(value)?))
+  }
+}
+
 /// Represents a question posted in the system.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+///
+/// Rows are mapped to questions by column name, so the queries must return the `id`,
+/// `title`, `content`, and `tags` columns.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Question {
   /// Identifier of the question.
   pub id: QuestionId,
@@ -3,9 +3,17 @@ CREATE TABLE answers (
   id serial PRIMARY KEY,
   content TEXT NOT NULL,
   created_on TIMESTAMP NOT NULL DEFAULT now(),
-  corresponding_question integer NOT NULL
+  /* Replaced by question_id, only written while SCHEMA_COMPAT_MODE is on */
+  corresponding_question integer NULL,
+  question_id integer NULL
 );
 
 ALTER TABLE answers 
   ADD CONSTRAINT fk_corresponding_question FOREIGN KEY (corresponding_question)
-  REFERENCES questions(id);
\ No newline at end of file
+  REFERENCES questions(id);
+
+ALTER TABLE answers 
+  ADD CONSTRAINT fk_answers_question_id FOREIGN KEY (question_id)
+  REFERENCES questions(id);
+
+CREATE INDEX idx_answers_question_id ON answers (question_id);
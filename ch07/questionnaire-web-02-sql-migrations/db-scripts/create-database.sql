@@ -1,12 +1,26 @@
 BEGIN;
 
 /* Drop tables if they already exist */
+DROP TABLE IF EXISTS response_choices;
+DROP TABLE IF EXISTS responses;
+DROP TABLE IF EXISTS survey_drafts;
+DROP TABLE IF EXISTS survey_invitations;
+DROP TABLE IF EXISTS survey_submissions;
+DROP TABLE IF EXISTS survey_question_rules;
+DROP TABLE IF EXISTS survey_questions;
+DROP TABLE IF EXISTS surveys;
+DROP TABLE IF EXISTS question_options;
+DROP TABLE IF EXISTS question_tags;
+DROP TABLE IF EXISTS tags;
 DROP TABLE IF EXISTS answers;
 DROP TABLE IF EXISTS questions;
 
 /* Create tables */
 \i questions.sql;
 \i answers.sql;
+\i tags.sql;
+\i surveys.sql;
+\i responses.sql;
 
 
 
@@ -4,6 +4,35 @@ CREATE TABLE questions (
   id serial PRIMARY KEY,
   title varchar (255) NOT NULL,
   content TEXT NOT NULL,
-  tags TEXT [],
-  created_on TIMESTAMP NOT NULL DEFAULT now()
-);
\ No newline at end of file
+  status varchar (16) NOT NULL DEFAULT 'open',
+  kind varchar (16) NOT NULL DEFAULT 'text',
+  rating_max integer NULL,
+  created_on TIMESTAMP NOT NULL DEFAULT now(),
+  deleted_at TIMESTAMP NULL,
+  version integer NOT NULL DEFAULT 1,
+  search_vector tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', coalesce(title, '')), 'A') || 
+    setweight(to_tsvector('english', coalesce(content, '')), 'B')
+  ) STORED
+);
+
+ALTER TABLE questions 
+  ADD CONSTRAINT chk_questions_status CHECK (status IN ('open', 'closed', 'archived'));
+
+ALTER TABLE questions 
+  ADD CONSTRAINT chk_questions_kind 
+  CHECK (kind IN ('text', 'single_choice', 'multiple_choice', 'rating'));
+
+/* Only rating questions have a scale, which goes from 1 to rating_max */
+ALTER TABLE questions 
+  ADD CONSTRAINT chk_questions_rating_max 
+  CHECK ((kind = 'rating') = (rating_max IS NOT NULL) AND rating_max BETWEEN 2 AND 10);
+
+CREATE INDEX idx_questions_search_vector ON questions USING GIN (search_vector);
+
+CREATE INDEX idx_questions_deleted_at ON questions (deleted_at) 
+  WHERE deleted_at IS NOT NULL;
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX idx_questions_title_trgm ON questions USING GIN (title gin_trgm_ops);
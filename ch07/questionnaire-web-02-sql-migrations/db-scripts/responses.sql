@@ -0,0 +1,29 @@
+CREATE TABLE question_options (
+  id serial PRIMARY KEY,
+  question_id integer NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+  position integer NOT NULL,
+  label varchar (255) NOT NULL,
+  /* Points the option is worth in quiz surveys, the options worth points being the 
+     correct ones */
+  points integer NOT NULL DEFAULT 0,
+  UNIQUE (question_id, position)
+);
+
+CREATE TABLE responses (
+  id serial PRIMARY KEY,
+  question_id integer NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+  rating integer NULL,
+  submission_id integer NULL REFERENCES survey_submissions(id) ON DELETE CASCADE,
+  created_on TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_responses_question_id ON responses (question_id);
+CREATE INDEX idx_responses_submission_id ON responses (submission_id);
+
+CREATE TABLE response_choices (
+  response_id integer NOT NULL REFERENCES responses(id) ON DELETE CASCADE,
+  option_id integer NOT NULL REFERENCES question_options(id) ON DELETE CASCADE,
+  PRIMARY KEY (response_id, option_id)
+);
+
+CREATE INDEX idx_response_choices_option_id ON response_choices (option_id);
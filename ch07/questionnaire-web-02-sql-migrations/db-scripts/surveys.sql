@@ -0,0 +1,80 @@
+CREATE TABLE surveys (
+  id serial PRIMARY KEY,
+  title varchar (255) NOT NULL,
+  opens_on TIMESTAMP NULL,
+  closes_on TIMESTAMP NULL,
+  invitation_only boolean NOT NULL DEFAULT false,
+  quiz boolean NOT NULL DEFAULT false,
+  /* Origins of the sites allowed to embed the survey widget */
+  embed_origins varchar (255) [] NOT NULL DEFAULT '{}',
+  /* A survey is closed once it reaches its maximum amount of submissions, or by the 
+     scheduler once its closing date passes */
+  max_submissions integer NULL,
+  submission_count integer NOT NULL DEFAULT 0,
+  closed_on TIMESTAMP NULL,
+  created_on TIMESTAMP NOT NULL DEFAULT now(),
+  CONSTRAINT chk_surveys_dates CHECK (closes_on > opens_on),
+  CONSTRAINT chk_surveys_max_submissions CHECK (max_submissions > 0)
+);
+
+CREATE TABLE survey_questions (
+  survey_id integer NOT NULL REFERENCES surveys(id) ON DELETE CASCADE,
+  question_id integer NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+  position integer NOT NULL,
+  required boolean NOT NULL DEFAULT true,
+  PRIMARY KEY (survey_id, question_id),
+  UNIQUE (survey_id, position)
+);
+
+CREATE INDEX idx_survey_questions_question_id ON survey_questions (question_id);
+
+/* A submission groups the responses given by a respondent to a survey */
+CREATE TABLE survey_submissions (
+  id serial PRIMARY KEY,
+  survey_id integer NOT NULL REFERENCES surveys(id) ON DELETE CASCADE,
+  /* Score of the submissions to quiz surveys, computed when they are submitted */
+  score integer NULL,
+  created_on TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_survey_submissions_survey_id ON survey_submissions (survey_id);
+
+/* Only a hash of each token is kept, and nothing links an invitation to the 
+   submission it was used for, so the responses stay anonymous */
+CREATE TABLE survey_invitations (
+  token_hash char (64) PRIMARY KEY,
+  survey_id integer NOT NULL REFERENCES surveys(id) ON DELETE CASCADE,
+  used boolean NOT NULL DEFAULT false
+);
+
+CREATE INDEX idx_survey_invitations_survey_id ON survey_invitations (survey_id);
+
+/* A rule shows a question of a survey only when the response to an earlier 
+   question of the same survey includes any of the given choices or ratings */
+CREATE TABLE survey_question_rules (
+  survey_id integer NOT NULL,
+  question_id integer NOT NULL,
+  depends_on integer NOT NULL,
+  any_of integer [] NOT NULL,
+  PRIMARY KEY (survey_id, question_id),
+  FOREIGN KEY (survey_id, question_id) 
+    REFERENCES survey_questions(survey_id, question_id) ON DELETE CASCADE,
+  FOREIGN KEY (survey_id, depends_on) 
+    REFERENCES survey_questions(survey_id, question_id) ON DELETE CASCADE
+);
+
+/* A draft keeps the responses saved so far by a respondent, to resume later. Only a 
+   hash of its key is kept, like for the invitations. It expires after some time 
+   without being saved, and is then purged */
+CREATE TABLE survey_drafts (
+  key_hash char (64) PRIMARY KEY,
+  survey_id integer NOT NULL REFERENCES surveys(id) ON DELETE CASCADE,
+  responses jsonb NOT NULL,
+  created_on TIMESTAMP NOT NULL DEFAULT now(),
+  updated_on TIMESTAMP NOT NULL DEFAULT now(),
+  expires_on TIMESTAMP NOT NULL DEFAULT now() + interval '30 days'
+);
+
+CREATE INDEX idx_survey_drafts_survey_id ON survey_drafts (survey_id);
+
+CREATE INDEX idx_survey_drafts_expires_on ON survey_drafts (expires_on);
@@ -0,0 +1,13 @@
+
+CREATE TABLE tags (
+  id serial PRIMARY KEY,
+  name varchar (64) NOT NULL UNIQUE
+);
+
+CREATE TABLE question_tags (
+  question_id integer NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+  tag_id integer NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+  PRIMARY KEY (question_id, tag_id)
+);
+
+CREATE INDEX idx_question_tags_tag_id ON question_tags (tag_id);
@@ -1,99 +1,691 @@
+use std::convert::Infallible;
+
+use serde::Serialize;
+use thiserror::Error;
 use tracing::{event, instrument, Level};
+use uuid::Uuid;
 use warp::filters::body::BodyDeserializeError;
 use warp::filters::cors::CorsForbidden;
+use warp::filters::path::FullPath;
+use warp::http::header::{HeaderMap, HeaderValue, ACCEPT, CONTENT_TYPE, RETRY_AFTER};
 use warp::hyper::StatusCode;
-use warp::reject::Reject;
-use warp::{Rejection, Reply};
+use warp::reject::{LengthRequired, Reject, UnsupportedMediaType};
+use warp::reply::Response;
+use warp::{Filter, Rejection, Reply};
+
+/// Media type of the problem details of RFC 7807.
+const PROBLEM_MEDIA_TYPE: &str = "application/problem+json";
+
+/// Represents an error for parsing the identifier (ID) of an entity.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum IdParseError {
+  /// No ID was given.
+  #[error("No ID provided.")]
+  Empty,
+  /// The ID is not an integer number.
+  #[error("ID '{0}' is not an integer.")]
+  NotANumber(String),
+  /// The ID is a negative number.
+  #[error("ID {0} is negative.")]
+  Negative(i32),
+  /// The ID is a number too large to be an ID.
+  #[error("ID {0} is out of range.")]
+  Overflow(String),
+} // end enum IdParseError
+
+/// Represents a problem with the response to one of the questions of a survey.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResponseError {
+  /// Unique identifier (ID) of the question.
+  pub question_id: i32,
+  /// Description of the problem.
+  pub message: String,
+} // end struct ResponseError
+
+impl std::fmt::Display for ResponseError {
+  fn fmt(
+    &self,
+    f: &mut std::fmt::Formatter<'_>,
+  ) -> std::fmt::Result {
+    write!(f, "question {}: {}", self.question_id, self.message)
+  }
+}
+
+/// Represents a problem with a query string parameter.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QueryError {
+  /// Name of the parameter, or `None` when the problem is not with a single one.
+  pub parameter: Option<String>,
+  /// Description of the problem.
+  pub message: String,
+} // end struct QueryError
+
+impl std::fmt::Display for QueryError {
+  fn fmt(
+    &self,
+    f: &mut std::fmt::Formatter<'_>,
+  ) -> std::fmt::Result {
+    match &self.parameter {
+      Some(parameter) => write!(f, "{}: {}", parameter, self.message),
+      None => write!(f, "{}", self.message),
+    }
+  }
+}
+
+/// Represents a problem with a field of a request body.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldError {
+  /// Name of the field, like `title`.
+  pub field: String,
+  /// Description of the problem.
+  pub message: String,
+} // end struct FieldError
+
+impl std::fmt::Display for FieldError {
+  fn fmt(
+    &self,
+    f: &mut std::fmt::Formatter<'_>,
+  ) -> std::fmt::Result {
+    write!(f, "{}: {}", self.field, self.message)
+  }
+}
+
+/// Represents an existing question similar to a new one, so it may be a duplicate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SimilarQuestion {
+  /// Unique identifier (ID) of the question.
+  pub id: i32,
+  /// Title of the question.
+  pub title: String,
+} // end struct SimilarQuestion
+
+impl std::fmt::Display for SimilarQuestion {
+  fn fmt(
+    &self,
+    f: &mut std::fmt::Formatter<'_>,
+  ) -> std::fmt::Result {
+    write!(f, "{} ({})", self.id, self.title)
+  }
+}
+
+/// Represents the fields of a question that an update changes, at some version.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, sqlx::FromRow)]
+pub struct QuestionFields {
+  /// Title of the question.
+  pub title: String,
+  /// Content of the question.
+  pub content: String,
+  /// Names of the tags of the question, in order.
+  pub tags: Vec<String>,
+} // end struct QuestionFields
+
+/// Represents the hint to merge an update based on an older version of a question
+/// with its current version, so clients can offer a three-way merge.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MergeHint {
+  /// Current version of the question.
+  pub current_version: i32,
+  /// Version the update was based on.
+  pub base_version: i32,
+  /// Fields at the version the update was based on, or `None` when they are no longer
+  /// known.
+  pub base: Option<QuestionFields>,
+  /// Fields at the current version.
+  pub theirs: QuestionFields,
+  /// Fields of the update.
+  pub ours: QuestionFields,
+  /// Fields with the changes of both sides. The conflicting fields have the current
+  /// values.
+  pub merged: QuestionFields,
+  /// Names of the fields both sides changed differently.
+  pub conflicts: Vec<String>,
+} // end struct MergeHint
+
+/// Represents machine-readable details of an error, which depend on its kind.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum ErrorDetails<'a> {
+  /// Problems with the query string, one per parameter.
+  Query(&'a [QueryError]),
+  /// Problems with the responses, one per question.
+  Responses(&'a [ResponseError]),
+  /// Problems with the fields of the request body, one per problem.
+  Fields(&'a [FieldError]),
+  /// Names of the fields missing from the request body.
+  Missing {
+    /// Names of the fields.
+    missing_fields: &'a [String],
+  },
+  /// IDs of the open surveys that use a question.
+  Surveys {
+    /// IDs of the surveys.
+    survey_ids: &'a [i32],
+  },
+  /// Page sizes allowed.
+  Pagination {
+    /// Smallest page size allowed.
+    min_limit: i32,
+    /// Largest page size allowed.
+    max_limit: i32,
+  },
+  /// Hint to merge an update with the current version of a question.
+  Merge(&'a MergeHint),
+  /// Existing questions that a new question may duplicate.
+  Duplicates {
+    /// Similar questions, from the most to the least similar.
+    duplicates: &'a [SimilarQuestion],
+  },
+  /// Seconds until more requests may be sent.
+  RetryAfter {
+    /// Seconds to wait.
+    retry_after_secs: u64,
+  },
+  /// Reference of an internal error, logged along with the error.
+  Reference {
+    /// Unique identifier (ID) the error is logged with.
+    error_id: String,
+  },
+} // end enum ErrorDetails
+
+/// Represents an error as replied in a JSON body.
+#[derive(Debug, Serialize)]
+pub struct ErrorBody<'a> {
+  /// Machine-readable code of the error, like `QUESTION_NOT_FOUND`.
+  pub code: &'a str,
+  /// Description of the error.
+  pub message: String,
+  /// Machine-readable details of the error, if its kind has any.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub details: Option<ErrorDetails<'a>>,
+} // end struct ErrorBody
+
+/// Represents the JSON body of every error reply, like
+/// `{"error": {"code": "QUESTION_NOT_FOUND", "message": "Question not found."}}`.
+#[derive(Debug, Serialize)]
+pub struct ErrorReply<'a> {
+  /// Error replied.
+  pub error: ErrorBody<'a>,
+} // end struct ErrorReply
+
+impl<'a> ErrorReply<'a> {
+  /// Creates the body of an error reply without details.
+  ///
+  /// # Arguments
+  ///
+  /// * `code`: Machine-readable code of the error.
+  /// * `message`: Description of the error.
+  pub fn new(
+    code: &'a str,
+    message: String,
+  ) -> Self {
+    ErrorReply {
+      error: ErrorBody {
+        code,
+        message,
+        details: None,
+      },
+    }
+  } // end fn new()
+}
+
+/// Represents an error as replied in an `application/problem+json` body, following
+/// RFC 7807, with the code and details of the error as extension members.
+#[derive(Debug, Serialize)]
+struct ProblemReply<'a> {
+  /// URI of the type of problem. `about:blank` means the title is the status phrase.
+  #[serde(rename = "type")]
+  problem_type: &'a str,
+  /// Short summary of the type of problem.
+  title: &'a str,
+  /// HTTP status code of the reply.
+  status: u16,
+  /// Description of this occurrence of the problem.
+  detail: String,
+  /// Path of the request with the problem, if known.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  instance: Option<&'a str>,
+  /// Machine-readable code of the error, like `QUESTION_NOT_FOUND`.
+  code: &'a str,
+  /// Machine-readable details of the error, if its kind has any.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  details: Option<ErrorDetails<'a>>,
+} // end struct ProblemReply
 
 /// Represents an error for processing query parameters.
-#[derive(Debug)]
+///
+/// New kinds of errors may be added, so the matches on it outside this crate need a
+/// wildcard arm.
+#[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum QError {
   /// An kind of error for parsing errors.
-  ParseError(std::num::ParseIntError),
+  #[error("Cannot parse the parameter: {0}")]
+  ParseError(#[from] std::num::ParseIntError),
+  /// A kind of error for identifiers (IDs) that cannot be parsed.
+  #[error("Invalid ID: {0}")]
+  InvalidId(#[from] IdParseError),
   /// A kind of error for missing parameters.
+  #[error("Missing parameter.")]
   MissingParameters,
+  /// A kind of error for parameters with an invalid value.
+  #[error("Invalid parameter: {0}")]
+  InvalidParameter(String),
+  /// A kind of error for query strings with unknown or malformed parameters, with the
+  /// problem of each parameter.
+  #[error("Invalid query: {}", join(.0, "; "))]
+  InvalidQuery(Vec<QueryError>),
+  /// A kind of error for request bodies with invalid fields, with the problem of each
+  /// field.
+  #[error("Invalid fields: {}", join(.0, "; "))]
+  InvalidFields(Vec<FieldError>),
+  /// A kind of error for request bodies without some required fields, with their names.
+  #[error("Missing fields: {}.", .0.join(", "))]
+  MissingFields(Vec<String>),
+  /// A kind of error for request bodies sent with another `Content-Type` than the
+  /// media type of the route, which is given.
+  #[error("Unsupported media type: send the body as {0}.")]
+  UnsupportedMediaType(String),
+  /// A kind of error for request bodies larger than the limit of their route, which is
+  /// given in bytes.
+  #[error("Payload too large: the body must be at most {0} bytes.")]
+  PayloadTooLarge(u64),
+  /// A kind of error for page sizes out of the allowed range, with the smallest and
+  /// the largest page sizes allowed.
+  #[error("Invalid pagination: limit must be from {0} to {1}.")]
+  InvalidPagination(i32, i32),
   /// A kind of error for questions not found.
+  #[error("Question not found.")]
   QuestionNotFound,
-  // A kind of error for database errors.
-  DatabaseQueryError(sqlx::error::Error),
+  /// A kind of error for questions that do not accept new answers.
+  #[error("Question is not open for answers.")]
+  QuestionClosed,
+  /// A kind of error for questions that cannot be deleted because surveys that are not
+  /// closed yet link them, with the IDs of those surveys.
+  #[error("Question is used by open surveys: {}.", join(.0, ", "))]
+  QuestionInUse(Vec<i32>),
+  /// A kind of error for new questions whose title is similar to the titles of existing
+  /// questions, which are given from the most to the least similar.
+  #[error(
+    "Possible duplicates: {}. Send force=true to add the question anyway.",
+    join(.0, ", ")
+  )]
+  PossibleDuplicates(Vec<SimilarQuestion>),
+  /// A kind of error for redirects of replaced questions not found.
+  #[error("Redirect not found.")]
+  RedirectNotFound,
+  /// A kind of error for tags not found.
+  #[error("Tag not found.")]
+  TagNotFound,
+  /// A kind of error for surveys not found.
+  #[error("Survey not found.")]
+  SurveyNotFound,
+  /// A kind of error for surveys that do not accept responses at the moment.
+  #[error("Survey is not open for responses.")]
+  SurveyClosed,
+  /// A kind of error for invitation tokens that do not exist or were already used.
+  #[error("Invitation token is not valid or was already used.")]
+  InvalidInvitation,
+  /// A kind of error for responses to a survey with problems in some questions.
+  #[error("Invalid responses: {}", join(.0, "; "))]
+  InvalidResponses(Vec<ResponseError>),
+  /// A kind of error for draft responses not found.
+  #[error("Draft not found.")]
+  DraftNotFound,
+  /// A kind of error for rows that would duplicate a unique value, with the name of
+  /// the constraint.
+  #[error("Conflict: a row already exists for {0}.")]
+  Conflict(String),
+  /// A kind of error for rows that reference rows that do not exist, with the name of
+  /// the constraint.
+  #[error("Invalid reference: the row referenced by {0} does not exist.")]
+  InvalidReference(String),
+  /// A kind of error for rows without a required value, with the name of the column.
+  #[error("Missing value for {0}.")]
+  MissingValue(String),
+  /// A kind of error for updates without the version of the row they are based on.
+  #[error("Missing version: send the version being updated in If-Match.")]
+  MissingVersion,
+  /// A kind of error for updates of questions based on an older version, with the hint
+  /// to merge the update with the current version.
+  #[error("Version mismatch: the current version is {}.", .0.current_version)]
+  VersionConflict(Box<MergeHint>),
+  /// A kind of error for database queries that take too long to reply.
+  #[error("Database did not reply in time.")]
+  DatabaseTimeout,
+  /// A kind of error for translations requested while no translator is set up.
+  #[error("Translations are not available.")]
+  TranslationUnavailable,
+  /// A kind of error for translations the translator failed to make.
+  #[error("Translation failed, try again later.")]
+  TranslationFailed,
+  /// A kind of error for clients that sent too many requests, with the seconds until
+  /// they may send more.
+  #[error("Too many requests, try again in {0} seconds.")]
+  TooManyRequests(u64),
+  /// A kind of error for requests that need a dependency that is down, with its name.
+  #[error("The {0} is unavailable.")]
+  DependencyUnavailable(String),
+  /// A kind of error for unexpected failures of the server, with their description,
+  /// which is only logged.
+  #[error("Internal error. {0}")]
+  Internal(String),
+  /// A kind of error for database errors.
+  #[error("Database error. {0}")]
+  DatabaseQueryError(#[from] sqlx::error::Error),
 } // end enum QError
 
-impl std::fmt::Display for QError {
-  fn fmt(
-    &self,
-    f: &mut std::fmt::Formatter<'_>,
-  ) -> std::fmt::Result {
-    match &*self {
-      QError::ParseError(ref err) => {
-        write!(f, "Cannot parse the parameter: {}", err)
+/// Joins the given items with a separator, for the messages of the errors.
+///
+/// # Arguments
+///
+/// * `items`: Items to join.
+/// * `separator`: Text between each pair of items.
+fn join<T: std::fmt::Display>(
+  items: &[T],
+  separator: &str,
+) -> String {
+  items
+    .iter()
+    .map(|item| item.to_string())
+    .collect::<Vec<_>>()
+    .join(separator)
+} // end fn join()
+
+impl QError {
+  /// Gets the machine-readable code of the error, like `QUESTION_NOT_FOUND`.
+  pub fn code(&self) -> &'static str {
+    match self {
+      QError::ParseError(_) => "PARSE_ERROR",
+      QError::InvalidId(_) => "INVALID_ID",
+      QError::MissingParameters => "MISSING_PARAMETERS",
+      QError::InvalidParameter(_) => "INVALID_PARAMETER",
+      QError::InvalidQuery(_) => "INVALID_QUERY",
+      QError::InvalidFields(_) => "INVALID_FIELDS",
+      QError::MissingFields(_) => "MISSING_FIELDS",
+      QError::UnsupportedMediaType(_) => "UNSUPPORTED_MEDIA_TYPE",
+      QError::PayloadTooLarge(_) => "PAYLOAD_TOO_LARGE",
+      QError::InvalidPagination(_, _) => "INVALID_PAGINATION",
+      QError::QuestionNotFound => "QUESTION_NOT_FOUND",
+      QError::QuestionClosed => "QUESTION_CLOSED",
+      QError::QuestionInUse(_) => "QUESTION_IN_USE",
+      QError::PossibleDuplicates(_) => "POSSIBLE_DUPLICATES",
+      QError::RedirectNotFound => "REDIRECT_NOT_FOUND",
+      QError::TagNotFound => "TAG_NOT_FOUND",
+      QError::SurveyNotFound => "SURVEY_NOT_FOUND",
+      QError::SurveyClosed => "SURVEY_CLOSED",
+      QError::InvalidInvitation => "INVALID_INVITATION",
+      QError::InvalidResponses(_) => "INVALID_RESPONSES",
+      QError::DraftNotFound => "DRAFT_NOT_FOUND",
+      QError::Conflict(_) => "CONFLICT",
+      QError::InvalidReference(_) => "INVALID_REFERENCE",
+      QError::MissingValue(_) => "MISSING_VALUE",
+      QError::MissingVersion => "MISSING_VERSION",
+      QError::VersionConflict(_) => "VERSION_MISMATCH",
+      QError::DatabaseTimeout => "DATABASE_TIMEOUT",
+      QError::TranslationUnavailable => "TRANSLATION_UNAVAILABLE",
+      QError::TranslationFailed => "TRANSLATION_FAILED",
+      QError::TooManyRequests(_) => "TOO_MANY_REQUESTS",
+      QError::DependencyUnavailable(_) => "DEPENDENCY_UNAVAILABLE",
+      QError::Internal(_) => "INTERNAL_ERROR",
+      QError::DatabaseQueryError(_) => "DATABASE_ERROR",
+    }
+  } // end fn code()
+
+  /// Gets the HTTP status code the error is replied with.
+  pub fn status(&self) -> StatusCode {
+    match self {
+      QError::ParseError(_)
+      | QError::InvalidId(_)
+      | QError::MissingParameters
+      | QError::InvalidParameter(_)
+      | QError::InvalidQuery(_)
+      | QError::MissingFields(_)
+      | QError::InvalidPagination(_, _) => StatusCode::BAD_REQUEST,
+      QError::QuestionNotFound
+      | QError::RedirectNotFound
+      | QError::TagNotFound
+      | QError::SurveyNotFound
+      | QError::DraftNotFound => StatusCode::NOT_FOUND,
+      QError::QuestionClosed
+      | QError::QuestionInUse(_)
+      | QError::PossibleDuplicates(_)
+      | QError::SurveyClosed
+      | QError::Conflict(_)
+      | QError::VersionConflict(_) => StatusCode::CONFLICT,
+      QError::InvalidResponses(_)
+      | QError::InvalidFields(_)
+      | QError::InvalidReference(_)
+      | QError::MissingValue(_) => StatusCode::UNPROCESSABLE_ENTITY,
+      QError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+      QError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+      QError::InvalidInvitation => StatusCode::FORBIDDEN,
+      QError::MissingVersion => StatusCode::PRECONDITION_REQUIRED,
+      QError::DatabaseTimeout | QError::DependencyUnavailable(_) => {
+        StatusCode::SERVICE_UNAVAILABLE
+      }
+      QError::TranslationUnavailable => StatusCode::NOT_IMPLEMENTED,
+      QError::TranslationFailed => StatusCode::BAD_GATEWAY,
+      QError::TooManyRequests(_) => StatusCode::TOO_MANY_REQUESTS,
+      QError::Internal(_) | QError::DatabaseQueryError(_) => {
+        StatusCode::INTERNAL_SERVER_ERROR
       }
-      QError::MissingParameters => write!(f, "Missing parameter."),
-      QError::QuestionNotFound => write!(f, "Question not found."),
-      QError::DatabaseQueryError(err) => write!(f, "Database error. {}", err),
     }
-  }
+  } // end fn status()
+
+  /// Gets the machine-readable details of the error, if its kind has any.
+  pub fn details(&self) -> Option<ErrorDetails<'_>> {
+    match self {
+      QError::InvalidQuery(errors) => Some(ErrorDetails::Query(errors)),
+      QError::InvalidResponses(errors) => Some(ErrorDetails::Responses(errors)),
+      QError::InvalidFields(errors) => Some(ErrorDetails::Fields(errors)),
+      QError::MissingFields(missing_fields) => {
+        Some(ErrorDetails::Missing { missing_fields })
+      }
+      QError::QuestionInUse(survey_ids) => Some(ErrorDetails::Surveys { survey_ids }),
+      QError::PossibleDuplicates(duplicates) => {
+        Some(ErrorDetails::Duplicates { duplicates })
+      }
+      QError::VersionConflict(hint) => Some(ErrorDetails::Merge(hint)),
+      QError::InvalidPagination(min, max) => Some(ErrorDetails::Pagination {
+        min_limit: *min,
+        max_limit: *max,
+      }),
+      QError::TooManyRequests(secs) => Some(ErrorDetails::RetryAfter {
+        retry_after_secs: *secs,
+      }),
+      _ => None,
+    }
+  } // end fn details()
 }
 
 impl Reject for QError {}
 
-/// Returns a Warp error reply for the given rejection.
+/// Logs an error along with a new reference, and gets that reference. Clients are only
+/// shown the reference, which finds the details of the error in the logs.
+///
+/// # Arguments
+///
+/// * `error`: Error to log.
+pub fn log_with_reference(error: &impl std::fmt::Debug) -> String {
+  let error_id = Uuid::new_v4().to_string();
+  event!(Level::ERROR, "Internal error {}. {:?}", error_id, error);
+  error_id
+} // end fn log_with_reference()
+
+/// Returns a Warp error reply for the given rejection, with a JSON body like
+/// `{"error": {"code": "QUESTION_NOT_FOUND", "message": "Question not found."}}`.
 ///
 /// # Arguments
 ///
 /// * `rej`: Warp rejection object containing an error that happened.
 #[instrument]
-pub async fn return_error(rej: Rejection) -> Result<impl Reply, Rejection> {
+pub async fn return_error(rej: Rejection) -> Result<Response, Rejection> {
+  Ok(reply_rejection(&rej, None, None))
+} // end fn return_error()
+
+/// Wraps the given routes so their rejections are replied like `return_error` does,
+/// unless the `Accept` header asks for `application/problem+json`, in which case they
+/// are replied as RFC 7807 problem details of the path of the request.
+///
+/// # Arguments
+///
+/// * `routes`: Routes whose rejections are replied.
+pub fn recover_errors<F, R>(
+  routes: F
+) -> impl Filter<Extract = (Response,), Error = Infallible> + Clone
+where
+  F: Filter<Extract = (R,), Error = Rejection> + Clone + Send + Sync + 'static,
+  R: Reply,
+{
+  let outcome = routes
+    .map(|reply: R| Ok(reply.into_response()))
+    .or_else(|rej| async move { Ok::<_, Infallible>((Err(rej),)) });
+
+  warp::header::headers_cloned()
+    .and(warp::path::full())
+    .and(outcome)
+    .map(
+      |headers: HeaderMap, path: FullPath, outcome: Result<Response, Rejection>| {
+        match outcome {
+          Ok(response) => response,
+          Err(rej) => {
+            let accept = headers.get(ACCEPT).and_then(|accept| accept.to_str().ok());
+            reply_rejection(&rej, accept, Some(path.as_str()))
+          }
+        }
+      },
+    )
+} // end fn recover_errors()
+
+/// Gets the reply to the error of the given rejection, as problem details when the
+/// `Accept` header asks for them.
+///
+/// # Arguments
+///
+/// * `rej`: Warp rejection object containing an error that happened.
+/// * `accept`: Value of the `Accept` header of the request, if any.
+/// * `instance`: Path of the request, if known.
+pub fn reply_rejection(
+  rej: &Rejection,
+  accept: Option<&str>,
+  instance: Option<&str>,
+) -> Response {
+  let (status, body) = error_reply(rej);
+  let mut response = match accept.map_or(false, accepts_problem) {
+    true => {
+      let problem = ProblemReply {
+        problem_type: "about:blank",
+        title: status.canonical_reason().unwrap_or_default(),
+        status: status.as_u16(),
+        detail: body.error.message,
+        instance,
+        code: body.error.code,
+        details: body.error.details,
+      };
+      let mut response =
+        warp::reply::with_status(warp::reply::json(&problem), status).into_response();
+      response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(PROBLEM_MEDIA_TYPE));
+      response
+    }
+    false => warp::reply::with_status(warp::reply::json(&body), status).into_response(),
+  };
+
+  // Tell the clients over their rate when to retry
+  if let Some(QError::TooManyRequests(secs)) = rej.find::<QError>() {
+    if let Ok(secs) = HeaderValue::from_str(&secs.to_string()) {
+      response.headers_mut().insert(RETRY_AFTER, secs);
+    }
+  }
+
+  response
+} // end fn reply_rejection()
+
+/// Checks whether the given `Accept` header asks for problem details, that is, lists
+/// `application/problem+json` without a zero quality.
+///
+/// # Arguments
+///
+/// * `accept`: Value of the `Accept` header.
+fn accepts_problem(accept: &str) -> bool {
+  accept.split(',').any(|range| {
+    let mut parts = range.split(';').map(str::trim);
+    let media_type = parts.next().unwrap_or_default();
+    let refused = parts.any(|parameter| {
+      matches!(parameter.split_once('='), Some((name, quality))
+        if name.trim().eq_ignore_ascii_case("q")
+          && quality.trim().parse::<f32>().map_or(false, |quality| quality <= 0.0))
+    });
+    media_type.eq_ignore_ascii_case(PROBLEM_MEDIA_TYPE) && !refused
+  })
+} // end fn accepts_problem()
+
+/// Gets the status code and the body of the reply to the error of the given rejection.
+///
+/// # Arguments
+///
+/// * `rej`: Warp rejection object containing an error that happened.
+fn error_reply(rej: &Rejection) -> (StatusCode, ErrorReply<'_>) {
   // Handle operations errors
   if let Some(error) = rej.find::<QError>() {
-    match error {
-      QError::QuestionNotFound => Ok(warp::reply::with_status(
-        error.to_string(),
-        StatusCode::NOT_FOUND,
-      )),
-      QError::MissingParameters => Ok(warp::reply::with_status(
-        error.to_string(),
-        StatusCode::BAD_REQUEST,
-      )),
-      QError::ParseError(_) => Ok(warp::reply::with_status(
-        error.to_string(),
-        StatusCode::BAD_REQUEST,
-      )),
-      QError::DatabaseQueryError(err) => {
-        event!(Level::ERROR, "{}", format!("Database query error. {}", error));
-        Ok(warp::reply::with_status(
-          err.to_string(),
-          StatusCode::BAD_REQUEST,
-        ))
-      }
-      // _ => Ok(warp::reply::with_status(
-      //   error.to_string(),
-      //   StatusCode::RANGE_NOT_SATISFIABLE,
-      // )),
-      // _ => Ok(warp::reply::with_status(
-      //   error.to_string(),
-      //   StatusCode::NOT_FOUND,
-      // )),
+    let message = match error {
+      QError::InvalidQuery(_) => "Invalid query parameters.".to_string(),
+      QError::InvalidResponses(_) => "Invalid responses.".to_string(),
+      QError::InvalidFields(_) => "Invalid fields.".to_string(),
+      _ => error.to_string(),
+    };
+    let mut body = ErrorReply {
+      error: ErrorBody {
+        code: error.code(),
+        message,
+        details: error.details(),
+      },
+    };
+
+    // The SQL and the schema in database errors, and the causes of internal errors, are
+    // only logged, along with an ID the client can report to find them.
+    if let QError::DatabaseQueryError(_) | QError::Internal(_) = error {
+      let error_id = log_with_reference(error);
+      body.error.message =
+        format!("Internal server error. Report the reference {}.", error_id);
+      body.error.details = Some(ErrorDetails::Reference { error_id });
     }
+
+    (error.status(), body)
   }
   // Handle CORS errors
   else if let Some(error) = rej.find::<CorsForbidden>() {
-    Ok(warp::reply::with_status(
-      error.to_string(),
+    (
       StatusCode::FORBIDDEN,
-    ))
+      ErrorReply::new("CORS_FORBIDDEN", error.to_string()),
+    )
   }
   // Handle malformed HTTP Bodies
   else if let Some(error) = rej.find::<BodyDeserializeError>() {
-    Ok(warp::reply::with_status(
-      error.to_string(),
+    (
       StatusCode::UNPROCESSABLE_ENTITY,
-    ))
+      ErrorReply::new("INVALID_BODY", error.to_string()),
+    )
+  }
+  // Handle bodies sent without their length
+  else if let Some(error) = rej.find::<LengthRequired>() {
+    (
+      StatusCode::LENGTH_REQUIRED,
+      ErrorReply::new("LENGTH_REQUIRED", error.to_string()),
+    )
+  }
+  else if let Some(error) = rej.find::<UnsupportedMediaType>() {
+    (
+      StatusCode::UNSUPPORTED_MEDIA_TYPE,
+      ErrorReply::new("UNSUPPORTED_MEDIA_TYPE", error.to_string()),
+    )
   }
   // At this point, the possible rejection is that a path not found
   else {
-    Ok(warp::reply::with_status(
-      "Route not found".to_string(),
+    (
       StatusCode::NOT_FOUND,
-    ))
+      ErrorReply::new("ROUTE_NOT_FOUND", "Route not found".to_string()),
+    )
   }
-} // end fn return_error()
+} // end fn error_reply()
@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS answer_code_blocks;
+DROP TABLE IF EXISTS answers;
+DROP TABLE IF EXISTS question_options;
+DROP TABLE IF EXISTS question_tags;
+DROP TABLE IF EXISTS tags;
+DROP TABLE IF EXISTS questions;
@@ -0,0 +1,62 @@
+/* Schema of the questions and answers for the SQLite backend, equivalent to the 
+   tables the PostgreSQL migrations build for them. The surveys, the search and the 
+   change feed rely on PostgreSQL, so they have no tables here */
+CREATE TABLE questions (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  title TEXT NOT NULL CHECK (length(title) <= 255),
+  content TEXT NOT NULL,
+  status TEXT NOT NULL DEFAULT 'open' 
+    CHECK (status IN ('open', 'closed', 'archived')),
+  kind TEXT NOT NULL DEFAULT 'text' 
+    CHECK (kind IN ('text', 'single_choice', 'multiple_choice', 'rating')),
+  rating_max INTEGER NULL,
+  version INTEGER NOT NULL DEFAULT 1,
+  created_on DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  /* Only rating questions have a scale, which goes from 1 to rating_max */
+  CHECK ((kind = 'rating') = (rating_max IS NOT NULL)),
+  CHECK (rating_max IS NULL OR rating_max BETWEEN 2 AND 10)
+);
+
+CREATE TABLE tags (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  name TEXT NOT NULL UNIQUE CHECK (length(name) <= 64)
+);
+
+CREATE TABLE question_tags (
+  question_id INTEGER NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+  tag_id INTEGER NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+  PRIMARY KEY (question_id, tag_id)
+);
+
+CREATE INDEX idx_question_tags_tag_id ON question_tags (tag_id);
+
+CREATE TABLE question_options (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  question_id INTEGER NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+  position INTEGER NOT NULL,
+  label TEXT NOT NULL CHECK (length(label) <= 255),
+  points INTEGER NOT NULL DEFAULT 0,
+  UNIQUE (question_id, position)
+);
+
+CREATE TABLE answers (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  content TEXT NOT NULL,
+  question_id INTEGER NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+  created_on DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_answers_question_id ON answers (question_id);
+
+/* Fenced code blocks of the answers, parsed when the answers are written, so the 
+   questions can be filtered by the languages of the code in their answers */
+CREATE TABLE answer_code_blocks (
+  answer_id INTEGER NOT NULL REFERENCES answers(id) ON DELETE CASCADE,
+  position INTEGER NOT NULL,
+  language TEXT NULL CHECK (length(language) <= 32),
+  line_count INTEGER NOT NULL,
+  start_line INTEGER NOT NULL,
+  PRIMARY KEY (answer_id, position)
+);
+
+CREATE INDEX idx_answer_code_blocks_language ON answer_code_blocks (language);
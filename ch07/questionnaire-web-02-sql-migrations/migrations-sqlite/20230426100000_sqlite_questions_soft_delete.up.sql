@@ -0,0 +1,4 @@
+/* Deleted questions are kept, marked with the date they were deleted, like in the 
+   PostgreSQL schema */
+ALTER TABLE questions 
+  ADD COLUMN deleted_at DATETIME NULL;
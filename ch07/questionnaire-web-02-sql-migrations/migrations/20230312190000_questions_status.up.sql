@@ -0,0 +1,5 @@
+ALTER TABLE questions 
+  ADD COLUMN status varchar (16) NOT NULL DEFAULT 'open';
+
+ALTER TABLE questions 
+  ADD CONSTRAINT chk_questions_status CHECK (status IN ('open', 'closed', 'archived'));
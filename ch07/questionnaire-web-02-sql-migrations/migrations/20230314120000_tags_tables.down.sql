@@ -0,0 +1,12 @@
+ALTER TABLE questions ADD COLUMN tags TEXT [];
+
+/* Move the tags back into the array column */
+UPDATE questions q 
+  SET tags = (
+    SELECT array_agg(t.name ORDER BY t.name) 
+    FROM question_tags qt JOIN tags t ON t.id = qt.tag_id 
+    WHERE qt.question_id = q.id
+  );
+
+DROP TABLE IF EXISTS question_tags;
+DROP TABLE IF EXISTS tags;
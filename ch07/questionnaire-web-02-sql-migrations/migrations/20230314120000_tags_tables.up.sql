@@ -0,0 +1,25 @@
+CREATE TABLE tags (
+  id serial PRIMARY KEY,
+  name varchar (64) NOT NULL UNIQUE
+);
+
+CREATE TABLE question_tags (
+  question_id integer NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+  tag_id integer NOT NULL REFERENCES tags(id) ON DELETE CASCADE,
+  PRIMARY KEY (question_id, tag_id)
+);
+
+CREATE INDEX idx_question_tags_tag_id ON question_tags (tag_id);
+
+/* Move the tags of the array column into the new tables */
+INSERT INTO tags (name) 
+  SELECT DISTINCT unnest(tags) FROM questions 
+  ON CONFLICT (name) DO NOTHING;
+
+INSERT INTO question_tags (question_id, tag_id) 
+  SELECT DISTINCT q.id, t.id 
+  FROM questions q 
+  CROSS JOIN LATERAL unnest(q.tags) AS tag_name 
+  JOIN tags t ON t.name = tag_name;
+
+ALTER TABLE questions DROP COLUMN tags;
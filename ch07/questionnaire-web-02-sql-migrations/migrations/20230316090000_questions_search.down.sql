@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_questions_search_vector;
+
+ALTER TABLE questions 
+  DROP COLUMN IF EXISTS search_vector;
@@ -0,0 +1,7 @@
+ALTER TABLE questions 
+  ADD COLUMN search_vector tsvector GENERATED ALWAYS AS (
+    setweight(to_tsvector('english', coalesce(title, '')), 'A') || 
+    setweight(to_tsvector('english', coalesce(content, '')), 'B')
+  ) STORED;
+
+CREATE INDEX idx_questions_search_vector ON questions USING GIN (search_vector);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_questions_deleted_at;
+
+ALTER TABLE questions 
+  DROP COLUMN IF EXISTS deleted_at;
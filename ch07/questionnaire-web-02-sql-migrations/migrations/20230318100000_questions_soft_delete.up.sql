@@ -0,0 +1,5 @@
+ALTER TABLE questions 
+  ADD COLUMN deleted_at TIMESTAMP NULL;
+
+CREATE INDEX idx_questions_deleted_at ON questions (deleted_at) 
+  WHERE deleted_at IS NOT NULL;
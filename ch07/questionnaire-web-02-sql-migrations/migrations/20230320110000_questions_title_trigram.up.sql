@@ -0,0 +1,3 @@
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX idx_questions_title_trgm ON questions USING GIN (title gin_trgm_ops);
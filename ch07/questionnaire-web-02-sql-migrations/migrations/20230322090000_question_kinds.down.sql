@@ -0,0 +1,9 @@
+DROP TABLE IF EXISTS response_choices;
+DROP TABLE IF EXISTS responses;
+DROP TABLE IF EXISTS question_options;
+
+ALTER TABLE questions 
+  DROP CONSTRAINT IF EXISTS chk_questions_rating_max,
+  DROP CONSTRAINT IF EXISTS chk_questions_kind,
+  DROP COLUMN IF EXISTS rating_max,
+  DROP COLUMN IF EXISTS kind;
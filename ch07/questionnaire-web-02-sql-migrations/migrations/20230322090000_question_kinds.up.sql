@@ -0,0 +1,37 @@
+ALTER TABLE questions 
+  ADD COLUMN kind varchar (16) NOT NULL DEFAULT 'text',
+  ADD COLUMN rating_max integer NULL;
+
+ALTER TABLE questions 
+  ADD CONSTRAINT chk_questions_kind 
+  CHECK (kind IN ('text', 'single_choice', 'multiple_choice', 'rating'));
+
+/* Only rating questions have a scale, which goes from 1 to rating_max */
+ALTER TABLE questions 
+  ADD CONSTRAINT chk_questions_rating_max 
+  CHECK ((kind = 'rating') = (rating_max IS NOT NULL) AND rating_max BETWEEN 2 AND 10);
+
+CREATE TABLE question_options (
+  id serial PRIMARY KEY,
+  question_id integer NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+  position integer NOT NULL,
+  label varchar (255) NOT NULL,
+  UNIQUE (question_id, position)
+);
+
+CREATE TABLE responses (
+  id serial PRIMARY KEY,
+  question_id integer NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+  rating integer NULL,
+  created_on TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_responses_question_id ON responses (question_id);
+
+CREATE TABLE response_choices (
+  response_id integer NOT NULL REFERENCES responses(id) ON DELETE CASCADE,
+  option_id integer NOT NULL REFERENCES question_options(id) ON DELETE CASCADE,
+  PRIMARY KEY (response_id, option_id)
+);
+
+CREATE INDEX idx_response_choices_option_id ON response_choices (option_id);
@@ -0,0 +1,8 @@
+DROP INDEX IF EXISTS idx_responses_submission_id;
+
+ALTER TABLE responses 
+  DROP COLUMN IF EXISTS submission_id;
+
+DROP TABLE IF EXISTS survey_submissions;
+DROP TABLE IF EXISTS survey_questions;
+DROP TABLE IF EXISTS surveys;
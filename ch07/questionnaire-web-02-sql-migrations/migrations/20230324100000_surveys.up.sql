@@ -0,0 +1,32 @@
+CREATE TABLE surveys (
+  id serial PRIMARY KEY,
+  title varchar (255) NOT NULL,
+  opens_on TIMESTAMP NULL,
+  closes_on TIMESTAMP NULL,
+  created_on TIMESTAMP NOT NULL DEFAULT now(),
+  CONSTRAINT chk_surveys_dates CHECK (closes_on > opens_on)
+);
+
+CREATE TABLE survey_questions (
+  survey_id integer NOT NULL REFERENCES surveys(id) ON DELETE CASCADE,
+  question_id integer NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+  position integer NOT NULL,
+  PRIMARY KEY (survey_id, question_id),
+  UNIQUE (survey_id, position)
+);
+
+CREATE INDEX idx_survey_questions_question_id ON survey_questions (question_id);
+
+/* A submission groups the responses given by a respondent to a survey */
+CREATE TABLE survey_submissions (
+  id serial PRIMARY KEY,
+  survey_id integer NOT NULL REFERENCES surveys(id) ON DELETE CASCADE,
+  created_on TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_survey_submissions_survey_id ON survey_submissions (survey_id);
+
+ALTER TABLE responses 
+  ADD COLUMN submission_id integer NULL REFERENCES survey_submissions(id) ON DELETE CASCADE;
+
+CREATE INDEX idx_responses_submission_id ON responses (submission_id);
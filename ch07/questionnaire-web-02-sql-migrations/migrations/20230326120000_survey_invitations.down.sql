@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS survey_invitations;
+
+ALTER TABLE surveys 
+  DROP COLUMN IF EXISTS invitation_only;
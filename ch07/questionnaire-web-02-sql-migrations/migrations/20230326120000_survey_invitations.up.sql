@@ -0,0 +1,12 @@
+ALTER TABLE surveys 
+  ADD COLUMN invitation_only boolean NOT NULL DEFAULT false;
+
+/* Only a hash of each token is kept, and nothing links an invitation to the 
+   submission it was used for, so the responses stay anonymous */
+CREATE TABLE survey_invitations (
+  token_hash char (64) PRIMARY KEY,
+  survey_id integer NOT NULL REFERENCES surveys(id) ON DELETE CASCADE,
+  used boolean NOT NULL DEFAULT false
+);
+
+CREATE INDEX idx_survey_invitations_survey_id ON survey_invitations (survey_id);
@@ -0,0 +1,13 @@
+/* A rule shows a question of a survey only when the response to an earlier 
+   question of the same survey includes any of the given choices or ratings */
+CREATE TABLE survey_question_rules (
+  survey_id integer NOT NULL,
+  question_id integer NOT NULL,
+  depends_on integer NOT NULL,
+  any_of integer [] NOT NULL,
+  PRIMARY KEY (survey_id, question_id),
+  FOREIGN KEY (survey_id, question_id) 
+    REFERENCES survey_questions(survey_id, question_id) ON DELETE CASCADE,
+  FOREIGN KEY (survey_id, depends_on) 
+    REFERENCES survey_questions(survey_id, question_id) ON DELETE CASCADE
+);
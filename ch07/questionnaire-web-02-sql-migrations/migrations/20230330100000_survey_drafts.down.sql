@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS survey_drafts;
+
+ALTER TABLE survey_questions 
+  DROP COLUMN IF EXISTS required;
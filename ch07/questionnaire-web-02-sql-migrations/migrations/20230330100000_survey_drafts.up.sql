@@ -0,0 +1,14 @@
+ALTER TABLE survey_questions 
+  ADD COLUMN required boolean NOT NULL DEFAULT true;
+
+/* A draft keeps the responses saved so far by a respondent, to resume later. Only a 
+   hash of its key is kept, like for the invitations */
+CREATE TABLE survey_drafts (
+  key_hash char (64) PRIMARY KEY,
+  survey_id integer NOT NULL REFERENCES surveys(id) ON DELETE CASCADE,
+  responses jsonb NOT NULL,
+  created_on TIMESTAMP NOT NULL DEFAULT now(),
+  updated_on TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_survey_drafts_survey_id ON survey_drafts (survey_id);
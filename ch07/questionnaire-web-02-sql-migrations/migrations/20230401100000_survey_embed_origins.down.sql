@@ -0,0 +1,2 @@
+ALTER TABLE surveys 
+  DROP COLUMN IF EXISTS embed_origins;
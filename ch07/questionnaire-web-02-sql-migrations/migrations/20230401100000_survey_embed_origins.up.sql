@@ -0,0 +1,3 @@
+/* Origins of the sites allowed to embed the survey widget */
+ALTER TABLE surveys 
+  ADD COLUMN embed_origins varchar (255) [] NOT NULL DEFAULT '{}';
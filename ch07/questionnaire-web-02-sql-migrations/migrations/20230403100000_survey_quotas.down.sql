@@ -0,0 +1,5 @@
+ALTER TABLE surveys 
+  DROP CONSTRAINT IF EXISTS chk_surveys_max_submissions,
+  DROP COLUMN IF EXISTS closed_on,
+  DROP COLUMN IF EXISTS submission_count,
+  DROP COLUMN IF EXISTS max_submissions;
@@ -0,0 +1,10 @@
+/* A survey is closed once it reaches its maximum amount of submissions, or by the 
+   scheduler once its closing date passes */
+ALTER TABLE surveys 
+  ADD COLUMN max_submissions integer NULL,
+  ADD COLUMN submission_count integer NOT NULL DEFAULT 0,
+  ADD COLUMN closed_on TIMESTAMP NULL,
+  ADD CONSTRAINT chk_surveys_max_submissions CHECK (max_submissions > 0);
+
+UPDATE surveys s 
+SET submission_count = (SELECT COUNT(*) FROM survey_submissions ss WHERE ss.survey_id = s.id);
@@ -0,0 +1,8 @@
+ALTER TABLE survey_submissions 
+  DROP COLUMN IF EXISTS score;
+
+ALTER TABLE surveys 
+  DROP COLUMN IF EXISTS quiz;
+
+ALTER TABLE question_options 
+  DROP COLUMN IF EXISTS points;
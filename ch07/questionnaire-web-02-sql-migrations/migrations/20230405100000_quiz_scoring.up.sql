@@ -0,0 +1,11 @@
+/* Points each option is worth in quiz surveys, the options worth points being the 
+   correct ones */
+ALTER TABLE question_options 
+  ADD COLUMN points integer NOT NULL DEFAULT 0;
+
+ALTER TABLE surveys 
+  ADD COLUMN quiz boolean NOT NULL DEFAULT false;
+
+/* Score of the submissions to quiz surveys, computed when they are submitted */
+ALTER TABLE survey_submissions 
+  ADD COLUMN score integer NULL;
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_survey_drafts_expires_on;
+
+ALTER TABLE survey_drafts 
+  DROP COLUMN IF EXISTS expires_on;
@@ -0,0 +1,8 @@
+/* A draft expires after some time without being saved, and is then purged. The 
+   existing drafts expire counting from their last save */
+ALTER TABLE survey_drafts 
+  ADD COLUMN expires_on TIMESTAMP NOT NULL DEFAULT now() + interval '30 days';
+
+UPDATE survey_drafts SET expires_on = updated_on + interval '30 days';
+
+CREATE INDEX idx_survey_drafts_expires_on ON survey_drafts (expires_on);
@@ -0,0 +1,4 @@
+/* The version of a question grows with each change, so an update based on an older 
+   version can be refused instead of overwriting the newer data */
+ALTER TABLE questions 
+  ADD COLUMN version integer NOT NULL DEFAULT 1;
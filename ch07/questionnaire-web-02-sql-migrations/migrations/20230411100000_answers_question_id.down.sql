@@ -0,0 +1,10 @@
+UPDATE answers SET corresponding_question = question_id 
+  WHERE corresponding_question IS NULL;
+
+ALTER TABLE answers 
+  ALTER COLUMN corresponding_question SET NOT NULL;
+
+DROP INDEX IF EXISTS idx_answers_question_id;
+
+ALTER TABLE answers 
+  DROP COLUMN IF EXISTS question_id;
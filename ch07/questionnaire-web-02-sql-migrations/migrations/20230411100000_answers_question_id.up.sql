@@ -0,0 +1,14 @@
+/* Expand phase of the move of answers.corresponding_question to answers.question_id, 
+   the name used by the other tables. Builds that only know the old column keep 
+   working while the newer ones write both, and the old column becomes optional so 
+   it can stop being written once SCHEMA_COMPAT_MODE is turned off. A later 
+   migration drops it */
+ALTER TABLE answers 
+  ADD COLUMN question_id integer NULL REFERENCES questions(id);
+
+UPDATE answers SET question_id = corresponding_question;
+
+ALTER TABLE answers 
+  ALTER COLUMN corresponding_question DROP NOT NULL;
+
+CREATE INDEX idx_answers_question_id ON answers (question_id);
@@ -0,0 +1,4 @@
+/* Lets the suggestions of tags match any part of their names, like the titles */
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX idx_tags_name_trgm ON tags USING GIN (name gin_trgm_ops);
@@ -0,0 +1,3 @@
+ALTER TABLE answers DROP COLUMN IF EXISTS code_parsed;
+
+DROP TABLE IF EXISTS answer_code_blocks;
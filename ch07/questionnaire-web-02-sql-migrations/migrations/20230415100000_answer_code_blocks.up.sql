@@ -0,0 +1,17 @@
+/* Fenced code blocks of the answers, parsed when the answers are written, so the 
+   questions can be filtered by the languages of the code in their answers */
+CREATE TABLE answer_code_blocks (
+  answer_id integer NOT NULL REFERENCES answers(id) ON DELETE CASCADE,
+  position integer NOT NULL,
+  language varchar (32) NULL,
+  line_count integer NOT NULL,
+  start_line integer NOT NULL,
+  PRIMARY KEY (answer_id, position)
+);
+
+CREATE INDEX idx_answer_code_blocks_language ON answer_code_blocks (language);
+
+/* Whether the code blocks of the answer were parsed. The existing answers, and the 
+   ones written by older builds, are parsed at startup */
+ALTER TABLE answers 
+  ADD COLUMN code_parsed boolean NOT NULL DEFAULT false;
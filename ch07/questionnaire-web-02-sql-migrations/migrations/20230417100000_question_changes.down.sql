@@ -0,0 +1,3 @@
+DROP TRIGGER IF EXISTS trg_questions_changes ON questions;
+DROP FUNCTION IF EXISTS record_question_change();
+DROP TABLE IF EXISTS question_changes;
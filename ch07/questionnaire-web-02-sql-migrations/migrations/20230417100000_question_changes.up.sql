@@ -0,0 +1,41 @@
+/* Append-only log of the changes of the questions, for the clients that sync them. 
+   The changes are recorded by a trigger, so every write is logged, those of older 
+   builds included. The transaction of each change orders them, since the changes of 
+   a transaction are only seen once it commits */
+CREATE TABLE question_changes (
+  id bigserial PRIMARY KEY,
+  question_id integer NOT NULL,
+  change varchar (16) NOT NULL CHECK (change IN ('created', 'updated', 'deleted')),
+  transaction_id xid8 NOT NULL DEFAULT pg_current_xact_id(),
+  changed_on TIMESTAMP NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_question_changes_transaction_id ON question_changes (transaction_id);
+
+CREATE FUNCTION record_question_change() RETURNS trigger AS $$
+BEGIN
+  IF TG_OP = 'INSERT' THEN
+    INSERT INTO question_changes (question_id, change) 
+      VALUES (NEW.id, CASE WHEN NEW.deleted_at IS NULL THEN 'created' ELSE 'deleted' END);
+  ELSIF TG_OP = 'DELETE' THEN
+    /* Deleted questions were logged when they were marked as deleted */
+    IF OLD.deleted_at IS NULL THEN
+      INSERT INTO question_changes (question_id, change) VALUES (OLD.id, 'deleted');
+    END IF;
+  ELSIF NEW.deleted_at IS NULL THEN
+    /* Restored questions are logged as updated */
+    INSERT INTO question_changes (question_id, change) VALUES (NEW.id, 'updated');
+  ELSIF OLD.deleted_at IS NULL THEN
+    INSERT INTO question_changes (question_id, change) VALUES (NEW.id, 'deleted');
+  END IF;
+  RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trg_questions_changes 
+  AFTER INSERT OR UPDATE OR DELETE ON questions 
+  FOR EACH ROW EXECUTE FUNCTION record_question_change();
+
+/* The existing questions are logged as created, so a first sync gets them all */
+INSERT INTO question_changes (question_id, change) 
+  SELECT id, 'created' FROM questions WHERE deleted_at IS NULL ORDER BY id;
@@ -0,0 +1,18 @@
+/* Title, content and tags of each version of the questions, so an update based on an 
+   older version can be replied with a hint to merge it with the current version */
+CREATE TABLE question_revisions (
+  question_id integer NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+  version integer NOT NULL,
+  title varchar (255) NOT NULL,
+  content text NOT NULL,
+  tags text [] NOT NULL,
+  revised_on TIMESTAMP NOT NULL DEFAULT now(),
+  PRIMARY KEY (question_id, version)
+);
+
+/* The current versions are the first ones known */
+INSERT INTO question_revisions (question_id, version, title, content, tags) 
+  SELECT q.id, q.version, q.title, q.content, 
+    ARRAY(SELECT t.name FROM question_tags qt JOIN tags t ON t.id = qt.tag_id 
+      WHERE qt.question_id = q.id ORDER BY t.name) 
+  FROM questions q;
@@ -0,0 +1,11 @@
+/* IDs of the questions replaced by another one, e.g. after merging duplicates, so the 
+   old links are redirected to the question that replaced them. The old IDs do not 
+   reference the questions, since they are gone once purged */
+CREATE TABLE question_redirects (
+  from_id integer PRIMARY KEY,
+  target_id integer NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+  created_on TIMESTAMP NOT NULL DEFAULT now(),
+  CONSTRAINT question_redirects_other_target CHECK (target_id <> from_id)
+);
+
+CREATE INDEX question_redirects_target_id_idx ON question_redirects (target_id);
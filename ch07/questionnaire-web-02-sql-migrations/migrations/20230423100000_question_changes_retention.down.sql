@@ -0,0 +1 @@
+DROP INDEX IF EXISTS question_changes_changed_on_idx;
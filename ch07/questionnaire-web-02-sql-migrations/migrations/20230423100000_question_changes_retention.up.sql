@@ -0,0 +1,2 @@
+/* The changes older than their retention period are deleted by their date */
+CREATE INDEX question_changes_changed_on_idx ON question_changes (changed_on);
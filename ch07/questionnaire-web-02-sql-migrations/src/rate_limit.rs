@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use warp::{Filter, Rejection};
+
+use handle_errors::errors::QError;
+
+use crate::server::ClientAddress;
+
+/// Amount of clients tracked from which the ones whose window ended are forgotten.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+/// Start of the current window of each client, by their IP address, along with the
+/// requests they sent in it. Requests without a known address share one window.
+type Windows = HashMap<Option<IpAddr>, (Instant, u32)>;
+
+/// Represents a limit of the requests each client may send in a window of time.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+  /// Maximum amount of requests of a client in a window.
+  max_requests: u32,
+  /// Length of a window.
+  window: Duration,
+  /// Windows of the clients.
+  windows: Arc<Mutex<Windows>>,
+} // end struct RateLimiter
+
+impl RateLimiter {
+  /// Creates a limit of the given amount of requests per window for each client.
+  ///
+  /// # Arguments
+  ///
+  /// * `max_requests`: Maximum amount of requests of a client in a window.
+  /// * `window`: Length of a window.
+  pub fn new(
+    max_requests: u32,
+    window: Duration,
+  ) -> Self {
+    RateLimiter {
+      max_requests,
+      window,
+      windows: Arc::new(Mutex::new(HashMap::new())),
+    }
+  } // end fn new()
+
+  /// Counts a request of the given client, failing with the seconds until the next
+  /// window when the client already sent all the requests of the current one.
+  ///
+  /// # Arguments
+  ///
+  /// * `client`: IP address of the client, if known.
+  pub fn check(
+    &self,
+    client: Option<IpAddr>,
+  ) -> Result<(), QError> {
+    let now = Instant::now();
+    let mut windows = self.windows();
+    if windows.len() >= MAX_TRACKED_CLIENTS {
+      windows.retain(|_, (start, _)| now.duration_since(*start) < self.window);
+    }
+
+    let (start, count) = windows.entry(client).or_insert((now, 0));
+    if now.duration_since(*start) >= self.window {
+      *start = now;
+      *count = 0;
+    }
+    if *count >= self.max_requests {
+      let wait = self.window.saturating_sub(now.duration_since(*start));
+      return Err(QError::TooManyRequests(wait.as_secs().max(1)));
+    }
+
+    *count += 1;
+    Ok(())
+  } // end fn check()
+
+  /// Locks the windows of the clients.
+  fn windows(&self) -> MutexGuard<'_, Windows> {
+    // The windows only hold counters, so they are still usable after a panic while
+    // they were locked.
+    self.windows.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+  } // end fn windows()
+}
+
+/// Creates a filter that rejects the requests of the clients over the given limit with
+/// a `QError::TooManyRequests` error, replied as `429 Too Many Requests`.
+///
+/// # Arguments
+///
+/// * `limiter`: Limit of the requests of each client.
+pub fn limit_rate(
+  limiter: RateLimiter
+) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+  warp::ext::optional::<ClientAddress>()
+    .and_then(move |client: Option<ClientAddress>| {
+      let result = limiter.check(client.map(|client| client.0.ip()));
+      async move { result.map_err(warp::reject::custom) }
+    })
+    .untuple_one()
+} // end fn limit_rate()
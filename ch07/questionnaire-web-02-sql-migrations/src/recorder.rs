@@ -0,0 +1,227 @@
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use warp::http::header::{CONTENT_LENGTH, HOST};
+use warp::http::{Request, Response, StatusCode};
+use warp::hyper::body::to_bytes;
+use warp::hyper::client::HttpConnector;
+use warp::hyper::service::Service;
+use warp::hyper::{Body, Client};
+
+use crate::types::recording::{Exchange, RecordedBody};
+
+/// Represents the file where the exchanges are appended, one JSON object per line.
+#[derive(Debug, Clone)]
+pub struct Recording {
+  /// File opened for appending.
+  file: Arc<Mutex<tokio::fs::File>>,
+} // end struct Recording
+
+impl Recording {
+  /// Opens the file the exchanges are appended to, creating it if it does not exist.
+  ///
+  /// # Arguments
+  ///
+  /// * `path`: Path of the file.
+  pub async fn open(path: &str) -> Self {
+    let file = tokio::fs::OpenOptions::new()
+      .create(true)
+      .append(true)
+      .open(path)
+      .await
+      .expect("cannot open the recording file");
+
+    Recording {
+      file: Arc::new(Mutex::new(file)),
+    }
+  } // end fn open()
+
+  /// Appends an exchange to the file, logging instead of failing the request when it
+  /// cannot be written.
+  ///
+  /// # Arguments
+  ///
+  /// * `exchange`: Exchange to append.
+  async fn append(
+    &self,
+    exchange: &Exchange,
+  ) {
+    let mut line = match serde_json::to_vec(exchange) {
+      Ok(line) => line,
+      Err(err) => {
+        tracing::event!(tracing::Level::ERROR, "Cannot serialize the exchange. {}", err);
+        return;
+      }
+    };
+    line.push(b'\n');
+
+    if let Err(err) = self.file.lock().await.write_all(&line).await {
+      tracing::event!(tracing::Level::ERROR, "Cannot record the exchange. {}", err);
+    }
+  } // end fn append()
+}
+
+/// Replies a request with the given service and appends the request and its response
+/// to a file, with the secrets redacted, so the traffic can be replayed later with
+/// `replay`.
+///
+/// The request and response bodies are buffered in memory to be recorded, so this is
+/// meant for development only.
+///
+/// # Arguments
+///
+/// * `service`: Service of the API routes.
+/// * `recording`: File where the exchange is recorded.
+/// * `request`: Request to reply.
+pub async fn record_exchange<S>(
+  mut service: S,
+  recording: Recording,
+  request: Request<Body>,
+) -> Result<Response<Body>, Infallible>
+where
+  S: Service<Request<Body>, Response = Response<Body>, Error = Infallible>,
+{
+  let (parts, body) = request.into_parts();
+  let request_body = match to_bytes(body).await {
+    Ok(bytes) => bytes,
+    Err(err) => {
+      tracing::event!(tracing::Level::WARN, "Cannot read the request body. {}", err);
+      let mut response = Response::new(Body::from("Cannot read the request body."));
+      *response.status_mut() = StatusCode::BAD_REQUEST;
+      return Ok(response);
+    }
+  };
+  let mut exchange =
+    Exchange::new(&parts.method, &parts.uri, &parts.headers, &request_body);
+
+  futures::future::poll_fn(|context| service.poll_ready(context)).await?;
+  let response = service
+    .call(Request::from_parts(parts, Body::from(request_body)))
+    .await?;
+
+  let (parts, body) = response.into_parts();
+  let response_body = match to_bytes(body).await {
+    Ok(bytes) => bytes,
+    Err(err) => {
+      tracing::event!(tracing::Level::ERROR, "Cannot read the response body. {}", err);
+      let mut response = Response::new(Body::empty());
+      *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+      return Ok(response);
+    }
+  };
+  exchange.set_response(parts.status, &parts.headers, &response_body);
+  recording.append(&exchange).await;
+
+  Ok(Response::from_parts(parts, Body::from(response_body)))
+} // end fn record_exchange()
+
+/// Represents the outcome of a replay of recorded exchanges.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReplaySummary {
+  /// Amount of exchanges that got the same status and body as recorded.
+  pub matched: u64,
+  /// Amount of exchanges that got a different status or body.
+  pub mismatched: u64,
+  /// Amount of exchanges that could not be sent.
+  pub failed: u64,
+} // end struct ReplaySummary
+
+/// Sends the recorded requests of a file, in order, to a running server, and compares
+/// the responses with the recorded ones, logging each difference.
+///
+/// Redacted secrets are sent as they were recorded, so requests that need a valid
+/// token or draft key are expected to differ.
+///
+/// # Arguments
+///
+/// * `path`: Path of the file with the recorded exchanges.
+/// * `base_url`: Base URL of the server, like `http://localhost:3030`.
+pub async fn replay(
+  path: &str,
+  base_url: &str,
+) -> ReplaySummary {
+  let file = std::fs::read_to_string(path).expect("cannot read the recording file");
+  let client = Client::new();
+  let mut summary = ReplaySummary::default();
+
+  for (index, line) in file.lines().enumerate().filter(|(_, line)| !line.is_empty()) {
+    let exchange: Exchange = match serde_json::from_str(line) {
+      Ok(exchange) => exchange,
+      Err(err) => {
+        tracing::event!(tracing::Level::ERROR, "Line {}: {}", index + 1, err);
+        summary.failed += 1;
+        continue;
+      }
+    };
+
+    let (status, body) = match replay_exchange(&client, base_url, &exchange).await {
+      Ok(response) => response,
+      Err(err) => {
+        tracing::event!(tracing::Level::ERROR, "Line {}: {}", index + 1, err);
+        summary.failed += 1;
+        continue;
+      }
+    };
+
+    if status == exchange.status && body == exchange.response_body {
+      summary.matched += 1;
+      continue;
+    }
+    summary.mismatched += 1;
+    let difference = match status == exchange.status {
+      true => "a different body".to_string(),
+      false => format!("status {} instead of {}", status, exchange.status),
+    };
+    tracing::event!(
+      tracing::Level::WARN,
+      "Line {}: {} {} replied {}.",
+      index + 1,
+      exchange.method,
+      exchange.path,
+      difference
+    );
+  }
+
+  summary
+} // end fn replay()
+
+/// Sends the request of a recorded exchange and gets the status code and body of the
+/// response, with the secrets redacted like in the recording.
+///
+/// # Arguments
+///
+/// * `client`: HTTP client to send the request with.
+/// * `base_url`: Base URL of the server.
+/// * `exchange`: Recorded exchange with the request to send.
+async fn replay_exchange(
+  client: &Client<HttpConnector>,
+  base_url: &str,
+  exchange: &Exchange,
+) -> Result<(u16, RecordedBody), String> {
+  let mut builder = Request::builder()
+    .method(exchange.method.as_str())
+    .uri(format!("{}{}", base_url.trim_end_matches('/'), exchange.path));
+  for (name, value) in &exchange.request_headers {
+    if !name.eq_ignore_ascii_case(HOST.as_str())
+      && !name.eq_ignore_ascii_case(CONTENT_LENGTH.as_str())
+    {
+      builder = builder.header(name.as_str(), value.as_str());
+    }
+  }
+  let request = builder
+    .body(Body::from(exchange.request_body.clone().into_bytes()))
+    .map_err(|err| format!("Invalid request. {}", err))?;
+
+  let response = client
+    .request(request)
+    .await
+    .map_err(|err| format!("Cannot send the request. {}", err))?;
+  let status = response.status().as_u16();
+  let body = to_bytes(response.into_body())
+    .await
+    .map_err(|err| format!("Cannot read the response body. {}", err))?;
+
+  Ok((status, RecordedBody::new(&body)))
+} // end fn replay_exchange()
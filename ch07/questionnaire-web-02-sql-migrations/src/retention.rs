@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+
+use handle_errors::errors::log_with_reference;
+
+use crate::store::PostgresStore;
+use crate::types::retention::{
+  RetentionPolicies, RetentionReport, RetentionRule, RuleMetrics, RuleReport,
+};
+
+/// Represents the engine that applies the retention rules on a schedule, keeping the
+/// metrics of the runs of each rule.
+#[derive(Debug, Clone)]
+pub struct Retention {
+  /// Retention period of each rule.
+  policies: RetentionPolicies,
+  /// Whether the scheduled runs only count the rows instead of removing them.
+  dry_run: bool,
+  /// Metrics of the runs of each rule.
+  metrics: Arc<Mutex<BTreeMap<RetentionRule, RuleMetrics>>>,
+} // end struct Retention
+
+impl Retention {
+  /// Creates the engine from the `RETENTION_RULES` and `RETENTION_DRY_RUN` environment
+  /// variables. The rules remove the data unless `RETENTION_DRY_RUN=true`.
+  pub fn from_env() -> Self {
+    Retention {
+      policies: RetentionPolicies::from_env(),
+      dry_run: std::env::var("RETENTION_DRY_RUN")
+        .ok()
+        .and_then(|dry_run| dry_run.parse().ok())
+        .unwrap_or(false),
+      metrics: Arc::default(),
+    }
+  } // end fn from_env()
+
+  /// Applies each rule that is on, in order, and records its metrics. A failed rule
+  /// does not stop the next ones.
+  ///
+  /// Returns the description of the problems of the failed rules, if any.
+  ///
+  /// # Arguments
+  ///
+  /// * `store`: Data store whose data is removed.
+  pub async fn run(
+    &self,
+    store: &PostgresStore,
+  ) -> Option<String> {
+    let mut errors = Vec::new();
+
+    for rule in RetentionRule::ALL {
+      let days = match self.policies.days(rule) {
+        Some(days) => days,
+        None => continue,
+      };
+
+      let started = Instant::now();
+      let outcome = store.apply_retention_rule(rule, days, self.dry_run).await;
+      let mut metrics = self.metrics();
+      let metrics = metrics.entry(rule).or_default();
+      metrics.runs += 1;
+      metrics.last_duration_ms = started.elapsed().as_secs_f64() * 1000.0;
+
+      match outcome {
+        Ok(rows) => {
+          metrics.last_rows = rows;
+          metrics.total_rows += rows;
+          metrics.last_error = None;
+          match (rows, self.dry_run) {
+            (0, _) => {}
+            (_, true) => tracing::event!(
+              tracing::Level::INFO,
+              "Retention rule {} would remove {} rows (dry run).",
+              rule.name(),
+              rows
+            ),
+            (_, false) => tracing::event!(
+              tracing::Level::INFO,
+              "Retention rule {} removed {} rows.",
+              rule.name(),
+              rows
+            ),
+          }
+        }
+        Err(err) => {
+          let error_id = log_with_reference(&err);
+          tracing::event!(
+            tracing::Level::ERROR,
+            "Cannot apply the retention rule {}. Reference {}.",
+            rule.name(),
+            error_id
+          );
+          metrics.failures += 1;
+          metrics.last_rows = 0;
+          metrics.last_error =
+            Some(format!("Internal error. Report the reference {}.", error_id));
+          errors.push(format!("{}: reference {}", rule.name(), error_id));
+        }
+      }
+    }
+
+    match errors.is_empty() {
+      true => None,
+      false => Some(errors.join("; ")),
+    }
+  } // end fn run()
+
+  /// Gets the report of the rows each rule would remove now, counted in dry-run mode
+  /// whatever the mode of the scheduled runs, along with the metrics of those runs.
+  ///
+  /// # Arguments
+  ///
+  /// * `store`: Data store whose data is counted.
+  pub async fn report(
+    &self,
+    store: &PostgresStore,
+  ) -> RetentionReport {
+    let mut rules = Vec::new();
+
+    for rule in RetentionRule::ALL {
+      let retention_days = self.policies.days(rule);
+      let pending_rows = match retention_days {
+        Some(days) => match store.apply_retention_rule(rule, days, true).await {
+          Ok(rows) => Some(rows),
+          Err(err) => {
+            tracing::event!(
+              tracing::Level::ERROR,
+              "Cannot count the rows of the retention rule {}. {}",
+              rule.name(),
+              err
+            );
+            None
+          }
+        },
+        None => None,
+      };
+
+      rules.push(RuleReport {
+        rule,
+        retention_days,
+        pending_rows,
+        metrics: self.metrics().get(&rule).cloned().unwrap_or_default(),
+      });
+    }
+
+    RetentionReport {
+      dry_run: self.dry_run,
+      rules,
+    }
+  } // end fn report()
+
+  /// Locks the metrics of the rules.
+  fn metrics(&self) -> MutexGuard<'_, BTreeMap<RetentionRule, RuleMetrics>> {
+    // The metrics are only updated whole, so they are still usable after a panic while
+    // they were locked.
+    self.metrics.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+  } // end fn metrics()
+}
@@ -1,22 +1,103 @@
 use warp::hyper::StatusCode;
 
-use crate::{store::Store, types::answer::NewAnswer};
+use crate::{
+  store::QuestionStore,
+  types::answer::{AddedAnswer, AnswerForm},
+};
+
+/// Minimum trigram similarity between two answers to consider them duplicates.
+const DUPLICATE_SIMILARITY_THRESHOLD: f32 = 0.7;
+
+/// Maximum amount of possible duplicates replied when adding an answer.
+const MAX_DUPLICATE_CANDIDATES: i32 = 5;
 
 /// Adds a new answer with the given parameters to a data store.
 ///
+/// The answer is replied along with the existing answers of the same question with a
+/// similar content, as possible duplicates the client may warn about. A form without
+/// some fields is rejected with a `MISSING_FIELDS` error that lists them.
+///
 /// # Arguments
 ///
 /// * `store`: Data store for where answer will be saved.
-/// * `answer`: New answer to be added to the datastore.
-pub async fn add_answer(
-  store: Store,
-  answer: NewAnswer,
+/// * `form`: Form fields of the new answer to be added to the datastore.
+pub async fn add_answer<S: QuestionStore>(
+  store: S,
+  form: AnswerForm,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+  let answer = form.into_answer()?;
+  answer.validate()?;
+
+  // The duplicates are found first, so the new answer is not one of them.
+  let possible_duplicates = store
+    .find_similar_answers(
+      answer.question_id,
+      &answer.content,
+      DUPLICATE_SIMILARITY_THRESHOLD,
+      MAX_DUPLICATE_CANDIDATES,
+    )
+    .await?;
+
   match store.add_answer(answer).await {
-    Ok(_) => Ok(warp::reply::with_status(
-      "Answer added",
+    Ok(answer) => Ok(warp::reply::with_status(
+      warp::reply::json(&AddedAnswer {
+        answer,
+        possible_duplicates,
+      }),
       StatusCode::CREATED,
     )),
     Err(err) => Err(warp::reject::custom(err)),
   }
 } // end fn add_answer()
+
+#[cfg(test)]
+mod tests {
+  use handle_errors::errors::QError;
+  use warp::Reply;
+
+  use super::*;
+  use crate::routes::testing::{json, store};
+  use crate::types::question::QuestionId;
+
+  #[tokio::test]
+  async fn rejects_an_answer_to_a_missing_question() {
+    let answer = AnswerForm {
+      content: Some("An answer".to_string()),
+      question_id: Some(QuestionId::new(9)),
+    };
+
+    let rej = add_answer(store(1), answer).await.err().unwrap();
+    assert!(matches!(rej.find::<QError>(), Some(QError::QuestionNotFound)));
+  }
+
+  #[tokio::test]
+  async fn adds_a_possible_duplicate_and_reports_it() {
+    let store = store(1);
+    let answer = AnswerForm {
+      content: Some("An answer".to_string()),
+      question_id: Some(QuestionId::new(1)),
+    };
+    let first = json(add_answer(store.clone(), answer.clone()).await.unwrap()).await;
+    assert!(first["possible_duplicates"].as_array().unwrap().is_empty());
+
+    let reply = add_answer(store, answer).await.unwrap().into_response();
+    assert_eq!(reply.status(), StatusCode::CREATED);
+    let second = json(reply).await;
+    assert_ne!(second["answer"]["id"], first["answer"]["id"]);
+    assert_eq!(second["possible_duplicates"][0]["id"], first["answer"]["id"]);
+  }
+  #[tokio::test]
+  async fn replies_400_listing_the_missing_fields() {
+    let form: AnswerForm = serde_urlencoded::from_str("").unwrap();
+
+    let rej = add_answer(store(1), form).await.err().unwrap();
+    let reply = handle_errors::errors::return_error(rej).await.unwrap();
+    assert_eq!(reply.status(), StatusCode::BAD_REQUEST);
+    let body = json(reply).await;
+    assert_eq!(body["error"]["code"], "MISSING_FIELDS");
+    assert_eq!(
+      body["error"]["details"]["missing_fields"],
+      serde_json::json!(["content", "question_id"])
+    );
+  }
+}
@@ -0,0 +1,70 @@
+use handle_errors::errors::QError;
+use serde::de::DeserializeOwned;
+use warp::{reject::PayloadTooLarge, Filter, Rejection};
+
+/// Media type of the JSON bodies.
+const JSON_MEDIA_TYPE: &str = "application/json";
+
+/// Media type of the form bodies.
+const FORM_MEDIA_TYPE: &str = "application/x-www-form-urlencoded";
+
+/// Creates a filter that extracts a JSON body as a `T`.
+///
+/// Unlike `warp::body::json()`, which also takes bodies without a `Content-Type`, the
+/// requests must send the body as `application/json`, or they are rejected with a
+/// `QError::UnsupportedMediaType` error.
+pub fn json_body<T: DeserializeOwned + Send>(
+) -> impl Filter<Extract = (T,), Error = Rejection> + Copy {
+  content_type(JSON_MEDIA_TYPE).and(warp::body::json())
+} // end fn json_body()
+
+/// Creates a filter that extracts a form body as a `T`.
+///
+/// Unlike `warp::body::form()`, which takes bodies of any `Content-Type`, the requests
+/// must send the body as `application/x-www-form-urlencoded`, or they are rejected with
+/// a `QError::UnsupportedMediaType` error.
+pub fn form_body<T: DeserializeOwned + Send>(
+) -> impl Filter<Extract = (T,), Error = Rejection> + Copy {
+  content_type(FORM_MEDIA_TYPE).and(warp::body::form())
+} // end fn form_body()
+
+/// Creates a filter that rejects the requests whose body is larger than the given
+/// size with a `QError::PayloadTooLarge` error, and the ones without a
+/// `Content-Length` header, since their size is not known beforehand.
+///
+/// # Arguments
+///
+/// * `max_bytes`: Largest size of the body allowed, in bytes.
+pub fn body_limit(max_bytes: u64) -> impl Filter<Extract = (), Error = Rejection> + Copy {
+  warp::body::content_length_limit(max_bytes).or_else(move |rej: Rejection| async move {
+    match rej.find::<PayloadTooLarge>() {
+      Some(_) => Err(warp::reject::custom(QError::PayloadTooLarge(max_bytes))),
+      None => Err(rej),
+    }
+  })
+} // end fn body_limit()
+
+/// Creates a filter that rejects the requests whose `Content-Type` is missing or is not
+/// the given media type. Parameters like `charset` are ignored.
+///
+/// # Arguments
+///
+/// * `expected`: Media type the body must be sent as.
+fn content_type(
+  expected: &'static str
+) -> impl Filter<Extract = (), Error = Rejection> + Copy {
+  warp::header::optional::<String>("content-type")
+    .and_then(move |value: Option<String>| async move {
+      let media_type = value
+        .as_deref()
+        .and_then(|value| value.split(';').next())
+        .map(str::trim);
+      match media_type {
+        Some(media_type) if media_type.eq_ignore_ascii_case(expected) => Ok(()),
+        _ => Err(warp::reject::custom(QError::UnsupportedMediaType(
+          expected.to_string(),
+        ))),
+      }
+    })
+    .untuple_one()
+} // end fn content_type()
@@ -0,0 +1,80 @@
+use warp::http::header::{HeaderValue, CACHE_CONTROL, CONTENT_TYPE};
+use warp::http::Response;
+use warp::hyper::{Body, StatusCode};
+
+use crate::{
+  store::PostgresStore,
+  templates::{survey_embed, SURVEY_EMBED_SCRIPT, SURVEY_EMBED_STYLE},
+  types::survey::SurveyId,
+};
+
+/// Name of the header with the Content Security Policy of a page.
+const CONTENT_SECURITY_POLICY: &str = "content-security-policy";
+
+/// Gets the widget page that renders a survey and posts its responses, to be embedded
+/// in the sites of the allowed origins of the survey.
+///
+/// # Arguments
+///
+/// * `id`: ID (unique identifier) of the survey.
+/// * `store`: Data store that contains all the surveys.
+pub async fn get_survey_embed(
+  id: SurveyId,
+  store: PostgresStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  let survey = match store.get_survey(id.value()).await {
+    Ok(survey) => survey,
+    Err(err) => return Err(warp::reject::custom(err)),
+  };
+
+  // Only the allowed origins can show the page in a frame.
+  let frame_ancestors = match survey.embed_origins.is_empty() {
+    true => "'none'".to_string(),
+    false => survey.embed_origins.join(" "),
+  };
+  let policy = format!(
+    "default-src 'none'; script-src 'self'; style-src 'self'; connect-src 'self'; \
+     base-uri 'none'; form-action 'none'; frame-ancestors {}",
+    frame_ancestors
+  );
+
+  let mut response = text_response(survey_embed(id), "text/html; charset=utf-8");
+  match HeaderValue::from_str(&policy) {
+    Ok(policy) => {
+      response.headers_mut().insert(CONTENT_SECURITY_POLICY, policy);
+    }
+    Err(_) => *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR,
+  }
+
+  Ok(response)
+} // end fn get_survey_embed()
+
+/// Gets the script of the survey widget.
+pub async fn get_embed_script() -> Result<impl warp::Reply, warp::Rejection> {
+  Ok(text_response(
+    SURVEY_EMBED_SCRIPT,
+    "text/javascript; charset=utf-8",
+  ))
+} // end fn get_embed_script()
+
+/// Gets the style sheet of the survey widget.
+pub async fn get_embed_style() -> Result<impl warp::Reply, warp::Rejection> {
+  Ok(text_response(SURVEY_EMBED_STYLE, "text/css; charset=utf-8"))
+} // end fn get_embed_style()
+
+/// Creates a reply with the given text body and media type, that is not cached.
+///
+/// # Arguments
+///
+/// * `body`: Text of the body.
+/// * `media_type`: Media type for the `Content-Type` header.
+fn text_response(
+  body: impl Into<Body>,
+  media_type: &'static str,
+) -> Response<Body> {
+  let mut response = Response::new(body.into());
+  let headers = response.headers_mut();
+  headers.insert(CONTENT_TYPE, HeaderValue::from_static(media_type));
+  headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+  response
+} // end fn text_response()
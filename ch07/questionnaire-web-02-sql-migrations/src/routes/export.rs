@@ -0,0 +1,209 @@
+use futures::TryStreamExt;
+use tokio::sync::oneshot;
+use tracing::{event, Level};
+use warp::http::header::{HeaderValue, CONTENT_DISPOSITION, CONTENT_TYPE};
+use warp::http::Response;
+use warp::hyper::body::{Bytes, Sender};
+use warp::hyper::{Body, StatusCode};
+
+use handle_errors::errors::QError;
+
+use crate::{
+  routes::question::ROW_BUFFER_SIZE,
+  store::{PostgresStore, QuestionStore},
+  types::{
+    export::{validate_import, ExportFormat, ExportRow, CSV_HEADER, CSV_MEDIA_TYPE},
+    format::JSON_MEDIA_TYPE,
+    query::{ExportParams, ImportParams},
+  },
+};
+
+/// Exports all the questions, joined with their answers, as a JSON or CSV file chosen
+/// with the `format` query parameter, to back up or migrate the data store.
+///
+/// The rows are streamed into the body as they are fetched, so the whole data store is
+/// never held in memory.
+///
+/// # Arguments
+///
+/// * `params`: Query string parameters of the request.
+/// * `store`: Data store that contains all the questions and answers.
+pub async fn get_export<S: QuestionStore>(
+  params: ExportParams,
+  store: S,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  let format = params.format.unwrap_or_default();
+
+  let (first_row_sender, first_row) = oneshot::channel();
+  let (body_sender, body) = Body::channel();
+  tokio::spawn(stream_export(store, format, first_row_sender, body_sender));
+
+  match first_row.await {
+    Ok(Ok(())) => {
+      let mut response = Response::new(body);
+      let media_type = match format {
+        ExportFormat::Json => JSON_MEDIA_TYPE,
+        ExportFormat::Csv => CSV_MEDIA_TYPE,
+      };
+      response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(media_type));
+      if let Ok(disposition) =
+        HeaderValue::from_str(&format!("attachment; filename=\"{}\"", format.file_name()))
+      {
+        response.headers_mut().insert(CONTENT_DISPOSITION, disposition);
+      }
+      Ok(response)
+    }
+    Ok(Err(err)) => Err(warp::reject::custom(err)),
+    Err(_) => Err(warp::reject::reject()),
+  }
+} // end fn get_export()
+
+/// Restores the questions and answers of a JSON export, keeping their IDs.
+///
+/// With the `dry_run=true` query parameter, nothing is changed and only the report of
+/// what would change is replied.
+///
+/// # Arguments
+///
+/// * `params`: Query string parameters of the request.
+/// * `store`: Data store for where the questions and answers will be restored.
+/// * `rows`: Rows of the JSON export.
+pub async fn add_import(
+  params: ImportParams,
+  store: PostgresStore,
+  rows: Vec<ExportRow>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  let dry_run = params.dry_run.unwrap_or_default();
+  validate_import(&rows)?;
+
+  match store.import(rows, dry_run).await {
+    Ok(report) => Ok(warp::reply::with_status(
+      warp::reply::json(&report),
+      StatusCode::OK,
+    )),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn add_import()
+
+/// Writes all the rows of the export into an HTTP body in the given format.
+///
+/// Like the question listing, serialized rows are buffered up to `ROW_BUFFER_SIZE`
+/// bytes, and sending waits until the client has consumed the previous chunk.
+///
+/// # Arguments
+///
+/// * `store`: Data store that contains all the questions and answers.
+/// * `format`: Format of the export.
+/// * `first_row`: Channel to report whether the first row could be fetched, so a
+///   database error can still be returned as an error reply.
+/// * `body`: Sender of the HTTP body chunks.
+async fn stream_export<S: QuestionStore>(
+  store: S,
+  format: ExportFormat,
+  first_row: oneshot::Sender<Result<(), QError>>,
+  mut body: Sender,
+) {
+  let mut rows = store.export_rows();
+
+  let mut next = match rows.try_next().await {
+    Ok(row) => {
+      let _ = first_row.send(Ok(()));
+      row
+    }
+    Err(err) => {
+      let _ = first_row.send(Err(err));
+      return;
+    }
+  };
+
+  let mut buffer: Vec<u8> = Vec::with_capacity(ROW_BUFFER_SIZE);
+  match format {
+    ExportFormat::Json => buffer.push(b'['),
+    ExportFormat::Csv => buffer.extend_from_slice(CSV_HEADER.as_bytes()),
+  }
+  let mut is_first = true;
+
+  while let Some(row) = next {
+    match format {
+      ExportFormat::Json => {
+        if !is_first {
+          buffer.push(b',');
+        }
+        if let Err(err) = serde_json::to_writer(&mut buffer, &row) {
+          event!(Level::ERROR, "{:?}", err);
+          body.abort();
+          return;
+        }
+      }
+      ExportFormat::Csv => row.write_csv(&mut buffer),
+    }
+    is_first = false;
+
+    if buffer.len() >= ROW_BUFFER_SIZE {
+      let chunk = std::mem::replace(&mut buffer, Vec::with_capacity(ROW_BUFFER_SIZE));
+      // The client went away, so stop fetching rows.
+      if body.send_data(Bytes::from(chunk)).await.is_err() {
+        return;
+      }
+    }
+
+    next = match rows.try_next().await {
+      Ok(row) => row,
+      Err(_) => {
+        // The error was already logged by the store, and the status code was already
+        // sent, so the only option left is to cut the body short.
+        body.abort();
+        return;
+      }
+    };
+  }
+
+  if format == ExportFormat::Json {
+    buffer.push(b']');
+  }
+  let _ = body.send_data(Bytes::from(buffer)).await;
+} // end fn stream_export()
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::routes::question::delete_question;
+  use crate::routes::testing::{json, store};
+  use crate::types::question::QuestionId;
+
+  #[tokio::test]
+  async fn exports_a_deleted_question_with_its_date() {
+    let store = store(2);
+    delete_question(QuestionId::new(2), store.clone()).await.unwrap();
+
+    let reply = get_export(ExportParams::default(), store).await.unwrap();
+    let rows = json(reply).await;
+    let rows = rows.as_array().unwrap();
+    assert!(rows[0]["question_deleted_at"].is_null());
+    assert_eq!(rows[1]["question_id"], 2);
+    assert!(rows[1]["question_deleted_at"].is_string());
+  }
+
+  #[tokio::test]
+  async fn exports_a_csv_file_with_a_header() {
+    let params = ExportParams {
+      format: Some(ExportFormat::Csv),
+    };
+    let reply = get_export(params, store(2)).await.unwrap();
+    let response = warp::Reply::into_response(reply);
+    assert_eq!(response.headers()[CONTENT_TYPE], CSV_MEDIA_TYPE);
+    assert_eq!(
+      response.headers()[CONTENT_DISPOSITION],
+      "attachment; filename=\"questionnaire-export.csv\""
+    );
+
+    let body = warp::hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let body = String::from_utf8(body.to_vec()).unwrap();
+    let records = body.strip_prefix(CSV_HEADER).unwrap();
+    assert!(records.starts_with("1,Question 1,Content,,open,text,"));
+    assert!(records.contains("\r\n2,Question 2,Content,,open,text,"));
+    assert!(records.ends_with("\r\n"));
+  }
+}
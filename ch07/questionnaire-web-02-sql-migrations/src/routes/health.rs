@@ -0,0 +1,58 @@
+use warp::hyper::StatusCode;
+
+use handle_errors::errors::log_with_reference;
+
+use crate::{store::PostgresStore, tasks::TaskMonitor, types::health::Health};
+
+/// Gets the health of the application, checking that the database replies to queries
+/// and that the background tasks are still running.
+///
+/// The reply is `503 Service Unavailable` when the database does not reply in time or a
+/// background task stopped running, so load balancers and orchestrators can stop
+/// sending requests to this instance. The error of the database is only logged, and
+/// the reply has its reference.
+///
+/// # Arguments
+///
+/// * `tasks`: Monitor of the background tasks.
+/// * `store`: Data store whose database is checked.
+pub async fn get_health(
+  tasks: TaskMonitor,
+  store: PostgresStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  let (pool, replica_pool) = store.pool_status();
+  let tasks = tasks.statuses();
+  let tasks_running = tasks.iter().all(|task| task.running);
+  let (health, status) = match store.health_check().await {
+    Ok(latency) => (
+      Health {
+        database_up: true,
+        latency_ms: Some(latency.as_secs_f64() * 1000.0),
+        error: None,
+        pool,
+        replica_pool,
+        tasks,
+      },
+      match tasks_running {
+        true => StatusCode::OK,
+        false => StatusCode::SERVICE_UNAVAILABLE,
+      },
+    ),
+    Err(err) => (
+      Health {
+        database_up: false,
+        latency_ms: None,
+        error: Some(format!(
+          "Database unavailable. Report the reference {}.",
+          log_with_reference(&err)
+        )),
+        pool,
+        replica_pool,
+        tasks,
+      },
+      StatusCode::SERVICE_UNAVAILABLE,
+    ),
+  };
+
+  Ok(warp::reply::with_status(warp::reply::json(&health), status))
+} // end fn get_health()
@@ -0,0 +1,62 @@
+use handle_errors::errors::ErrorReply;
+use warp::http::header::{HeaderValue, ALLOW, CONTENT_TYPE};
+use warp::http::{Method, Response};
+use warp::hyper::{Body, StatusCode};
+use warp::{Filter, Rejection};
+
+/// Creates a filter that matches `GET` and `HEAD` requests.
+///
+/// The server sends no body for `HEAD` requests, so the `GET` handlers answer both.
+pub fn get_or_head() -> impl Filter<Extract = (), Error = Rejection> + Copy {
+  warp::get().or(warp::head()).unify()
+} // end fn get_or_head()
+
+/// Creates a filter that answers the requests to a path whose method has no route.
+///
+/// `OPTIONS` requests are replied with `204 No Content`, and the other methods not
+/// allowed with `405 Method Not Allowed`. Both replies list the allowed methods in the
+/// `Allow` header. Requests with an allowed method are rejected as not found, so the
+/// rejections of their routes are replied instead.
+///
+/// # Arguments
+///
+/// * `allowed`: Methods that have a route for the path.
+pub fn method_fallback(
+  allowed: &'static [Method]
+) -> impl Filter<Extract = (Response<Body>,), Error = Rejection> + Clone {
+  warp::method().and_then(move |method: Method| async move {
+    let status = if method == Method::OPTIONS {
+      StatusCode::NO_CONTENT
+    } else if allowed.contains(&method) {
+      return Err(warp::reject::not_found());
+    } else {
+      StatusCode::METHOD_NOT_ALLOWED
+    };
+
+    let allow: Vec<&str> = allowed
+      .iter()
+      .chain(std::iter::once(&Method::OPTIONS))
+      .map(|method| method.as_str())
+      .collect();
+
+    let mut response = match status {
+      StatusCode::METHOD_NOT_ALLOWED => {
+        let body =
+          ErrorReply::new("METHOD_NOT_ALLOWED", "Method not allowed".to_string());
+        let mut response =
+          Response::new(Body::from(serde_json::to_vec(&body).unwrap_or_default()));
+        response
+          .headers_mut()
+          .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        response
+      }
+      _ => Response::new(Body::empty()),
+    };
+    *response.status_mut() = status;
+    if let Ok(allow) = HeaderValue::from_str(&allow.join(", ")) {
+      response.headers_mut().insert(ALLOW, allow);
+    }
+
+    Ok(response)
+  })
+} // end fn method_fallback()
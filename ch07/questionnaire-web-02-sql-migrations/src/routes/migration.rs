@@ -0,0 +1,17 @@
+use crate::store::PostgresStore;
+
+/// Gets the latest migration applied to the database and the phase of the schema
+/// transitions, to know when the builds that only know the old columns can be stopped
+/// and the old columns dropped.
+///
+/// # Arguments
+///
+/// * `store`: Data store whose database is checked.
+pub async fn get_migration_status(
+  store: PostgresStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  match store.migration_status().await {
+    Ok(status) => Ok(warp::reply::json(&status)),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn get_migration_status()
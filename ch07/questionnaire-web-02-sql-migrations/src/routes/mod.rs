@@ -1,2 +1,56 @@
 pub mod answer;
+pub mod body;
+pub mod embed;
+pub mod export;
+pub mod health;
+pub mod method;
+pub mod migration;
 pub mod question;
+pub mod redirect;
+pub mod response;
+pub mod retention;
+pub mod suggest;
+pub mod survey;
+pub mod tag;
+
+/// Helpers shared by the tests of the routes.
+#[cfg(test)]
+pub mod testing {
+  use warp::hyper::body::to_bytes;
+  use warp::Reply;
+
+  use crate::store::InMemoryStore;
+  use crate::types::seed::{SeedQuestion, SeedQuestions};
+
+  /// Creates an in-memory data store with the given amount of questions, with IDs from
+  /// 1 on.
+  ///
+  /// # Arguments
+  ///
+  /// * `count`: Amount of questions.
+  pub fn store(count: i32) -> InMemoryStore {
+    let fixture: SeedQuestions = (1..=count)
+      .map(|id| {
+        let question = SeedQuestion {
+          id: id.to_string(),
+          title: format!("Question {}", id),
+          content: "Content".to_string(),
+          tags: None,
+          answers: Vec::new(),
+        };
+        (id.to_string(), question)
+      })
+      .collect();
+    InMemoryStore::new(fixture).expect("valid fixture")
+  } // end fn store()
+
+  /// Gets the JSON body of the reply of a handler.
+  ///
+  /// # Arguments
+  ///
+  /// * `reply`: Reply of the handler.
+  pub async fn json(reply: impl Reply) -> serde_json::Value {
+    let body = to_bytes(reply.into_response().into_body()).await.expect("body");
+    serde_json::from_slice(&body).expect("JSON body")
+  } // end fn json()
+}
@@ -1,60 +1,504 @@
-use std::collections::HashMap;
+use futures::TryStreamExt;
+use tokio::sync::oneshot;
 use tracing::{event, instrument, Level};
-use warp::hyper::StatusCode;
+use warp::http::header::{
+  HeaderValue, CACHE_CONTROL, CONTENT_LANGUAGE, CONTENT_TYPE, ETAG, LOCATION,
+};
+use warp::http::Response;
+use warp::hyper::body::{Bytes, Sender};
+use warp::hyper::{Body, StatusCode};
 
-use handle_errors::errors::QError;
+use handle_errors::errors::{QError, SimilarQuestion};
 
 use crate::{
-  store::Store,
+  store::{PostgresStore, QuestionStore},
+  translator::Translations,
   types::{
-    pagination::{extract_pagination, Pagination},
-    question::{NewQuestion, Question},
+    format::{Format, JSON_MEDIA_TYPE},
+    pagination::{extract_pagination, Page, PageInfo, PageLimits},
+    query::{
+      extract_question_query, ChangesParams, ForceParams, QuestionDetailParams,
+      QuestionParams, QuestionQuery, RandomParams, SearchParams,
+    },
+    question::{NewQuestion, Question, QuestionId, QuestionPage, QuestionStatus},
   },
 };
 
+/// Path of the listing of questions, for the links to other pages.
+const QUESTIONS_PATH: &str = "/questions";
+
+/// Path of the search of questions, for the links to other pages.
+const SEARCH_PATH: &str = "/questions/search";
+
+/// Maximum amount of serialized bytes buffered before sending a chunk of the body.
+pub const ROW_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Minimum trigram similarity between two titles to consider the questions duplicates.
+const DUPLICATE_SIMILARITY_THRESHOLD: f32 = 0.6;
+
+/// Maximum amount of possible duplicates replied when adding a question.
+const MAX_DUPLICATE_CANDIDATES: i32 = 5;
+
+/// Represents how a streamed listing of questions is wrapped.
+#[derive(Debug, Clone)]
+enum Listing {
+  /// Keyset pagination, with the limit of the page: the questions are wrapped along
+  /// with the cursor of the next page.
+  Keyset(i32),
+  /// Offset pagination, or none, with the query string parameters of the request: the
+  /// questions are wrapped along with their total and the links to the pages around.
+  Offset(Vec<(String, String)>),
+} // end enum Listing
+
 /// Gets a set of questions from the given parameters and data store.
 ///
+/// The questions are replied in an object along with their total and the links to
+/// the next and previous pages, or along with the cursor of the next page for keyset
+/// pagination.
+///
 /// # Arguments
 ///
 /// * `params`: Parameters to filter the set of questions to retrieve.
+/// * `accept`: Value of the `Accept` header, used to choose the response format.
+/// * `limits`: Page sizes allowed.
 /// * `store`: Data store that contains all the questions.
-#[instrument]
-pub async fn get_questions(
-  params: HashMap<String, String>,
-  store: Store,
+#[instrument(skip(store))]
+pub async fn get_questions<S: QuestionStore>(
+  params: QuestionParams,
+  accept: Option<String>,
+  limits: PageLimits,
+  store: S,
 ) -> Result<impl warp::Reply, warp::Rejection> {
   event!(target: "questionnaire web api", Level::INFO, "querying questions");
-  // Default pagination
-  let mut pagination: Pagination = Pagination::default();
+  let link_params = params.pairs.clone();
+  // Create the listing parameters, including the pagination, from the HTTP parameters.
+  let query: QuestionQuery = extract_question_query(params, &limits)?;
+  event!(Level::INFO, pagination = query.pagination.limit.is_some());
+
+  let listing = match query.pagination.after.and(query.pagination.limit) {
+    Some(limit) => Listing::Keyset(limit),
+    None => Listing::Offset(link_params),
+  };
+
+  // MessagePack arrays need their length up front, so the page is collected first.
+  if Format::from_accept(accept.as_deref()) == Format::MessagePack {
+    let rows = store
+      .get_questions(query.clone())
+      .try_collect::<Vec<(Question, i64)>>()
+      .await
+      .map_err(warp::reject::custom)?;
+    let total = match rows.first() {
+      Some((_, total)) => *total,
+      None => empty_page_total(&store, &query).await?,
+    };
+    let questions: Vec<Question> =
+      rows.into_iter().map(|(question, _)| question).collect();
+
+    return Ok(match listing {
+      Listing::Keyset(limit) => {
+        Format::MessagePack.reply(&QuestionPage::new(questions, limit), StatusCode::OK)
+      }
+      Listing::Offset(params) => Format::MessagePack.reply(
+        &Page {
+          items: questions,
+          info: PageInfo::new(QUESTIONS_PATH, &params, &query.pagination, total),
+        },
+        StatusCode::OK,
+      ),
+    });
+  }
+
+  // The rows are written into the body by a separate task, so the reply can be
+  // returned while the remaining questions are still being fetched.
+  let (first_row_sender, first_row) = oneshot::channel();
+  let (body_sender, body) = Body::channel();
+  let task = tokio::spawn(stream_questions(
+    store,
+    query,
+    listing,
+    first_row_sender,
+    body_sender,
+  ));
+
+  match first_row.await {
+    Ok(Ok(())) => {
+      let mut response = Response::new(body);
+      response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(JSON_MEDIA_TYPE));
+      Ok(response)
+    }
+    Ok(Err(err)) => Err(warp::reject::custom(err)),
+    // The task dropped the sender without a reply, so it panicked or was cancelled.
+    Err(_) => {
+      let cause = match task.await {
+        Err(err) => err.to_string(),
+        Ok(()) => "the task ended without the first row".to_string(),
+      };
+      Err(warp::reject::custom(QError::Internal(format!(
+        "Cannot stream the questions. {}",
+        cause
+      ))))
+    }
+  }
+}
+
+/// Gets the total of a listing whose page has no questions. The questions are counted
+/// along with each row, so a page past the last one fetches the first question to read
+/// the total from it.
+///
+/// # Arguments
+///
+/// * `store`: Data store that contains all the questions.
+/// * `query`: Pagination, filters and tags of the empty page.
+pub async fn empty_page_total<S: QuestionStore>(
+  store: &S,
+  query: &QuestionQuery,
+) -> Result<i64, QError> {
+  if query.pagination.offset <= 0 {
+    return Ok(0);
+  }
 
-  //
-  if !params.is_empty() {
-    event!(Level::INFO, pagination = true);
-    // Create the pagination object from the given HTTP parameters.
-    pagination = extract_pagination(params)?;
-  } else {
-    event!(Level::INFO, pagination = false);
+  let mut first_page = query.clone();
+  first_page.pagination.offset = 0;
+  first_page.pagination.limit = Some(1);
+  let first_row = store.get_questions(first_page).try_next().await?;
+
+  Ok(first_row.map_or(0, |(_, total)| total))
+} // end fn empty_page_total()
+
+/// Writes the questions of the given page into an HTTP body as a JSON object, with the
+/// questions as an array along with the total and the links of the page, or along
+/// with the cursor of the next page for keyset pagination.
+///
+/// Serialized rows are buffered up to `ROW_BUFFER_SIZE` bytes before being sent, and
+/// sending waits until the client has consumed the previous chunk, so the memory used
+/// per request stays bounded regardless of the page size.
+///
+/// # Arguments
+///
+/// * `store`: Data store that contains all the questions.
+/// * `query`: Pagination, filters and tags of the set of questions to retrieve.
+/// * `listing`: How the questions are wrapped.
+/// * `first_row`: Channel to report whether the first row could be fetched, so a
+///   database error can still be returned as an error reply.
+/// * `body`: Sender of the HTTP body chunks.
+async fn stream_questions<S: QuestionStore>(
+  store: S,
+  query: QuestionQuery,
+  listing: Listing,
+  first_row: oneshot::Sender<Result<(), QError>>,
+  mut body: Sender,
+) {
+  let mut questions = store.get_questions(query.clone());
+
+  let mut next = match questions.try_next().await {
+    Ok(row) => {
+      let _ = first_row.send(Ok(()));
+      row
+    }
+    Err(err) => {
+      let _ = first_row.send(Err(err));
+      return;
+    }
+  };
+
+  let mut buffer: Vec<u8> = Vec::with_capacity(ROW_BUFFER_SIZE);
+  match listing {
+    Listing::Keyset(_) => buffer.extend_from_slice(br#"{"questions":["#),
+    Listing::Offset(_) => buffer.extend_from_slice(br#"{"items":["#),
+  }
+  let mut is_first = true;
+  let mut count: usize = 0;
+  let mut last_id: Option<QuestionId> = None;
+  let mut total: Option<i64> = None;
+
+  while let Some((question, question_total)) = next {
+    if !is_first {
+      buffer.push(b',');
+    }
+    is_first = false;
+    count += 1;
+    last_id = Some(question.id);
+    total = Some(question_total);
+
+    if let Err(err) = serde_json::to_writer(&mut buffer, &question) {
+      event!(Level::ERROR, "{:?}", err);
+      body.abort();
+      return;
+    }
+
+    if buffer.len() >= ROW_BUFFER_SIZE {
+      let chunk = std::mem::replace(&mut buffer, Vec::with_capacity(ROW_BUFFER_SIZE));
+      // The client went away, so stop fetching rows.
+      if body.send_data(Bytes::from(chunk)).await.is_err() {
+        return;
+      }
+    }
+
+    next = match questions.try_next().await {
+      Ok(row) => row,
+      Err(_) => {
+        // The error was already logged by the store, and the status code was already
+        // sent, so the only option left is to cut the body short.
+        body.abort();
+        return;
+      }
+    };
   }
 
+  buffer.extend_from_slice(b"],");
+  let trailer = match listing {
+    Listing::Keyset(limit) => serde_json::to_vec(&serde_json::json!({
+      "next_cursor": QuestionPage::next_cursor(last_id, count, limit)
+    })),
+    Listing::Offset(params) => {
+      let total = match total {
+        Some(total) => total,
+        None => match empty_page_total(&store, &query).await {
+          Ok(total) => total,
+          Err(_) => {
+            body.abort();
+            return;
+          }
+        },
+      };
+      let info = PageInfo::new(QUESTIONS_PATH, &params, &query.pagination, total);
+      serde_json::to_vec(&info)
+    }
+  };
+  // The fields of the trailer object are appended to the object with the questions.
+  match trailer {
+    Ok(trailer) => buffer.extend_from_slice(&trailer[1..]),
+    Err(err) => {
+      event!(Level::ERROR, "{:?}", err);
+      body.abort();
+      return;
+    }
+  }
+  let _ = body.send_data(Bytes::from(buffer)).await;
+} // end fn stream_questions()
+
+/// Searches the questions matching the keywords of the `q` parameter, most relevant first.
+///
+/// # Arguments
+///
+/// * `params`: Parameters with the keywords and, optionally, the pagination.
+/// * `limits`: Page sizes allowed.
+/// * `store`: Data store that contains all the questions.
+pub async fn search_questions(
+  params: SearchParams,
+  limits: PageLimits,
+  store: PostgresStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  let keywords = match &params.q {
+    Some(keywords) if !keywords.trim().is_empty() => keywords.clone(),
+    _ => return Err(warp::reject::custom(QError::MissingParameters)),
+  };
+
+  let pagination = extract_pagination(params.offset, params.limit, &limits)?;
+
   match store
-    .get_questions(pagination.offset, pagination.limit)
+    .search_questions(&keywords, pagination.offset, pagination.limit)
     .await
   {
-    Ok(questions) => Ok(warp::reply::json(&questions)),
+    Ok((questions, total)) => {
+      let link_params = params.pairs;
+      Ok(warp::reply::with_status(
+        warp::reply::json(&Page {
+          items: questions,
+          info: PageInfo::new(SEARCH_PATH, &link_params, &pagination, total),
+        }),
+        StatusCode::OK,
+      ))
+    }
     Err(err) => Err(warp::reject::custom(err)),
   }
-}
+} // end fn search_questions()
+
+/// Gets a random question, optionally among the ones with the given tag, like for a
+/// quiz. The reply is not cached, so each request gets a new question.
+///
+/// # Arguments
+///
+/// * `params`: Parameters with the tag of the question, if any.
+/// * `store`: Data store that contains all the questions.
+pub async fn get_random_question(
+  params: RandomParams,
+  store: PostgresStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  match store.get_random_question(params.tag.as_deref()).await {
+    Ok(question) => Ok(warp::reply::with_header(
+      warp::reply::json(&question),
+      CACHE_CONTROL,
+      "no-store",
+    )),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn get_random_question()
+
+/// Gets the IDs of the questions created, updated and deleted since the cursor of a
+/// previous reply, or since the start, along with the cursor to get the next changes
+/// with. Clients keep their copy in sync by fetching the created and updated questions
+/// and dropping the deleted ones.
+///
+/// # Arguments
+///
+/// * `params`: Parameters with the cursor of a previous reply, if any.
+/// * `store`: Data store that contains all the questions.
+pub async fn get_question_changes(
+  params: ChangesParams,
+  store: PostgresStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  match store.get_question_changes(params.since.unwrap_or_default()).await {
+    Ok(changes) => Ok(warp::reply::with_header(
+      warp::reply::json(&changes),
+      CACHE_CONTROL,
+      "no-store",
+    )),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn get_question_changes()
+
+/// Gets the question with the given ID from the data store.
+///
+/// When the question is not found but was replaced by another one, e.g. after merging
+/// duplicates, the request is redirected to the replacing question with a
+/// `301 Moved Permanently` status, so the old links keep working.
+///
+/// With the `translate` parameter, the title and content are replied translated into
+/// the given language, with the language in the `Content-Language` header. When the
+/// translator fails, the policy of `DEGRADATION_POLICIES` may reply them untranslated,
+/// without the header.
+///
+/// # Arguments
+///
+/// * `id`: ID (unique identifier) of the question to retrieve.
+/// * `params`: Query string parameters of the request.
+/// * `accept`: Value of the `Accept` header, used to choose the response format.
+/// * `translations`: Translations of the questions, if a translator is set up.
+/// * `store`: Data store that contains all the questions.
+pub async fn get_question<S: QuestionStore>(
+  id: QuestionId,
+  params: QuestionDetailParams,
+  accept: Option<String>,
+  translations: Option<Translations>,
+  store: S,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  let mut question = match store.get_question_by_id(id.value()).await {
+    Ok(question) => question,
+    Err(QError::QuestionNotFound) => {
+      return match store.get_question_redirect(id.value()).await? {
+        Some(target_id) => Ok(redirect_question(target_id, &params)),
+        None => Err(warp::reject::custom(QError::QuestionNotFound)),
+      };
+    }
+    Err(err) => return Err(warp::reject::custom(err)),
+  };
+
+  let mut translated = false;
+  if let Some(language) = &params.translate {
+    let translations = translations.ok_or(QError::TranslationUnavailable)?;
+    (question, translated) = translations.localize(question, language).await?;
+  }
+
+  let mut response =
+    Format::from_accept(accept.as_deref()).reply(&question, StatusCode::OK);
+  if let Ok(etag) = HeaderValue::from_str(&format!("\"{}\"", question.version)) {
+    response.headers_mut().insert(ETAG, etag);
+  }
+  let language = params.translate.filter(|_| translated);
+  if let Some(language) = language.and_then(|language| language.parse().ok()) {
+    response.headers_mut().insert(CONTENT_LANGUAGE, language);
+  }
+  Ok(response)
+} // end fn get_question()
+
+/// Gets the `301 Moved Permanently` reply that redirects a request for a replaced
+/// question to the question that replaced it, keeping the query string parameters.
+///
+/// # Arguments
+///
+/// * `target_id`: ID (unique identifier) of the question that replaced it.
+/// * `params`: Query string parameters of the request.
+fn redirect_question(
+  target_id: i32,
+  params: &QuestionDetailParams,
+) -> Response<Body> {
+  let mut location = format!("{}/{}", QUESTIONS_PATH, target_id);
+  if let Some(language) = &params.translate {
+    location.push_str(&format!("?translate={}", language));
+  }
+
+  let mut response = Response::new(Body::empty());
+  *response.status_mut() = StatusCode::MOVED_PERMANENTLY;
+  if let Ok(location) = HeaderValue::from_str(&location) {
+    response.headers_mut().insert(LOCATION, location);
+  }
+  response
+} // end fn redirect_question()
+
+/// Gets the surveys that link the question with the given ID, along with the position
+/// and required flag the question has in each one.
+///
+/// # Arguments
+///
+/// * `id`: ID (unique identifier) of the question.
+/// * `store`: Data store that contains all the questions and surveys.
+pub async fn get_question_surveys(
+  id: QuestionId,
+  store: PostgresStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  match store.get_question_surveys(id.value()).await {
+    Ok(surveys) => Ok(warp::reply::with_status(
+      warp::reply::json(&surveys),
+      StatusCode::OK,
+    )),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn get_question_surveys()
 
 /// Adds a new question to the given data store.
 ///
+/// When existing questions have a similar title, the question is not added and they are
+/// replied as the details of a `POSSIBLE_DUPLICATES` error, unless the `force=true`
+/// query parameter is given.
+///
 /// # Arguments
 ///
+/// * `params`: Query string parameters of the request.
 /// * `store`: Data store that contains all the questions.
 /// * `question`: Question to add to the data store.
-pub async fn add_question(
-  store: Store,
+pub async fn add_question<S: QuestionStore>(
+  params: ForceParams,
+  store: S,
   question: NewQuestion,
 ) -> Result<impl warp::Reply, warp::Rejection> {
+  let force = params.force.unwrap_or_default();
+
+  let question = question.validate()?;
+
+  if !force {
+    let duplicates = store
+      .find_similar_questions(
+        &question.title,
+        DUPLICATE_SIMILARITY_THRESHOLD,
+        MAX_DUPLICATE_CANDIDATES,
+      )
+      .await?;
+
+    if !duplicates.is_empty() {
+      let duplicates = duplicates
+        .into_iter()
+        .map(|duplicate| SimilarQuestion {
+          id: duplicate.id.value(),
+          title: duplicate.title,
+        })
+        .collect();
+      return Err(warp::reject::custom(QError::PossibleDuplicates(duplicates)));
+    }
+  }
+
   match store.add_question(question).await {
     Ok(questions) => Ok(warp::reply::with_status(
       warp::reply::json(&questions),
@@ -66,17 +510,25 @@ pub async fn add_question(
 
 /// Updates an existing question with the given the ID and data store.
 ///
+/// The update must give the version of the question it is based on, in the `If-Match`
+/// header or in the `version` field, so it does not overwrite a newer update.
+///
 /// # Arguments
 ///
 /// * `id`: ID (unique identifier) of the question to be updated.
+/// * `if_match`: Value of the `If-Match` header, with the version of the question.
 /// * `store`: Data store that contains all the questions.
 /// * `question`: Question to add to the data store.
-pub async fn update_question(
-  id: i32,
-  store: Store,
+pub async fn update_question<S: QuestionStore>(
+  id: QuestionId,
+  if_match: Option<String>,
+  store: S,
   question: Question,
 ) -> Result<impl warp::Reply, warp::Rejection> {
-  match store.update_question(question, id).await {
+  let version = question_version(if_match.as_deref(), &question)?;
+  question.validate()?;
+
+  match store.update_question(question, id.value(), version).await {
     Ok(questions) => Ok(warp::reply::with_status(
       warp::reply::json(&questions),
       StatusCode::OK,
@@ -85,23 +537,277 @@ pub async fn update_question(
   }
 } // end fn update_question()
 
+/// Closes an existing question, so it does not accept new answers.
+///
+/// # Arguments
+///
+/// * `id`: ID (unique identifier) of the question to be closed.
+/// * `store`: Data store that contains all the questions.
+pub async fn close_question(
+  id: QuestionId,
+  store: PostgresStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  change_question_status(id.value(), QuestionStatus::Closed, store).await
+} // end fn close_question()
+
+/// Reopens an existing question, so it accepts new answers again.
+///
+/// # Arguments
+///
+/// * `id`: ID (unique identifier) of the question to be reopened.
+/// * `store`: Data store that contains all the questions.
+pub async fn reopen_question(
+  id: QuestionId,
+  store: PostgresStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  change_question_status(id.value(), QuestionStatus::Open, store).await
+} // end fn reopen_question()
+
+/// Archives an existing question, keeping it only for reference.
+///
+/// # Arguments
+///
+/// * `id`: ID (unique identifier) of the question to be archived.
+/// * `store`: Data store that contains all the questions.
+pub async fn archive_question(
+  id: QuestionId,
+  store: PostgresStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  change_question_status(id.value(), QuestionStatus::Archived, store).await
+} // end fn archive_question()
+
+/// Restores a deleted question that has not been purged yet.
+///
+/// # Arguments
+///
+/// * `id`: ID (unique identifier) of the question to be restored.
+/// * `store`: Data store that contains all the questions.
+pub async fn restore_question(
+  id: QuestionId,
+  store: PostgresStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  match store.restore_question(id.value()).await {
+    Ok(question) => Ok(warp::reply::with_status(
+      warp::reply::json(&question),
+      StatusCode::OK,
+    )),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn restore_question()
+
+/// Changes the status of an existing question and replies with the updated question.
+///
+/// # Arguments
+///
+/// * `id`: ID (unique identifier) of the question.
+/// * `status`: New status of the question.
+/// * `store`: Data store that contains all the questions.
+async fn change_question_status(
+  id: i32,
+  status: QuestionStatus,
+  store: PostgresStore,
+) -> Result<warp::reply::WithStatus<warp::reply::Json>, warp::Rejection> {
+  match store.set_question_status(id, status).await {
+    Ok(question) => Ok(warp::reply::with_status(
+      warp::reply::json(&question),
+      StatusCode::OK,
+    )),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn change_question_status()
+
 /// Deletes an existing question with the given the ID and data store.
 ///
 /// # Arguments
 ///
 /// * `id`: ID (unique identifier) of the question to be deleted.
 /// * `store`: Data store that contains all the questions.
-pub async fn delete_question(
-  id: i32,
-  store: Store,
+pub async fn delete_question<S: QuestionStore>(
+  id: QuestionId,
+  store: S,
 ) -> Result<impl warp::Reply, warp::Rejection> {
-  match store.delete_question(id).await {
-    Ok(1..=u64::MAX) => Ok(warp::reply::with_status(
-      format!("Question {} deleted.", id),
+  match store.delete_question(id.value()).await {
+    Ok(()) => Ok(warp::reply::with_status(
+      format!("Question {} deleted.", id.value()),
       StatusCode::OK,
     )),
-    Ok(0) => Err(warp::reject::custom(QError::QuestionNotFound)),
-
     Err(err) => Err(warp::reject::custom(err)),
   }
 } // fn delete_question()
+
+/// Gets the version of a question an update is based on, from the `If-Match` header
+/// or else from the `version` field of the question.
+///
+/// # Arguments
+///
+/// * `if_match`: Value of the `If-Match` header, with an entity tag such as `"3"`.
+/// * `question`: Question data of the update.
+pub fn question_version(
+  if_match: Option<&str>,
+  question: &Question,
+) -> Result<i32, QError> {
+  let if_match = match if_match {
+    Some(if_match) => if_match,
+    None if question.version > 0 => return Ok(question.version),
+    None => return Err(QError::MissingVersion),
+  };
+
+  let version = if_match.trim().trim_start_matches("W/").trim_matches('"');
+  match version.parse::<i32>() {
+    Ok(version) if version > 0 => Ok(version),
+    _ => Err(QError::InvalidParameter(format!(
+      "If-Match must be the version of the question, such as \"3\", found '{}'",
+      if_match
+    ))),
+  }
+} // end fn question_version()
+
+#[cfg(test)]
+mod tests {
+  use warp::Reply;
+
+  use super::*;
+  use crate::routes::testing::{json, store};
+  use crate::store::SqliteStore;
+  use crate::types::question::QuestionKind;
+
+  #[tokio::test]
+  async fn lists_a_page_with_the_total() {
+    let params = QuestionParams {
+      offset: Some(2),
+      limit: Some(2),
+      ..Default::default()
+    };
+    let reply = get_questions(params, None, PageLimits::default(), store(5))
+      .await
+      .unwrap();
+    let page = json(reply).await;
+
+    let ids: Vec<i64> = page["items"]
+      .as_array()
+      .unwrap()
+      .iter()
+      .map(|question| question["id"].as_i64().unwrap())
+      .collect();
+    assert_eq!(ids, vec![3, 4]);
+    assert_eq!(page["total"], 5);
+  }
+
+  #[tokio::test]
+  async fn gets_a_question_with_its_version() {
+    let params = QuestionDetailParams::default();
+    let reply = get_question(QuestionId::new(1), params, None, None, store(1))
+      .await
+      .unwrap();
+    let response = reply.into_response();
+
+    assert_eq!(response.headers()[ETAG], "\"1\"");
+  }
+
+  #[tokio::test]
+  async fn rejects_an_update_of_an_older_version() {
+    let store = store(1);
+    let question = store.get_question_by_id(1).await.unwrap();
+    update_question(QuestionId::new(1), None, store.clone(), question.clone())
+      .await
+      .unwrap();
+
+    let rej = update_question(QuestionId::new(1), None, store, question)
+      .await
+      .err()
+      .unwrap();
+    assert!(matches!(rej.find::<QError>(), Some(QError::VersionConflict(_))));
+  }
+
+  #[tokio::test]
+  async fn rejects_an_update_of_a_deleted_question() {
+    let store = store(1);
+    let question = store.get_question_by_id(1).await.unwrap();
+    delete_question(QuestionId::new(1), store.clone()).await.unwrap();
+
+    let rej = update_question(QuestionId::new(1), None, store, question)
+      .await
+      .err()
+      .unwrap();
+    assert!(matches!(rej.find::<QError>(), Some(QError::QuestionNotFound)));
+  }
+
+  #[tokio::test]
+  async fn rejects_a_possible_duplicate_with_the_error_envelope() {
+    let store = store(1);
+    let question = NewQuestion {
+      title: "question 1".to_string(),
+      content: "Content".to_string(),
+      tags: None,
+      kind: QuestionKind::Text,
+      options: None,
+      rating_max: None,
+      points: None,
+    };
+
+    let rej = add_question(ForceParams::default(), store.clone(), question.clone())
+      .await
+      .err()
+      .unwrap();
+    let reply = handle_errors::errors::return_error(rej).await.unwrap();
+    assert_eq!(reply.status(), StatusCode::CONFLICT);
+    let body = json(reply).await;
+    assert_eq!(body["error"]["code"], "POSSIBLE_DUPLICATES");
+    assert_eq!(body["error"]["details"]["duplicates"][0]["id"], 1);
+
+    let params = ForceParams { force: Some(true) };
+    let reply = add_question(params, store, question).await.unwrap();
+    assert_eq!(reply.into_response().status(), StatusCode::CREATED);
+  }
+
+  #[tokio::test]
+  async fn rejects_a_delete_of_a_deleted_question() {
+    let store = store(1);
+    delete_question(QuestionId::new(1), store.clone()).await.unwrap();
+
+    let rej = delete_question(QuestionId::new(1), store.clone())
+      .await
+      .err()
+      .unwrap();
+    assert!(matches!(rej.find::<QError>(), Some(QError::QuestionNotFound)));
+    assert!(store.get_question_by_id(1).await.is_err());
+  }
+
+  #[tokio::test]
+  async fn serves_the_same_routes_from_sqlite() {
+    let path = std::env::temp_dir().join(format!("{}.db", uuid::Uuid::new_v4()));
+    let store = SqliteStore::new(&format!("sqlite://{}", path.display()))
+      .await
+      .expect("SQLite database");
+    for id in 1..=3 {
+      let question = NewQuestion {
+        title: format!("Question {}", id),
+        content: "Content".to_string(),
+        tags: Some(vec!["rust".to_string()]),
+        kind: QuestionKind::Text,
+        options: None,
+        rating_max: None,
+        points: None,
+      };
+      add_question(ForceParams::default(), store.clone(), question)
+        .await
+        .unwrap();
+    }
+    delete_question(QuestionId::new(2), store.clone()).await.unwrap();
+
+    let params = QuestionParams {
+      limit: Some(1),
+      offset: Some(1),
+      ..Default::default()
+    };
+    let reply = get_questions(params, None, PageLimits::default(), store)
+      .await
+      .unwrap();
+    let page = json(reply).await;
+    std::fs::remove_file(path).ok();
+
+    assert_eq!(page["items"][0]["id"], 3);
+    assert_eq!(page["items"][0]["tags"][0], "rust");
+    assert_eq!(page["total"], 2);
+  }
+}
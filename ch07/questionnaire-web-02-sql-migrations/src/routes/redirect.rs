@@ -0,0 +1,54 @@
+use warp::hyper::StatusCode;
+
+use handle_errors::errors::QError;
+
+use crate::{
+  store::PostgresStore,
+  types::{question::QuestionId, redirect::NewRedirect},
+};
+
+/// Sets the redirect of the links to a replaced question, e.g. after merging
+/// duplicates, to the question that replaced it. The links are only redirected once the
+/// replaced question is not found, that is, after it is deleted.
+///
+/// # Arguments
+///
+/// * `id`: ID (unique identifier) of the replaced question.
+/// * `store`: Data store that contains all the questions.
+/// * `redirect`: Question that replaced it.
+pub async fn update_question_redirect(
+  id: QuestionId,
+  store: PostgresStore,
+  redirect: NewRedirect,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  match store
+    .set_question_redirect(id.value(), redirect.target_id.value())
+    .await
+  {
+    Ok(redirect) => Ok(warp::reply::with_status(
+      warp::reply::json(&redirect),
+      StatusCode::OK,
+    )),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn update_question_redirect()
+
+/// Removes the redirect of the links to a replaced question.
+///
+/// # Arguments
+///
+/// * `id`: ID (unique identifier) of the replaced question.
+/// * `store`: Data store that contains all the questions.
+pub async fn delete_question_redirect(
+  id: QuestionId,
+  store: PostgresStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  match store.delete_question_redirect(id.value()).await {
+    Ok(1..=u64::MAX) => Ok(warp::reply::with_status(
+      format!("Redirect of question {} deleted.", id.value()),
+      StatusCode::OK,
+    )),
+    Ok(0) => Err(warp::reject::custom(QError::RedirectNotFound)),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn delete_question_redirect()
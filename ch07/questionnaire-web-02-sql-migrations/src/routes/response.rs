@@ -0,0 +1,46 @@
+use warp::hyper::StatusCode;
+
+use crate::{
+  store::PostgresStore,
+  types::{question::QuestionId, response::NewResponse},
+};
+
+/// Adds a new response to a choice or rating question.
+///
+/// # Arguments
+///
+/// * `id`: ID (unique identifier) of the question.
+/// * `store`: Data store for where the response will be saved.
+/// * `response`: New response to be added to the datastore.
+pub async fn add_response(
+  id: QuestionId,
+  store: PostgresStore,
+  response: NewResponse,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  match store.add_response(id.value(), response).await {
+    Ok(()) => Ok(warp::reply::with_status(
+      "Response added",
+      StatusCode::CREATED,
+    )),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn add_response()
+
+/// Gets the aggregated responses to a choice or rating question.
+///
+/// # Arguments
+///
+/// * `id`: ID (unique identifier) of the question.
+/// * `store`: Data store that contains all the responses.
+pub async fn get_question_results(
+  id: QuestionId,
+  store: PostgresStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  match store.get_question_results(id.value()).await {
+    Ok(results) => Ok(warp::reply::with_status(
+      warp::reply::json(&results),
+      StatusCode::OK,
+    )),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn get_question_results()
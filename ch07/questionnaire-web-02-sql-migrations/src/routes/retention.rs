@@ -0,0 +1,23 @@
+use warp::http::header::CACHE_CONTROL;
+
+use crate::{retention::Retention, store::PostgresStore};
+
+/// Gets the dry-run report of the retention rules: the period of each rule, the rows
+/// it would remove now and the metrics of its scheduled runs. Nothing is removed.
+///
+/// # Arguments
+///
+/// * `retention`: Engine that applies the retention rules.
+/// * `store`: Data store whose data is counted.
+pub async fn get_retention_report(
+  retention: Retention,
+  store: PostgresStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  let report = retention.report(&store).await;
+
+  Ok(warp::reply::with_header(
+    warp::reply::json(&report),
+    CACHE_CONTROL,
+    "no-store",
+  ))
+} // end fn get_retention_report()
@@ -0,0 +1,60 @@
+use handle_errors::errors::QError;
+use warp::hyper::StatusCode;
+
+use crate::store::PostgresStore;
+use crate::types::query::SuggestParams;
+
+/// Amount of titles, and of tags, suggested when no limit is given.
+const DEFAULT_SUGGESTIONS: i32 = 5;
+
+/// Maximum amount of titles, and of tags, suggested. Larger limits are capped to it.
+const MAX_SUGGESTIONS: i32 = 10;
+
+/// Minimum amount of characters of the typed text, since shorter ones match too much.
+const MIN_SUGGEST_CHARS: usize = 2;
+
+/// Maximum amount of characters of the typed text. Longer ones are cut to it.
+const MAX_SUGGEST_CHARS: usize = 100;
+
+/// Gets the titles of questions and the names of tags that contain the typed text, to
+/// suggest them while typing.
+///
+/// # Arguments
+///
+/// * `params`: Parameters with the typed text and, optionally, the amount to suggest.
+/// * `store`: Data store that contains all the questions and tags.
+pub async fn get_suggestions(
+  params: SuggestParams,
+  store: PostgresStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  let text = match &params.q {
+    Some(text) if !text.trim().is_empty() => text.trim(),
+    _ => return Err(warp::reject::custom(QError::MissingParameters)),
+  };
+  if text.chars().count() < MIN_SUGGEST_CHARS {
+    return Err(warp::reject::custom(QError::InvalidParameter(format!(
+      "q must have at least {} characters, found '{}'",
+      MIN_SUGGEST_CHARS, text
+    ))));
+  }
+  let text: String = text.chars().take(MAX_SUGGEST_CHARS).collect();
+
+  let limit = match params.limit {
+    Some(limit) if limit < 1 => {
+      return Err(warp::reject::custom(QError::InvalidParameter(format!(
+        "limit must be at least 1, found {}",
+        limit
+      ))))
+    }
+    Some(limit) => limit.min(MAX_SUGGESTIONS),
+    None => DEFAULT_SUGGESTIONS,
+  };
+
+  match store.suggest(&text, limit).await {
+    Ok(suggestions) => Ok(warp::reply::with_status(
+      warp::reply::json(&suggestions),
+      StatusCode::OK,
+    )),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn get_suggestions()
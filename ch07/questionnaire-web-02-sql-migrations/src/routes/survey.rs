@@ -0,0 +1,210 @@
+use warp::hyper::StatusCode;
+use warp::Reply;
+
+use crate::{
+  store::PostgresStore,
+  types::{
+    draft::NewDraft,
+    invitation::{Invitations, NewInvitations},
+    survey::{NewSubmission, NewSurvey, SurveyId},
+  },
+};
+
+/// Adds a new survey with the given questions to the data store.
+///
+/// # Arguments
+///
+/// * `store`: Data store for where the survey will be saved.
+/// * `survey`: Survey to add to the data store.
+pub async fn add_survey(
+  store: PostgresStore,
+  survey: NewSurvey,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  survey.validate()?;
+
+  match store.add_survey(survey).await {
+    Ok(survey) => Ok(warp::reply::with_status(
+      warp::reply::json(&survey),
+      StatusCode::CREATED,
+    )),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn add_survey()
+
+/// Gets the survey with the given ID, along with its questions.
+///
+/// # Arguments
+///
+/// * `id`: ID (unique identifier) of the survey.
+/// * `store`: Data store that contains all the surveys.
+pub async fn get_survey(
+  id: SurveyId,
+  store: PostgresStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  match store.get_survey(id.value()).await {
+    Ok(survey) => Ok(warp::reply::with_status(
+      warp::reply::json(&survey),
+      StatusCode::OK,
+    )),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn get_survey()
+
+/// Adds the responses of a respondent to all the questions of a survey.
+///
+/// For quiz surveys, the score of the responses is replied.
+///
+/// # Arguments
+///
+/// * `id`: ID (unique identifier) of the survey.
+/// * `store`: Data store for where the responses will be saved.
+/// * `submission`: Responses to the questions of the survey.
+pub async fn add_survey_responses(
+  id: SurveyId,
+  store: PostgresStore,
+  submission: NewSubmission,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  match store.add_survey_submission(id.value(), submission).await {
+    Ok(Some(result)) => Ok(
+      warp::reply::with_status(warp::reply::json(&result), StatusCode::CREATED)
+        .into_response(),
+    ),
+    Ok(None) => Ok(
+      warp::reply::with_status("Responses added", StatusCode::CREATED).into_response(),
+    ),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn add_survey_responses()
+
+/// Generates single-use invitations to respond to an invitation-only survey.
+///
+/// # Arguments
+///
+/// * `id`: ID (unique identifier) of the survey.
+/// * `store`: Data store for where the invitations will be saved.
+/// * `invitations`: Amount of invitations to generate.
+pub async fn add_survey_invitations(
+  id: SurveyId,
+  store: PostgresStore,
+  invitations: NewInvitations,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  invitations.validate()?;
+
+  match store
+    .add_survey_invitations(id.value(), invitations.count)
+    .await
+  {
+    Ok(tokens) => Ok(warp::reply::with_status(
+      warp::reply::json(&Invitations {
+        survey_id: id,
+        tokens,
+      }),
+      StatusCode::CREATED,
+    )),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn add_survey_invitations()
+
+/// Gets the aggregated responses submitted to all the questions of a survey.
+///
+/// # Arguments
+///
+/// * `id`: ID (unique identifier) of the survey.
+/// * `store`: Data store that contains all the responses.
+pub async fn get_survey_results(
+  id: SurveyId,
+  store: PostgresStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  match store.get_survey_results(id.value()).await {
+    Ok(results) => Ok(warp::reply::with_status(
+      warp::reply::json(&results),
+      StatusCode::OK,
+    )),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn get_survey_results()
+
+/// Gets the scores of all the submissions to a quiz survey, along with how many
+/// submissions got each score.
+///
+/// # Arguments
+///
+/// * `id`: ID (unique identifier) of the survey.
+/// * `store`: Data store that contains all the submissions.
+pub async fn get_quiz_scores(
+  id: SurveyId,
+  store: PostgresStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  match store.get_quiz_scores(id.value()).await {
+    Ok(scores) => Ok(warp::reply::with_status(
+      warp::reply::json(&scores),
+      StatusCode::OK,
+    )),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn get_quiz_scores()
+
+/// Saves a new draft of the responses to a survey, to resume it later.
+///
+/// # Arguments
+///
+/// * `id`: ID (unique identifier) of the survey.
+/// * `store`: Data store for where the draft will be saved.
+/// * `draft`: Responses saved so far.
+pub async fn add_survey_draft(
+  id: SurveyId,
+  store: PostgresStore,
+  draft: NewDraft,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  match store.add_survey_draft(id.value(), draft).await {
+    Ok(draft) => Ok(warp::reply::with_status(
+      warp::reply::json(&draft),
+      StatusCode::CREATED,
+    )),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn add_survey_draft()
+
+/// Gets the draft of the responses to a survey with the given key.
+///
+/// # Arguments
+///
+/// * `id`: ID (unique identifier) of the survey.
+/// * `key`: Secret key of the draft.
+/// * `store`: Data store that contains all the drafts.
+pub async fn get_survey_draft(
+  id: SurveyId,
+  key: String,
+  store: PostgresStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  match store.get_survey_draft(id.value(), &key).await {
+    Ok(draft) => Ok(warp::reply::with_status(
+      warp::reply::json(&draft),
+      StatusCode::OK,
+    )),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn get_survey_draft()
+
+/// Replaces the responses of the draft of a survey with the given key.
+///
+/// # Arguments
+///
+/// * `id`: ID (unique identifier) of the survey.
+/// * `key`: Secret key of the draft.
+/// * `store`: Data store that contains all the drafts.
+/// * `draft`: Responses saved so far.
+pub async fn update_survey_draft(
+  id: SurveyId,
+  key: String,
+  store: PostgresStore,
+  draft: NewDraft,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  match store.update_survey_draft(id.value(), &key, draft).await {
+    Ok(draft) => Ok(warp::reply::with_status(
+      warp::reply::json(&draft),
+      StatusCode::OK,
+    )),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn update_survey_draft()
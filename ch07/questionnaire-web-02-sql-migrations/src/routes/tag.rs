@@ -0,0 +1,37 @@
+use warp::hyper::StatusCode;
+
+use crate::store::PostgresStore;
+
+/// Gets all the tags along with how many questions use each one.
+///
+/// # Arguments
+///
+/// * `store`: Data store that contains all the tags.
+pub async fn get_tags(store: PostgresStore) -> Result<impl warp::Reply, warp::Rejection> {
+  match store.get_tags().await {
+    Ok(tags) => Ok(warp::reply::with_status(
+      warp::reply::json(&tags),
+      StatusCode::OK,
+    )),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn get_tags()
+
+/// Gets the questions that have the given tag.
+///
+/// # Arguments
+///
+/// * `name`: Name of the tag.
+/// * `store`: Data store that contains all the questions.
+pub async fn get_tag_questions(
+  name: String,
+  store: PostgresStore,
+) -> Result<impl warp::Reply, warp::Rejection> {
+  match store.get_tag_questions(&name).await {
+    Ok(questions) => Ok(warp::reply::with_status(
+      warp::reply::json(&questions),
+      StatusCode::OK,
+    )),
+    Err(err) => Err(warp::reject::custom(err)),
+  }
+} // end fn get_tag_questions()
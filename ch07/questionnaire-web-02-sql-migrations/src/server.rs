@@ -0,0 +1,177 @@
+use std::backtrace::Backtrace;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::panic::AssertUnwindSafe;
+
+use futures::FutureExt;
+use handle_errors::errors::{ErrorDetails, ErrorReply};
+use uuid::Uuid;
+use warp::http::header::{HeaderValue, CONTENT_TYPE};
+use warp::http::{Request, Response, StatusCode};
+use warp::hyper::server::conn::AddrStream;
+use warp::hyper::service::{make_service_fn, service_fn, Service};
+use warp::hyper::{Body, Server};
+
+use crate::recorder::{self, Recording};
+use crate::types::limits::RouteLimits;
+
+tokio::task_local! {
+  /// Unique identifier (ID) of the request being replied, for the logs of its panics.
+  static REQUEST_ID: Uuid;
+}
+
+/// Represents the address of the client that sent a request, added to the extensions of
+/// the request, since `warp::addr::remote()` cannot get it from a `warp::service`.
+#[derive(Debug, Clone, Copy)]
+pub struct ClientAddress(pub SocketAddr);
+
+/// Logs the panics with their stack trace, along with the ID of the request being
+/// replied when they happen within one, instead of printing them to the standard
+/// error.
+pub fn log_panics() {
+  std::panic::set_hook(Box::new(|info| {
+    let backtrace = Backtrace::force_capture();
+    match REQUEST_ID.try_with(|request_id| *request_id) {
+      Ok(request_id) => tracing::event!(
+        tracing::Level::ERROR,
+        "Request {} panicked. {}\n{}",
+        request_id,
+        info,
+        backtrace
+      ),
+      Err(_) => tracing::event!(tracing::Level::ERROR, "Panic. {}\n{}", info, backtrace),
+    }
+  }));
+} // end fn log_panics()
+
+/// Serves the API like `warp::serve`, replying 503 Service Unavailable to the requests
+/// that take longer than the timeout of their route, and 500 Internal Server Error to
+/// the ones whose route panics, so the connection is not dropped.
+///
+/// # Arguments
+///
+/// * `service`: Service of the API routes, from `warp::service`.
+/// * `address`: IP address and port to listen on.
+/// * `limits`: Limits of the routes, whose timeouts are applied.
+/// * `recording`: File where the exchanges are recorded, if any.
+pub async fn serve<S>(
+  service: S,
+  address: impl Into<SocketAddr>,
+  limits: RouteLimits,
+  recording: Option<Recording>,
+) where
+  S: Service<Request<Body>, Response = Response<Body>, Error = Infallible>
+    + Clone
+    + Send
+    + 'static,
+  S::Future: Send,
+{
+  let make_service = make_service_fn(move |connection: &AddrStream| {
+    let client = ClientAddress(connection.remote_addr());
+    let service = service.clone();
+    let limits = limits.clone();
+    let recording = recording.clone();
+    async move {
+      Ok::<_, Infallible>(service_fn(move |mut request: Request<Body>| {
+        request.extensions_mut().insert(client);
+        reply_in_time(service.clone(), limits.clone(), recording.clone(), request)
+      }))
+    }
+  });
+
+  if let Err(err) = Server::bind(&address.into()).serve(make_service).await {
+    tracing::event!(tracing::Level::ERROR, "Server error. {}", err);
+  }
+} // end fn serve()
+
+/// Replies a request with the given service, recording the exchange if a recording is
+/// given, or replies 503 Service Unavailable when the response does not start within
+/// the timeout of the route of the request, or 500 Internal Server Error when the
+/// service panics, with a reference to the logs of the panic.
+///
+/// # Arguments
+///
+/// * `service`: Service of the API routes.
+/// * `limits`: Limits of the routes.
+/// * `recording`: File where the exchange is recorded, if any.
+/// * `request`: Request to reply.
+async fn reply_in_time<S>(
+  mut service: S,
+  limits: RouteLimits,
+  recording: Option<Recording>,
+  request: Request<Body>,
+) -> Result<Response<Body>, Infallible>
+where
+  S: Service<Request<Body>, Response = Response<Body>, Error = Infallible>,
+{
+  let method = request.method().clone();
+  let path = request.uri().path().to_string();
+  let timeout = limits.for_route(&path).timeout;
+  let request_id = Uuid::new_v4();
+
+  let reply = async {
+    match recording {
+      Some(recording) => recorder::record_exchange(service, recording, request).await,
+      None => {
+        futures::future::poll_fn(|context| service.poll_ready(context)).await?;
+        service.call(request).await
+      }
+    }
+  };
+
+  let reply = REQUEST_ID.scope(request_id, AssertUnwindSafe(reply).catch_unwind());
+
+  match tokio::time::timeout(timeout, reply).await {
+    Ok(Ok(response)) => response,
+    Ok(Err(_)) => {
+      tracing::event!(
+        tracing::Level::ERROR,
+        "{} {} panicked, replied with the reference {}.",
+        method,
+        path,
+        request_id
+      );
+      let mut body = ErrorReply::new(
+        "INTERNAL_ERROR",
+        format!("Internal server error. Report the reference {}.", request_id),
+      );
+      body.error.details = Some(ErrorDetails::Reference {
+        error_id: request_id.to_string(),
+      });
+      Ok(json_response(StatusCode::INTERNAL_SERVER_ERROR, &body))
+    }
+    Err(_) => {
+      tracing::event!(
+        tracing::Level::WARN,
+        "{} {} did not complete within {} seconds.",
+        method,
+        path,
+        timeout.as_secs()
+      );
+      let body = ErrorReply::new(
+        "REQUEST_TIMEOUT",
+        "Request did not complete in time.".to_string(),
+      );
+      Ok(json_response(StatusCode::SERVICE_UNAVAILABLE, &body))
+    }
+  }
+} // end fn reply_in_time()
+
+/// Gets a response with the given status and error as its JSON body.
+///
+/// # Arguments
+///
+/// * `status`: Status code of the response.
+/// * `body`: Error replied.
+fn json_response(
+  status: StatusCode,
+  body: &ErrorReply<'_>,
+) -> Response<Body> {
+  let mut response =
+    Response::new(Body::from(serde_json::to_vec(body).unwrap_or_default()));
+  *response.status_mut() = status;
+  response
+    .headers_mut()
+    .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+  response
+} // end fn json_response()
@@ -0,0 +1,325 @@
+use serde::Serialize;
+use sqlx::migrate::Migrator;
+use sqlx::postgres::PgConnectOptions;
+use tracing_subscriber::EnvFilter;
+use warp::http::Uri;
+
+use crate::store::PostgresStore;
+use crate::types::degradation::DegradationPolicies;
+use crate::types::limits::RouteLimits;
+use crate::types::pagination::PageLimits;
+use crate::types::retention::RetentionPolicies;
+
+/// Names of the environment variables that take a whole number, along with the
+/// smallest and largest values they take.
+const NUMBER_VARIABLES: [(&str, u64, u64); 10] = [
+  ("DATABASE_CONNECT_TIMEOUT_SECS", 0, u64::MAX),
+  ("DATABASE_STATEMENT_TIMEOUT_SECS", 0, u64::MAX),
+  ("DATABASE_SLOW_QUERY_MS", 0, u64::MAX),
+  ("DATABASE_STATS_INTERVAL_SECS", 1, u64::MAX),
+  ("QUESTIONS_PURGE_DAYS", 0, i32::MAX as u64),
+  ("PAGE_SIZE_DEFAULT", 1, i32::MAX as u64),
+  ("PAGE_SIZE_MAX", 1, i32::MAX as u64),
+  ("REQUEST_BODY_MAX_BYTES", 1, u64::MAX),
+  ("REQUEST_TIMEOUT_SECS", 1, u64::MAX),
+  ("SUGGEST_RATE_LIMIT", 1, u32::MAX as u64),
+];
+
+/// Names of the environment variables that take `true` or `false`.
+const BOOLEAN_VARIABLES: [&str; 3] =
+  ["RETENTION_DRY_RUN", "SCHEMA_COMPAT_MODE", "STARTUP_CHECK_STRICT"];
+
+/// Represents the outcome of a check made at startup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+  /// Everything is as expected.
+  Passed,
+  /// Something is off, but the application works as expected anyway.
+  Warning,
+  /// Something does not work, so the application starts degraded, if at all.
+  Failed,
+} // end enum CheckStatus
+
+/// Represents a check made at startup.
+#[derive(Debug, Clone, Serialize)]
+pub struct Check {
+  /// What was checked.
+  pub name: String,
+  /// Outcome of the check.
+  pub status: CheckStatus,
+  /// Description of the outcome.
+  pub detail: String,
+} // end struct Check
+
+/// Represents the checks of the database, of its migrations and of the configuration
+/// made at startup.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StartupReport {
+  /// Checks made, in order.
+  pub checks: Vec<Check>,
+} // end struct StartupReport
+
+impl StartupReport {
+  /// Adds a check to the report.
+  ///
+  /// # Arguments
+  ///
+  /// * `name`: What was checked.
+  /// * `status`: Outcome of the check.
+  /// * `detail`: Description of the outcome.
+  pub fn push(
+    &mut self,
+    name: &str,
+    status: CheckStatus,
+    detail: String,
+  ) {
+    self.checks.push(Check {
+      name: name.to_string(),
+      status,
+      detail,
+    });
+  } // end fn push()
+
+  /// Gets the amount of failed checks.
+  pub fn failures(&self) -> usize {
+    self
+      .checks
+      .iter()
+      .filter(|check| check.status == CheckStatus::Failed)
+      .count()
+  } // end fn failures()
+
+  /// Logs each check with the level of its outcome, and then the whole report as JSON.
+  pub fn log(&self) {
+    for check in &self.checks {
+      match check.status {
+        CheckStatus::Passed => {
+          tracing::event!(tracing::Level::INFO, "Check {}: {}", check.name, check.detail)
+        }
+        CheckStatus::Warning => {
+          tracing::event!(tracing::Level::WARN, "Check {}: {}", check.name, check.detail)
+        }
+        CheckStatus::Failed => {
+          tracing::event!(tracing::Level::ERROR, "Check {}: {}", check.name, check.detail)
+        }
+      }
+    }
+
+    match serde_json::to_string(self) {
+      Ok(report) => tracing::event!(tracing::Level::INFO, "Startup report: {}", report),
+      Err(err) => tracing::event!(tracing::Level::ERROR, "{:?}", err),
+    }
+  } // end fn log()
+}
+
+/// Checks that the environment variables that are set have valid values. Invalid values
+/// are replaced with the defaults, which may not be what was meant.
+///
+/// # Arguments
+///
+/// * `report`: Report the checks are added to.
+pub fn check_configuration(report: &mut StartupReport) {
+  let failures = report.failures();
+
+  for (name, min, max) in NUMBER_VARIABLES {
+    if let Ok(value) = std::env::var(name) {
+      match value.parse::<u64>() {
+        Ok(number) if (min..=max).contains(&number) => {}
+        _ => report.push(
+          name,
+          CheckStatus::Failed,
+          format!(
+            "'{}' is not a number from {} to {}, the default is used",
+            value, min, max
+          ),
+        ),
+      }
+    }
+  }
+
+  for name in BOOLEAN_VARIABLES {
+    if let Ok(value) = std::env::var(name) {
+      if value.parse::<bool>().is_err() {
+        report.push(
+          name,
+          CheckStatus::Failed,
+          format!("'{}' is not true or false, the default is used", value),
+        );
+      }
+    }
+  }
+
+  if let Ok(url) = std::env::var("DATABASE_REPLICA_URL") {
+    if let Err(err) = url.parse::<PgConnectOptions>() {
+      let detail = format!("not a valid URL. {}", err);
+      report.push("DATABASE_REPLICA_URL", CheckStatus::Failed, detail);
+    }
+  }
+
+  if let Ok(url) = std::env::var("TRANSLATOR_URL") {
+    match url.parse::<Uri>() {
+      Ok(uri) if uri.scheme_str() == Some("http") => {}
+      Ok(_) => {
+        let detail = "only http URLs are supported, translations fail".to_string();
+        report.push("TRANSLATOR_URL", CheckStatus::Failed, detail);
+      }
+      Err(err) => {
+        let detail = format!("not a valid URL, translations fail. {}", err);
+        report.push("TRANSLATOR_URL", CheckStatus::Failed, detail);
+      }
+    }
+  }
+
+  if let Ok(filter) = std::env::var("RUST_LOG") {
+    if let Err(err) = EnvFilter::try_new(&filter) {
+      let detail = format!("not a valid filter. {}", err);
+      report.push("RUST_LOG", CheckStatus::Failed, detail);
+    }
+  }
+
+  if let Ok(spec) = std::env::var("DEGRADATION_POLICIES") {
+    if let Err(err) = DegradationPolicies::parse(&spec) {
+      let detail = format!("{}, the default policies are used", err);
+      report.push("DEGRADATION_POLICIES", CheckStatus::Failed, detail);
+    }
+  }
+
+  if let Ok(spec) = std::env::var("RETENTION_RULES") {
+    if let Err(err) = RetentionPolicies::parse(&spec, RetentionPolicies::default()) {
+      let detail = format!("{}, the default rules are used", err);
+      report.push("RETENTION_RULES", CheckStatus::Failed, detail);
+    }
+  }
+
+  if let Ok(spec) = std::env::var("ROUTE_LIMITS") {
+    if let Err(err) = RouteLimits::parse_overrides(&spec) {
+      let detail = format!("{}, the default limits are used", err);
+      report.push("ROUTE_LIMITS", CheckStatus::Failed, detail);
+    }
+  }
+
+  let limits = PageLimits::from_env();
+  let default_size = std::env::var("PAGE_SIZE_DEFAULT")
+    .ok()
+    .and_then(|size| size.parse::<i32>().ok());
+  if default_size.map_or(false, |size| size > limits.max) {
+    report.push(
+      "PAGE_SIZE_DEFAULT",
+      CheckStatus::Warning,
+      format!("larger than the largest page size, so {} is used", limits.max),
+    );
+  }
+
+  if report.failures() == failures {
+    report.push(
+      "configuration",
+      CheckStatus::Passed,
+      "the environment variables set have valid values".to_string(),
+    );
+  }
+} // end fn check_configuration()
+
+/// Checks that the database and its read replica, if any, reply in time.
+///
+/// # Arguments
+///
+/// * `store`: Data store whose databases are checked.
+/// * `report`: Report the checks are added to.
+pub async fn check_database(
+  store: &PostgresStore,
+  report: &mut StartupReport,
+) {
+  match store.health_check().await {
+    Ok(latency) => report.push(
+      "database",
+      CheckStatus::Passed,
+      format!("replied in {:.1} ms", latency.as_secs_f64() * 1000.0),
+    ),
+    Err(err) => report.push("database", CheckStatus::Failed, err.to_string()),
+  }
+
+  // The reads fall back to the primary database when the replica is unreachable
+  match store.replica_health_check().await {
+    Some(Ok(latency)) => report.push(
+      "database replica",
+      CheckStatus::Passed,
+      format!("replied in {:.1} ms", latency.as_secs_f64() * 1000.0),
+    ),
+    Some(Err(err)) => report.push(
+      "database replica",
+      CheckStatus::Warning,
+      format!("the reads use the primary database. {}", err),
+    ),
+    None => {}
+  }
+} // end fn check_database()
+
+/// Checks the migrations applied to the database against the ones of this build, before
+/// the pending ones are run.
+///
+/// # Arguments
+///
+/// * `migrator`: Migrations of this build.
+/// * `store`: Data store whose database is checked.
+/// * `report`: Report the checks are added to.
+pub async fn check_migrations(
+  migrator: &Migrator,
+  store: &PostgresStore,
+  report: &mut StartupReport,
+) {
+  let applied = match store.applied_migrations().await {
+    Ok(applied) => applied,
+    Err(err) => {
+      report.push("migrations", CheckStatus::Failed, err.to_string());
+      return;
+    }
+  };
+  let migrations: Vec<_> = migrator
+    .iter()
+    .filter(|migration| !migration.migration_type.is_down_migration())
+    .collect();
+
+  let changed: Vec<i64> = migrations
+    .iter()
+    .filter(|migration| {
+      applied.iter().any(|(version, checksum)| {
+        *version == migration.version && checksum[..] != migration.checksum[..]
+      })
+    })
+    .map(|migration| migration.version)
+    .collect();
+  if !changed.is_empty() {
+    report.push(
+      "migrations",
+      CheckStatus::Failed,
+      format!("applied migrations were changed afterwards: {:?}", changed),
+    );
+  }
+
+  // An older build may run against a database migrated by a newer one, e.g. during a
+  // blue/green deployment
+  let unknown = applied
+    .iter()
+    .filter(|(version, _)| {
+      !migrations.iter().any(|migration| migration.version == *version)
+    })
+    .count();
+  if unknown > 0 {
+    report.push(
+      "migrations",
+      CheckStatus::Warning,
+      format!("the database has {} migrations this build does not know", unknown),
+    );
+  }
+
+  let pending = migrations
+    .iter()
+    .filter(|migration| !applied.iter().any(|(version, _)| *version == migration.version))
+    .count();
+  report.push(
+    "migrations",
+    CheckStatus::Passed,
+    format!("{} applied, {} pending to be applied now", applied.len(), pending),
+  );
+} // end fn check_migrations()
@@ -0,0 +1,475 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime};
+use futures::stream::BoxStream;
+use tokio::sync::RwLock;
+
+use handle_errors::errors::QError;
+
+use super::QuestionStore;
+
+use crate::types::answer::{Answer, AnswerId, NewAnswer};
+use crate::types::code::extract_code_blocks;
+use crate::types::export::ExportRow;
+use crate::types::query::{QuestionQuery, TagMatch};
+use crate::types::question::{NewQuestion, Question, QuestionId, QuestionStatus};
+use crate::types::revision::merge_hint;
+use crate::types::seed::SeedQuestions;
+
+/// Contents of the answers generated for the fixture questions, used in turns.
+const MOCK_ANSWERS: [&str; 4] = [
+  "Have you tried restarting it?",
+  "The documentation of the crate has an example of this.",
+  "It works for me with the latest version.",
+  "Could you share the full error message?",
+];
+
+/// Represents a question of the in-memory data store, along with its creation and
+/// deletion dates.
+#[derive(Debug, Clone)]
+struct MockQuestion {
+  /// Question as replied by the API.
+  question: Question,
+  /// Date and time the question was created.
+  created_on: NaiveDateTime,
+  /// Date and time the question was deleted, if it was.
+  deleted_at: Option<NaiveDateTime>,
+} // end struct MockQuestion
+
+/// Represents an answer of the in-memory data store, along with its creation date.
+#[derive(Debug, Clone)]
+struct MockAnswer {
+  /// Answer as stored by the API.
+  answer: Answer,
+  /// Date and time the answer was created.
+  created_on: NaiveDateTime,
+} // end struct MockAnswer
+
+/// Represents the contents of the in-memory data store.
+#[derive(Debug, Default)]
+struct MockData {
+  /// Questions by their ID.
+  questions: BTreeMap<i32, MockQuestion>,
+  /// Answers by their ID.
+  answers: BTreeMap<i32, MockAnswer>,
+} // end struct MockData
+
+/// Represents a data store kept in memory with fixture data, to serve the API without a
+/// database while developing a frontend, or to test the routes.
+///
+/// The fixture questions get generated answers, and both get fixed creation dates, so
+/// every run starts with the same data. Changes are kept until the server stops.
+#[derive(Debug, Clone)]
+pub struct InMemoryStore {
+  /// Questions and answers of the data store.
+  data: Arc<RwLock<MockData>>,
+} // end struct InMemoryStore
+
+impl InMemoryStore {
+  /// Creates a new in-memory data store with the questions of a fixture file.
+  ///
+  /// # Arguments
+  ///
+  /// * `fixture`: Questions of the fixture file, by their ID. Their answers, if any, are
+  ///   replaced with generated ones.
+  pub fn new(fixture: SeedQuestions) -> Result<Self, QError> {
+    let mut data = MockData::default();
+
+    for question in fixture.into_values() {
+      let id = question.id.parse::<QuestionId>()?;
+      data.questions.insert(
+        id.value(),
+        MockQuestion {
+          question: Question {
+            id,
+            title: question.title,
+            content: question.content,
+            tags: question.tags,
+            status: QuestionStatus::Open,
+            kind: Default::default(),
+            options: None,
+            rating_max: None,
+            points: None,
+            version: 1,
+          },
+          created_on: fixture_date(id.value()),
+          deleted_at: None,
+        },
+      );
+    }
+
+    // Each question gets from 0 to 2 answers, depending on its ID.
+    let question_ids: Vec<i32> = data.questions.keys().copied().collect();
+    for question_id in question_ids {
+      for turn in 0..(question_id.rem_euclid(3)) {
+        let id = data.answers.len() as i32 + 1;
+        data.answers.insert(
+          id,
+          MockAnswer {
+            answer: Answer {
+              id: AnswerId::new(id),
+              content: MOCK_ANSWERS[(question_id + turn) as usize % MOCK_ANSWERS.len()]
+                .to_string(),
+              question_id: QuestionId::new(question_id),
+              code_blocks: Vec::new(),
+            },
+            created_on: fixture_date(question_id) + chrono::Duration::hours(turn as i64 + 1),
+          },
+        );
+      }
+    }
+
+    Ok(InMemoryStore {
+      data: Arc::new(RwLock::new(data)),
+    })
+  } // end fn new()
+
+  /// Gets the page of questions that match the given listing parameters, ordered by
+  /// their ID, along with the amount of matching questions across all the pages.
+  ///
+  /// Only the status, creation dates, tags, code language and pagination of the
+  /// parameters are applied, keyset pagination included.
+  ///
+  /// # Arguments
+  ///
+  /// * `query`: Parameters to select the questions.
+  async fn page(
+    &self,
+    query: &QuestionQuery,
+  ) -> (Vec<Question>, i64) {
+    let data = self.data.read().await;
+    let matches = |mock: &MockQuestion| {
+      let question = &mock.question;
+      let tags = question.tags.as_deref().unwrap_or_default();
+      mock.deleted_at.is_none()
+        && query.status.map_or(true, |status| question.status == status)
+        && query.created_after.map_or(true, |after| mock.created_on > after)
+        && query.created_before.map_or(true, |before| mock.created_on < before)
+        && (query.tags.is_empty()
+          || match query.tag_match {
+            TagMatch::All => query.tags.iter().all(|tag| tags.contains(tag)),
+            TagMatch::Any => query.tags.iter().any(|tag| tags.contains(tag)),
+          })
+        && query.code_language.as_ref().map_or(true, |language| {
+          data.answers.values().any(|answer| {
+            answer.answer.question_id == question.id
+              && answer
+                .answer
+                .code_blocks
+                .iter()
+                .any(|block| block.language.as_ref() == Some(language))
+          })
+        })
+    };
+
+    let matched: Vec<&Question> = data
+      .questions
+      .values()
+      .filter(|mock| matches(mock))
+      .map(|mock| &mock.question)
+      .filter(|question| {
+        query.pagination.after.map_or(true, |after| question.id.value() > after)
+      })
+      .collect();
+    let questions = matched
+      .iter()
+      .skip(query.pagination.offset.max(0) as usize)
+      .take(query.pagination.limit.map_or(usize::MAX, |limit| limit.max(0) as usize))
+      .map(|question| (*question).clone())
+      .collect();
+
+    (questions, matched.len() as i64)
+  } // end fn page()
+
+  /// Gets all the questions joined with their answers, in the format of an export.
+  async fn rows(&self) -> Vec<ExportRow> {
+    let data = self.data.read().await;
+    let mut rows = Vec::new();
+
+    for mock in data.questions.values() {
+      let question = &mock.question;
+      let row = ExportRow {
+        question_id: question.id,
+        title: question.title.clone(),
+        content: question.content.clone(),
+        tags: question.tags.clone(),
+        status: question.status.to_string(),
+        kind: question.kind.to_string(),
+        question_created_on: mock.created_on,
+        question_deleted_at: mock.deleted_at,
+        answer_id: None,
+        answer_content: None,
+        answer_created_on: None,
+      };
+
+      let answers: Vec<&MockAnswer> = data
+        .answers
+        .values()
+        .filter(|answer| answer.answer.question_id == question.id)
+        .collect();
+      if answers.is_empty() {
+        rows.push(row);
+        continue;
+      }
+      for answer in answers {
+        rows.push(ExportRow {
+          answer_id: Some(answer.answer.id),
+          answer_content: Some(answer.answer.content.clone()),
+          answer_created_on: Some(answer.created_on),
+          ..row.clone()
+        });
+      }
+    }
+
+    rows
+  } // end fn rows()
+}
+
+#[async_trait]
+impl QuestionStore for InMemoryStore {
+  /// Gets the questions that match the given listing parameters, ordered by their ID,
+  /// each one along with the amount of matching questions across all the pages.
+  ///
+  /// Only the status, creation dates, tags, code language and pagination of the
+  /// parameters are applied, keyset pagination included.
+  ///
+  /// # Arguments
+  ///
+  /// * `query`: Parameters to select the questions.
+  fn get_questions(
+    &self,
+    query: QuestionQuery,
+  ) -> BoxStream<'_, Result<(Question, i64), QError>> {
+    Box::pin(async_stream::try_stream! {
+      let (questions, total) = self.page(&query).await;
+      for question in questions {
+        yield (question, total);
+      }
+    })
+  } // end fn get_questions()
+
+  /// Gets all the questions joined with their answers, in the format of an export.
+  fn export_rows(&self) -> BoxStream<'_, Result<ExportRow, QError>> {
+    Box::pin(async_stream::try_stream! {
+      for row in self.rows().await {
+        yield row;
+      }
+    })
+  } // end fn export_rows()
+
+  /// Gets the question specified by the given ID.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the question.
+  async fn get_question_by_id(
+    &self,
+    id: i32,
+  ) -> Result<Question, QError> {
+    match self.data.read().await.questions.get(&id) {
+      Some(mock) if mock.deleted_at.is_none() => Ok(mock.question.clone()),
+      _ => Err(QError::QuestionNotFound),
+    }
+  } // end fn get_question_by_id()
+
+  /// Adds a new question, with the next ID after the highest one.
+  ///
+  /// # Arguments
+  ///
+  /// * `question`: Question to be added, already validated.
+  async fn add_question(
+    &self,
+    question: NewQuestion,
+  ) -> Result<Vec<Question>, QError> {
+    let mut data = self.data.write().await;
+    let id = data.questions.keys().next_back().copied().unwrap_or_default() + 1;
+    let question = Question {
+      id: QuestionId::new(id),
+      title: question.title,
+      content: question.content,
+      tags: question.tags,
+      status: QuestionStatus::Open,
+      kind: question.kind,
+      options: question.options,
+      rating_max: question.rating_max,
+      points: question.points,
+      version: 1,
+    };
+    data.questions.insert(
+      id,
+      MockQuestion {
+        question: question.clone(),
+        created_on: chrono::Utc::now().naive_utc(),
+        deleted_at: None,
+      },
+    );
+
+    Ok(vec![question])
+  } // end fn add_question()
+
+  /// Updates the title, contents and tags of a question, if it is still at the given
+  /// version, and moves it to the next version.
+  ///
+  /// # Arguments
+  ///
+  /// * `question`: Question data.
+  /// * `id`: Unique identifier (ID) of the question.
+  /// * `version`: Version of the question the update is based on.
+  async fn update_question(
+    &self,
+    question: Question,
+    id: i32,
+    version: i32,
+  ) -> Result<Vec<Question>, QError> {
+    let mut data = self.data.write().await;
+    let mock = match data.questions.get_mut(&id) {
+      Some(mock) if mock.deleted_at.is_none() => mock,
+      _ => return Err(QError::QuestionNotFound),
+    };
+    if mock.question.version != version {
+      let hint = merge_hint(version, None, &mock.question, &question);
+      return Err(QError::VersionConflict(Box::new(hint)));
+    }
+
+    mock.question.title = question.title;
+    mock.question.content = question.content;
+    mock.question.tags = question.tags.filter(|tags| !tags.is_empty());
+    mock.question.version += 1;
+
+    Ok(vec![mock.question.clone()])
+  } // end fn update_question()
+
+  /// Marks a question as deleted, which hides its answers too.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the question.
+  async fn delete_question(
+    &self,
+    id: i32,
+  ) -> Result<(), QError> {
+    let mut data = self.data.write().await;
+    match data.questions.get_mut(&id) {
+      Some(mock) if mock.deleted_at.is_none() => {
+        mock.deleted_at = Some(chrono::Utc::now().naive_utc());
+        Ok(())
+      }
+      _ => Err(QError::QuestionNotFound),
+    }
+  } // end fn delete_question()
+
+  /// Adds a new answer to an open question, with the next ID after the highest one.
+  ///
+  /// # Arguments
+  ///
+  /// * `answer`: Answer to be added.
+  async fn add_answer(
+    &self,
+    answer: NewAnswer,
+  ) -> Result<Answer, QError> {
+    let mut data = self.data.write().await;
+    match data.questions.get(&answer.question_id.value()) {
+      Some(mock) if mock.deleted_at.is_some() => return Err(QError::QuestionNotFound),
+      Some(mock) if mock.question.status != QuestionStatus::Open => {
+        return Err(QError::QuestionClosed)
+      }
+      Some(_) => {}
+      None => return Err(QError::QuestionNotFound),
+    }
+
+    let id = data.answers.keys().next_back().copied().unwrap_or_default() + 1;
+    let answer = Answer {
+      id: AnswerId::new(id),
+      code_blocks: extract_code_blocks(&answer.content),
+      content: answer.content,
+      question_id: answer.question_id,
+    };
+    data.answers.insert(
+      id,
+      MockAnswer {
+        answer: answer.clone(),
+        created_on: chrono::Utc::now().naive_utc(),
+      },
+    );
+
+    Ok(answer)
+  } // end fn add_answer()
+
+  /// Gets the questions with the same title as the given one, regardless of the case,
+  /// as the only ones similar to it.
+  ///
+  /// # Arguments
+  ///
+  /// * `title`: Title to compare with the titles of the existing questions.
+  /// * `_threshold`: Minimum similarity, met by the same titles whatever it is.
+  /// * `limit`: Maximum amount of questions to get.
+  async fn find_similar_questions(
+    &self,
+    title: &str,
+    _threshold: f32,
+    limit: i32,
+  ) -> Result<Vec<Question>, QError> {
+    let title = title.trim().to_lowercase();
+
+    Ok(
+      self
+        .data
+        .read()
+        .await
+        .questions
+        .values()
+        .filter(|mock| mock.deleted_at.is_none())
+        .filter(|mock| mock.question.title.trim().to_lowercase() == title)
+        .take(limit.max(0) as usize)
+        .map(|mock| mock.question.clone())
+        .collect(),
+    )
+  } // end fn find_similar_questions()
+
+  /// Gets the answers of a question with the same content as the given one, regardless
+  /// of the case, as the only ones similar to it.
+  ///
+  /// # Arguments
+  ///
+  /// * `question_id`: Unique identifier (ID) of the question of the answers.
+  /// * `content`: Content to compare with the contents of the existing answers.
+  /// * `_threshold`: Minimum similarity, met by the same contents whatever it is.
+  /// * `limit`: Maximum amount of answers to get.
+  async fn find_similar_answers(
+    &self,
+    question_id: QuestionId,
+    content: &str,
+    _threshold: f32,
+    limit: i32,
+  ) -> Result<Vec<Answer>, QError> {
+    let content = content.trim().to_lowercase();
+
+    Ok(
+      self
+        .data
+        .read()
+        .await
+        .answers
+        .values()
+        .filter(|mock| mock.answer.question_id == question_id)
+        .filter(|mock| mock.answer.content.trim().to_lowercase() == content)
+        .take(limit.max(0) as usize)
+        .map(|mock| mock.answer.clone())
+        .collect(),
+    )
+  } // end fn find_similar_answers()
+}
+
+/// Gets the creation date of a fixture question, one day after the previous question.
+///
+/// # Arguments
+///
+/// * `id`: Unique identifier (ID) of the question.
+fn fixture_date(id: i32) -> NaiveDateTime {
+  let start = NaiveDate::from_ymd_opt(2023, 1, 1)
+    .and_then(|date| date.and_hms_opt(9, 0, 0))
+    .unwrap_or_default();
+  start + chrono::Duration::days(id as i64)
+} // end fn fixture_date()
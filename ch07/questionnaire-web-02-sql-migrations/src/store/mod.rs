@@ -0,0 +1,239 @@
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+
+use handle_errors::errors::QError;
+
+use crate::types::answer::{Answer, NewAnswer};
+use crate::types::export::ExportRow;
+use crate::types::query::QuestionQuery;
+use crate::types::question::{NewQuestion, Question, QuestionId};
+
+mod memory;
+mod mongo;
+mod postgres;
+mod sqlite;
+
+pub use memory::InMemoryStore;
+pub use mongo::MongoStore;
+pub use postgres::PostgresStore;
+pub use sqlite::SqliteStore;
+
+/// Represents a backend that stores the questions and their answers.
+///
+/// The routes shared by every backend only need these operations. The ones with a
+/// default, like the similarity of the titles, are optional, and the backends without
+/// them find nothing. The features that rely on a given database, like the full-text
+/// search or the surveys, are methods of that backend alone.
+///
+/// Every backend follows the same contract, so the API replies the same whatever the
+/// backend:
+///
+/// * A deleted question is kept, marked with the date it was deleted, so it can be
+///   exported, and restored by the backends that can. It is no longer found, listed,
+///   updated nor answered.
+/// * The operations on a question that does not exist, or was deleted, fail with
+///   `QError::QuestionNotFound`.
+/// * An update based on another version than the current one fails with
+///   `QError::VersionConflict`, along with the hint to merge it.
+#[async_trait]
+pub trait QuestionStore: Clone + Send + Sync + 'static {
+  /// Gets the collection of questions as a stream of rows, each one along with the
+  /// amount of questions that match the query across all the pages.
+  ///
+  /// # Arguments
+  ///
+  /// * `query`: Pagination, filters and tags of the set of questions to retrieve. With
+  ///   keyset pagination, the questions after the given ID are listed by their ID.
+  fn get_questions(
+    &self,
+    query: QuestionQuery,
+  ) -> BoxStream<'_, Result<(Question, i64), QError>>;
+
+  /// Gets all the questions joined with their answers as a stream of rows, to export
+  /// the data store. Each question has one row per answer, or a single row without an
+  /// answer when it has none. The deleted questions are exported too, with the date
+  /// they were deleted.
+  fn export_rows(&self) -> BoxStream<'_, Result<ExportRow, QError>>;
+
+  /// Gets the question specified by the given id.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the question.
+  async fn get_question_by_id(
+    &self,
+    id: i32,
+  ) -> Result<Question, QError>;
+
+  /// Adds a new question, already validated, and gets it with its ID.
+  ///
+  /// # Arguments
+  ///
+  /// * `question`: Question to be added.
+  async fn add_question(
+    &self,
+    question: NewQuestion,
+  ) -> Result<Vec<Question>, QError>;
+
+  /// Updates an existing question, if it is still at the given version, and moves it
+  /// to the next version.
+  ///
+  /// # Arguments
+  ///
+  /// * `question`: Question data.
+  /// * `id`: Unique identifier (ID) of the question.
+  /// * `version`: Version of the question the update is based on.
+  async fn update_question(
+    &self,
+    question: Question,
+    id: i32,
+    version: i32,
+  ) -> Result<Vec<Question>, QError>;
+
+  /// Marks the question specified by the given id as deleted, along with its answers.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the question to be deleted.
+  async fn delete_question(
+    &self,
+    id: i32,
+  ) -> Result<(), QError>;
+
+  /// Adds a new answer to an open question that exists.
+  ///
+  /// # Arguments
+  ///
+  /// * `answer`: Answer to be added.
+  async fn add_answer(
+    &self,
+    answer: NewAnswer,
+  ) -> Result<Answer, QError>;
+
+  /// Gets the questions whose title is similar to the given one, from the most to the
+  /// least similar, to warn about possible duplicates.
+  ///
+  /// # Arguments
+  ///
+  /// * `_title`: Title to compare with the titles of the existing questions.
+  /// * `_threshold`: Minimum similarity, from 0 to 1, of a similar title.
+  /// * `_limit`: Maximum amount of questions to get.
+  async fn find_similar_questions(
+    &self,
+    _title: &str,
+    _threshold: f32,
+    _limit: i32,
+  ) -> Result<Vec<Question>, QError> {
+    Ok(Vec::new())
+  } // end fn find_similar_questions()
+
+  /// Gets the answers of a question whose content is similar to the given one, from the
+  /// most to the least similar, to warn about possible duplicates.
+  ///
+  /// # Arguments
+  ///
+  /// * `_question_id`: Unique identifier (ID) of the question of the answers.
+  /// * `_content`: Content to compare with the contents of the existing answers.
+  /// * `_threshold`: Minimum similarity, from 0 to 1, of a similar content.
+  /// * `_limit`: Maximum amount of answers to get.
+  async fn find_similar_answers(
+    &self,
+    _question_id: QuestionId,
+    _content: &str,
+    _threshold: f32,
+    _limit: i32,
+  ) -> Result<Vec<Answer>, QError> {
+    Ok(Vec::new())
+  } // end fn find_similar_answers()
+
+  /// Gets the ID of the question that replaced the given one, or `None` when the
+  /// question was not replaced.
+  ///
+  /// # Arguments
+  ///
+  /// * `_id`: Unique identifier (ID) of the question not found.
+  async fn get_question_redirect(
+    &self,
+    _id: i32,
+  ) -> Result<Option<i32>, QError> {
+    Ok(None)
+  } // end fn get_question_redirect()
+} // end trait QuestionStore
+
+#[cfg(test)]
+mod tests {
+  use futures::TryStreamExt;
+
+  use super::*;
+  use crate::types::question::QuestionKind;
+
+  /// Checks that a backend follows the contract of `QuestionStore` on a question it
+  /// adds, updates, answers and deletes.
+  ///
+  /// # Arguments
+  ///
+  /// * `store`: Empty data store of the backend.
+  async fn check_contract<S: QuestionStore>(store: S) {
+    let new_question = NewQuestion {
+      title: "Title".to_string(),
+      content: "Content".to_string(),
+      tags: Some(vec!["rust".to_string()]),
+      kind: QuestionKind::Text,
+      options: None,
+      rating_max: None,
+      points: None,
+    };
+    let question = store.add_question(new_question).await.unwrap().remove(0);
+    let id = question.id.value();
+    assert_eq!(question.version, 1);
+    assert_eq!(store.get_question_by_id(id).await.unwrap().title, "Title");
+
+    let changed = Question {
+      title: "Changed title".to_string(),
+      ..question.clone()
+    };
+    let updated = store.update_question(changed.clone(), id, 1).await.unwrap();
+    assert_eq!(updated[0].version, 2);
+    let stale = store.update_question(changed.clone(), id, 1).await.unwrap_err();
+    assert!(matches!(stale, QError::VersionConflict(_)));
+
+    let answer = NewAnswer {
+      content: "Answer".to_string(),
+      question_id: question.id,
+    };
+    store.add_answer(answer.clone()).await.unwrap();
+
+    store.delete_question(id).await.unwrap();
+    let not_found =
+      |result: Result<(), QError>| matches!(result, Err(QError::QuestionNotFound));
+    assert!(not_found(store.delete_question(id).await));
+    assert!(not_found(store.get_question_by_id(id).await.map(|_| ())));
+    assert!(not_found(store.update_question(changed, id, 2).await.map(|_| ())));
+    assert!(not_found(store.add_answer(answer).await.map(|_| ())));
+
+    let listed: Vec<_> =
+      store.get_questions(QuestionQuery::default()).try_collect().await.unwrap();
+    assert!(listed.is_empty());
+    let rows: Vec<ExportRow> = store.export_rows().try_collect().await.unwrap();
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].title, "Changed title");
+    assert!(rows[0].question_deleted_at.is_some());
+    assert_eq!(rows[0].answer_content.as_deref(), Some("Answer"));
+  }
+
+  #[tokio::test]
+  async fn the_memory_store_follows_the_contract() {
+    let store = InMemoryStore::new(Default::default()).unwrap();
+    check_contract(store).await;
+  }
+
+  #[tokio::test]
+  async fn the_sqlite_store_follows_the_contract() {
+    let path = std::env::temp_dir().join(format!("{}.db", uuid::Uuid::new_v4()));
+    let store = SqliteStore::new(&format!("sqlite://{}", path.display()))
+      .await
+      .expect("SQLite database");
+    check_contract(store).await;
+    let _ = std::fs::remove_file(path);
+  }
+}
@@ -0,0 +1,497 @@
+use std::collections::HashSet;
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use futures::stream::BoxStream;
+use futures::TryStreamExt;
+use mongodb::bson::{self, doc, Bson, DateTime, Document};
+use mongodb::options::{FindOneAndUpdateOptions, FindOptions, ReturnDocument};
+use mongodb::{Client, Collection, Database};
+use serde::{Deserialize, Serialize};
+
+use handle_errors::errors::QError;
+
+use super::QuestionStore;
+use crate::types::answer::{Answer, AnswerId, NewAnswer};
+use crate::types::code::{extract_code_blocks, CodeBlock};
+use crate::types::export::ExportRow;
+use crate::types::query::{QuestionQuery, TagMatch};
+use crate::types::question::{
+  NewQuestion, Question, QuestionId, QuestionKind, QuestionStatus,
+};
+use crate::types::revision::merge_hint;
+
+/// Name of the database used when the URL does not name one.
+const DEFAULT_DATABASE: &str = "rustwebdev";
+
+/// Name of the collection of the questions, each one with its answers embedded.
+const QUESTIONS_COLLECTION: &str = "questions";
+
+/// Name of the collection of the last IDs given to the questions and the answers.
+const COUNTERS_COLLECTION: &str = "counters";
+
+/// Represents a question as stored in MongoDB, with its answers embedded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct QuestionDocument {
+  /// Unique identifier (ID) of the question.
+  #[serde(rename = "_id")]
+  id: i32,
+  /// Title of the question.
+  title: String,
+  /// Text contents of the question.
+  content: String,
+  /// Tags of the question, if any.
+  tags: Vec<String>,
+  /// Stage of the lifecycle of the question.
+  status: QuestionStatus,
+  /// Kind of answers the question takes.
+  kind: QuestionKind,
+  /// Labels of the options to choose from, in order, for choice questions.
+  options: Option<Vec<String>>,
+  /// Highest rating, for rating questions.
+  rating_max: Option<i32>,
+  /// Points each option is worth, in order, for choice questions.
+  points: Option<Vec<i32>>,
+  /// Version of the question, increased by every update.
+  version: i32,
+  /// Date and time the question was created.
+  created_on: DateTime,
+  /// Date and time the question was deleted, if it was. The documents without it were
+  /// never deleted.
+  #[serde(default)]
+  deleted_at: Option<DateTime>,
+  /// Answers to the question, in order. They are left out of the listings.
+  #[serde(default)]
+  answers: Vec<AnswerDocument>,
+} // end struct QuestionDocument
+
+/// Represents an answer as stored in MongoDB, within its question.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AnswerDocument {
+  /// Unique identifier (ID) of the answer.
+  id: i32,
+  /// Text contents of the answer.
+  content: String,
+  /// Date and time the answer was created.
+  created_on: DateTime,
+  /// Fenced code blocks of the contents, in order.
+  code_blocks: Vec<CodeBlock>,
+} // end struct AnswerDocument
+
+impl QuestionDocument {
+  /// Converts the document into the question replied by the API.
+  fn into_question(self) -> Question {
+    Question {
+      id: QuestionId::new(self.id),
+      title: self.title,
+      content: self.content,
+      tags: (!self.tags.is_empty()).then_some(self.tags),
+      status: self.status,
+      kind: self.kind,
+      options: self.options,
+      rating_max: self.rating_max,
+      points: self.points,
+      version: self.version,
+    }
+  } // end fn into_question()
+
+  /// Converts the document into export rows, one per answer, or a single row without
+  /// an answer when the question has none.
+  fn into_rows(self) -> Vec<ExportRow> {
+    let row = ExportRow {
+      question_id: QuestionId::new(self.id),
+      title: self.title,
+      content: self.content,
+      tags: (!self.tags.is_empty()).then_some(self.tags),
+      status: self.status.to_string(),
+      kind: self.kind.to_string(),
+      question_created_on: naive_date(self.created_on),
+      question_deleted_at: self.deleted_at.map(naive_date),
+      answer_id: None,
+      answer_content: None,
+      answer_created_on: None,
+    };
+    if self.answers.is_empty() {
+      return vec![row];
+    }
+
+    self
+      .answers
+      .into_iter()
+      .map(|answer| ExportRow {
+        answer_id: Some(AnswerId::new(answer.id)),
+        answer_content: Some(answer.content),
+        answer_created_on: Some(naive_date(answer.created_on)),
+        ..row.clone()
+      })
+      .collect()
+  } // end fn into_rows()
+}
+
+/// Represents the data store of the application in a MongoDB database, to show the
+/// routes working on a document store.
+///
+/// Each question is a document with its answers embedded, and the IDs come from
+/// counters, so they are whole numbers like in the SQL stores. Only the questions and
+/// their answers are stored.
+#[derive(Debug, Clone)]
+pub struct MongoStore {
+  /// Database of the collections.
+  database: Database,
+} // end struct MongoStore
+
+impl MongoStore {
+  /// Creates a new data store and checks that the database replies.
+  ///
+  /// # Arguments
+  ///
+  /// * `db_url`: URL of the database, like `mongodb://localhost:27017/rustwebdev`.
+  pub async fn new(db_url: &str) -> Result<Self, QError> {
+    let client = Client::with_uri_str(db_url)
+      .await
+      .map_err(Self::database_error)?;
+    let database = client
+      .default_database()
+      .unwrap_or_else(|| client.database(DEFAULT_DATABASE));
+
+    database
+      .run_command(doc! { "ping": 1 }, None)
+      .await
+      .map_err(Self::database_error)?;
+
+    Ok(MongoStore { database })
+  } // end fn new()
+
+  /// Gets the collection of the questions.
+  fn questions(&self) -> Collection<QuestionDocument> {
+    self.database.collection(QUESTIONS_COLLECTION)
+  } // end fn questions()
+
+  /// Increases the counter of the given name and gets its new value, which is the next
+  /// free ID. Counters start at 1.
+  ///
+  /// # Arguments
+  ///
+  /// * `name`: Name of the counter, like the collection the IDs are for.
+  async fn next_id(
+    &self,
+    name: &str,
+  ) -> Result<i32, QError> {
+    let options = FindOneAndUpdateOptions::builder()
+      .upsert(true)
+      .return_document(ReturnDocument::After)
+      .build();
+    let counter = self
+      .database
+      .collection::<Document>(COUNTERS_COLLECTION)
+      .find_one_and_update(doc! { "_id": name }, doc! { "$inc": { "seq": 1 } }, options)
+      .await
+      .map_err(Self::database_error)?;
+
+    match counter.as_ref().map(|counter| counter.get_i32("seq")) {
+      Some(Ok(id)) => Ok(id),
+      _ => Err(QError::Internal(format!("the counter {} has no valid value", name))),
+    }
+  } // end fn next_id()
+
+  /// Converts an error of the database into an internal error, whose details are only
+  /// logged.
+  ///
+  /// # Arguments
+  ///
+  /// * `err`: Error returned by the database.
+  fn database_error(err: mongodb::error::Error) -> QError {
+    tracing::event!(tracing::Level::ERROR, "{:?}", err);
+    QError::Internal(format!("MongoDB error. {}", err))
+  } // end fn database_error()
+}
+
+#[async_trait]
+impl QuestionStore for MongoStore {
+  /// Gets the collection of questions as a stream of rows, each one along with the
+  /// amount of questions that match the query across all the pages.
+  ///
+  /// Only the status, creation dates, tags, code language and pagination of the query
+  /// are applied, and the questions are listed by their ID.
+  ///
+  /// # Arguments
+  ///
+  /// * `query`: Pagination, filters and tags of the set of questions to retrieve. With
+  ///   keyset pagination, the questions after the given ID are listed.
+  fn get_questions(
+    &self,
+    query: QuestionQuery,
+  ) -> BoxStream<'_, Result<(Question, i64), QError>> {
+    Box::pin(async_stream::try_stream! {
+      let mut filter = doc! { "deleted_at": null };
+      if let Some(status) = query.status {
+        filter.insert("status", status.as_str());
+      }
+      let mut created_on = doc! {};
+      if let Some(created_after) = query.created_after {
+        created_on.insert("$gt", mongo_date(created_after));
+      }
+      if let Some(created_before) = query.created_before {
+        created_on.insert("$lt", mongo_date(created_before));
+      }
+      if !created_on.is_empty() {
+        filter.insert("created_on", created_on);
+      }
+      if !query.tags.is_empty() {
+        let tags: HashSet<&String> = query.tags.iter().collect();
+        let tags: Vec<Bson> =
+          tags.into_iter().map(|tag| Bson::from(tag.as_str())).collect();
+        match query.tag_match {
+          TagMatch::All => filter.insert("tags", doc! { "$all": tags }),
+          TagMatch::Any => filter.insert("tags", doc! { "$in": tags }),
+        };
+      }
+      if let Some(language) = query.code_language.clone() {
+        filter.insert("answers.code_blocks.language", language);
+      }
+      if let Some(after) = query.pagination.after {
+        filter.insert("_id", doc! { "$gt": after });
+      }
+
+      let total = self
+        .questions()
+        .count_documents(filter.clone(), None)
+        .await
+        .map_err(Self::database_error)? as i64;
+      let options = FindOptions::builder()
+        .sort(doc! { "_id": 1 })
+        .skip(query.pagination.offset.max(0) as u64)
+        .limit(query.pagination.limit.map(i64::from))
+        .projection(doc! { "answers": 0 })
+        .build();
+      let documents = self
+        .questions()
+        .find(filter, options)
+        .await
+        .map_err(Self::database_error)?;
+
+      let mut documents = documents.map_err(Self::database_error);
+      while let Some(document) = documents.try_next().await? {
+        yield (document.into_question(), total);
+      }
+    })
+  } // end fn get_questions()
+
+  /// Gets all the questions joined with their answers as a stream of rows, to export
+  /// the data store. The documents are fetched as the stream is polled.
+  fn export_rows(&self) -> BoxStream<'_, Result<ExportRow, QError>> {
+    Box::pin(async_stream::try_stream! {
+      let options = FindOptions::builder().sort(doc! { "_id": 1 }).build();
+      let documents = self
+        .questions()
+        .find(doc! {}, options)
+        .await
+        .map_err(Self::database_error)?;
+
+      let mut documents = documents.map_err(Self::database_error);
+      while let Some(document) = documents.try_next().await? {
+        for row in document.into_rows() {
+          yield row;
+        }
+      }
+    })
+  } // end fn export_rows()
+
+  /// Gets the question specified by the given id.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the question.
+  async fn get_question_by_id(
+    &self,
+    id: i32,
+  ) -> Result<Question, QError> {
+    match self
+      .questions()
+      .find_one(doc! { "_id": id, "deleted_at": null }, None)
+      .await
+      .map_err(Self::database_error)?
+    {
+      Some(document) => Ok(document.into_question()),
+      None => Err(QError::QuestionNotFound),
+    }
+  } // end fn get_question_by_id()
+
+  /// Adds a new question, with the next ID of its counter.
+  ///
+  /// # Arguments
+  ///
+  /// * `question`: Question to be added.
+  async fn add_question(
+    &self,
+    question: NewQuestion,
+  ) -> Result<Vec<Question>, QError> {
+    let document = QuestionDocument {
+      id: self.next_id("questions").await?,
+      title: question.title,
+      content: question.content,
+      tags: question.tags.unwrap_or_default(),
+      status: QuestionStatus::Open,
+      kind: question.kind,
+      options: question.options,
+      rating_max: question.rating_max,
+      points: question.points,
+      version: 1,
+      created_on: DateTime::now(),
+      deleted_at: None,
+      answers: Vec::new(),
+    };
+
+    self
+      .questions()
+      .insert_one(&document, None)
+      .await
+      .map_err(Self::database_error)?;
+
+    Ok(vec![document.into_question()])
+  } // end fn add_question()
+
+  /// Updates the title, contents and tags of a question, if it is still at the given
+  /// version, and moves it to the next version.
+  ///
+  /// The version is checked by the filter of the update, so two updates based on the
+  /// same version cannot both succeed.
+  ///
+  /// # Arguments
+  ///
+  /// * `question`: Question data.
+  /// * `id`: Unique identifier (ID) of the question.
+  /// * `version`: Version of the question the update is based on.
+  async fn update_question(
+    &self,
+    question: Question,
+    id: i32,
+    version: i32,
+  ) -> Result<Vec<Question>, QError> {
+    let options = FindOneAndUpdateOptions::builder()
+      .return_document(ReturnDocument::After)
+      .projection(doc! { "answers": 0 })
+      .build();
+    let update = doc! {
+      "$set": {
+        "title": &question.title,
+        "content": &question.content,
+        "tags": question.tags.clone().unwrap_or_default(),
+      },
+      "$inc": { "version": 1 },
+    };
+    let filter = doc! { "_id": id, "version": version, "deleted_at": null };
+
+    let updated = self
+      .questions()
+      .find_one_and_update(filter, update, options)
+      .await
+      .map_err(Self::database_error)?;
+
+    match updated {
+      Some(document) => Ok(vec![document.into_question()]),
+      None => {
+        let current = self.get_question_by_id(id).await?;
+        let hint = merge_hint(version, None, &current, &question);
+        Err(QError::VersionConflict(Box::new(hint)))
+      }
+    }
+  } // end fn update_question()
+
+  /// Marks the question specified by the given id as deleted, which hides its embedded
+  /// answers too.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the question to be deleted.
+  async fn delete_question(
+    &self,
+    id: i32,
+  ) -> Result<(), QError> {
+    let deleted = self
+      .questions()
+      .update_one(
+        doc! { "_id": id, "deleted_at": null },
+        doc! { "$set": { "deleted_at": DateTime::now() } },
+        None,
+      )
+      .await
+      .map_err(Self::database_error)?;
+
+    match deleted.matched_count {
+      0 => Err(QError::QuestionNotFound),
+      _ => Ok(()),
+    }
+  } // end fn delete_question()
+
+  /// Adds a new answer to an open question, embedded at the end of its answers.
+  ///
+  /// The status is checked by the filter of the update, so an answer cannot be added to
+  /// a question closed meanwhile.
+  ///
+  /// # Arguments
+  ///
+  /// * `answer`: Answer to be added.
+  async fn add_answer(
+    &self,
+    answer: NewAnswer,
+  ) -> Result<Answer, QError> {
+    let question_id = answer.question_id.value();
+    let document = AnswerDocument {
+      id: self.next_id("answers").await?,
+      code_blocks: extract_code_blocks(&answer.content),
+      content: answer.content,
+      created_on: DateTime::now(),
+    };
+    let pushed = bson::to_bson(&document)
+      .map_err(|err| QError::Internal(format!("cannot encode the answer. {}", err)))?;
+
+    let updated = self
+      .questions()
+      .update_one(
+        doc! {
+          "_id": question_id,
+          "status": QuestionStatus::Open.as_str(),
+          "deleted_at": null,
+        },
+        doc! { "$push": { "answers": pushed } },
+        None,
+      )
+      .await
+      .map_err(Self::database_error)?;
+
+    if updated.matched_count == 0 {
+      return match self.get_question_by_id(question_id).await {
+        Ok(_) => Err(QError::QuestionClosed),
+        Err(err) => Err(err),
+      };
+    }
+
+    Ok(Answer {
+      id: AnswerId::new(document.id),
+      content: document.content,
+      question_id: answer.question_id,
+      code_blocks: document.code_blocks,
+    })
+  } // end fn add_answer()
+}
+
+/// Converts a date and time in UTC into a MongoDB date, to the millisecond.
+///
+/// # Arguments
+///
+/// * `date`: Date and time in UTC.
+fn mongo_date(date: NaiveDateTime) -> DateTime {
+  DateTime::from_millis(date.and_utc().timestamp_millis())
+} // end fn mongo_date()
+
+/// Converts a MongoDB date into a date and time in UTC.
+///
+/// # Arguments
+///
+/// * `date`: MongoDB date.
+fn naive_date(date: DateTime) -> NaiveDateTime {
+  chrono::DateTime::from_timestamp_millis(date.timestamp_millis())
+    .map(|date| date.naive_utc())
+    .unwrap_or_default()
+} // end fn naive_date()
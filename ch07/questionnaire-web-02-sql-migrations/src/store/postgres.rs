@@ -0,0 +1,2947 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use futures::stream::BoxStream;
+use futures::TryStreamExt;
+use rand::Rng;
+use sqlx::pool::PoolConnection;
+use log::LevelFilter;
+use sqlx::postgres::{PgConnectOptions, PgConnection, PgDatabaseError, PgPoolOptions, PgRow};
+use sqlx::{
+  ConnectOptions, Connection, Executor, FromRow, PgPool, Postgres, QueryBuilder,
+  Transaction,
+};
+// This trait allows working with row results
+use sqlx::Row;
+
+use crate::types::answer::{Answer, AnswerId, NewAnswer};
+use crate::types::change::QuestionChanges;
+use crate::types::code::{extract_code_blocks, CodeBlock};
+use crate::types::degradation::{DegradationPolicy, Dependency};
+use crate::types::draft::{Draft, NewDraft};
+use crate::types::export::{ExportRow, ImportReport};
+use crate::types::health::PoolStatus;
+use crate::types::invitation::{generate_token, hash_token};
+use crate::types::migration::{ColumnTransition, MigrationPhase, MigrationStatus};
+use crate::types::query::{QuestionQuery, TagMatch};
+use crate::types::quiz::{max_points, QuizResult, QuizScores, ScoreCount, SubmissionScore};
+use crate::types::redirect::{QuestionRedirect, REDIRECT_MAX_HOPS};
+use crate::types::retention::RetentionRule;
+use crate::types::revision::merge_hint;
+use crate::types::response::{
+  percentage, NewResponse, OptionResult, QuestionResults, RatingResult,
+};
+use crate::types::survey::{
+  DisplayRule, NewSubmission, NewSurvey, QuestionSurvey, Survey, SurveyId,
+  SurveyQuestionResponse, SurveyResults,
+};
+use crate::types::seed::{SeedQuestions, SeedSummary};
+use crate::types::suggestion::{Suggestions, TitleSuggestion};
+use crate::types::tag::Tag;
+use crate::types::question::{
+  NewQuestion, Question, QuestionFilter, QuestionId, QuestionKind, QuestionStatus,
+};
+
+use handle_errors::errors::{QError, QuestionFields};
+
+use super::QuestionStore;
+
+/// SQL expression with the tag names of the question aliased as `q`, or `NULL` when
+/// the question has no tags.
+const QUESTION_TAGS_SQL: &str = r#"NULLIF(ARRAY(SELECT t.name 
+  FROM question_tags qt JOIN tags t ON t.id = qt.tag_id 
+  WHERE qt.question_id = q.id ORDER BY t.name), '{}') AS tags"#;
+
+/// SQL expressions with the kind, highest rating, option labels and option points of
+/// the question aliased as `q`. The labels and points are in order. The labels are
+/// `NULL` when the question has no options, and the points when no option is worth
+/// points.
+const QUESTION_KIND_SQL: &str = r#"q.kind, q.rating_max, NULLIF(ARRAY(SELECT o.label 
+  FROM question_options o 
+  WHERE o.question_id = q.id ORDER BY o.position), '{}') AS options, 
+  CASE WHEN EXISTS (SELECT 1 FROM question_options o 
+    WHERE o.question_id = q.id AND o.points <> 0) 
+  THEN ARRAY(SELECT o.points FROM question_options o 
+    WHERE o.question_id = q.id ORDER BY o.position) END AS points"#;
+
+/// SQL expressions with the languages, start lines and line counts of the code blocks
+/// of the answer aliased as `a`, in order.
+const ANSWER_CODE_SQL: &str = r#"ARRAY(SELECT c.language FROM answer_code_blocks c 
+  WHERE c.answer_id = a.id ORDER BY c.position) AS code_languages, 
+  ARRAY(SELECT c.start_line FROM answer_code_blocks c 
+  WHERE c.answer_id = a.id ORDER BY c.position) AS code_start_lines, 
+  ARRAY(SELECT c.line_count FROM answer_code_blocks c 
+  WHERE c.answer_id = a.id ORDER BY c.position) AS code_line_counts"#;
+
+/// Amount of answers whose code blocks are parsed in each transaction at startup.
+const CODE_PARSE_BATCH_SIZE: i64 = 500;
+
+/// Amount of the latest revisions of each question kept to hint the merges of updates
+/// based on older versions.
+const REVISIONS_KEPT: i32 = 50;
+
+/// SQL condition that keeps the responses `r` submitted to the survey bound as `$2`, or
+/// all the responses when it is `NULL`.
+const SURVEY_RESPONSES_SQL: &str = r#"AND ($2::integer IS NULL OR r.submission_id IN 
+  (SELECT s.id FROM survey_submissions s WHERE s.survey_id = $2))"#;
+
+/// SQL condition that keeps the surveys that accept responses at the moment.
+const SURVEY_OPEN_SQL: &str = r#"closed_on IS NULL 
+  AND (opens_on IS NULL OR opens_on <= now()) 
+  AND (closes_on IS NULL OR closes_on > now())"#;
+
+/// SQL condition that keeps the questions `q` deleted longer ago than the days bound as
+/// `$1`, and not linked to any survey.
+const PURGED_QUESTIONS_SQL: &str = r#"q.deleted_at < now() - make_interval(days => $1) 
+  AND NOT EXISTS (SELECT 1 FROM survey_questions sq WHERE sq.question_id = q.id)"#;
+
+/// SQL condition that keeps the drafts past their expiry date, or last saved longer ago
+/// than the days bound as `$1`.
+const PURGED_DRAFTS_SQL: &str =
+  "expires_on <= now() OR updated_on < now() - make_interval(days => $1)";
+
+/// SQL condition that keeps the question changes made longer ago than the days bound as
+/// `$1`.
+const PURGED_CHANGES_SQL: &str = "changed_on < now() - make_interval(days => $1)";
+
+/// Days a draft is kept after it was last saved, before it expires.
+const DRAFT_LIFETIME_DAYS: i32 = 30;
+
+/// Time the results of a survey are reused before they are computed again.
+const SURVEY_RESULTS_TTL: Duration = Duration::from_secs(60);
+
+/// Time to wait before the second attempt to connect to the database. It doubles after
+/// each failed attempt.
+const CONNECT_INITIAL_DELAY: Duration = Duration::from_millis(250);
+
+/// Longest time to wait between two attempts to connect to the database.
+const CONNECT_MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// Longest time to wait for a connection of the read replica before the reads fall
+/// back to the primary database.
+const REPLICA_ACQUIRE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Longest time to wait for the reply of the database to a health check.
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// SQLSTATE code of the errors for statements canceled after the statement timeout.
+const QUERY_CANCELED: &str = "57014";
+
+/// SQLSTATE code of the errors for rows that break a `NOT NULL` constraint.
+const NOT_NULL_VIOLATION: &str = "23502";
+
+/// SQLSTATE code of the errors for rows that reference rows that do not exist.
+const FOREIGN_KEY_VIOLATION: &str = "23503";
+
+/// SQLSTATE code of the errors for rows that break a unique constraint.
+const UNIQUE_VIOLATION: &str = "23505";
+
+/// SQLSTATE code of the errors for rows that break a `CHECK` constraint.
+const CHECK_VIOLATION: &str = "23514";
+
+/// Represents the data store of the application in a PostgreSQL database.
+#[derive(Debug, Clone)]
+pub struct PostgresStore {
+  /// Pool for database connections.
+  pub connection: PgPool,
+  /// Pool for connections to a read replica of the database, if any.
+  replica: Option<PgPool>,
+  /// Results of surveys computed recently, by survey ID, along with the time they
+  /// were computed.
+  survey_results: Arc<Mutex<HashMap<i32, (Instant, SurveyResults)>>>,
+  /// Whether the old columns of the schema transitions are still written, so builds
+  /// that only know them can run side by side with this one.
+  schema_compat: bool,
+  /// What to do with the reads when the read replica is unreachable.
+  replica_policy: DegradationPolicy,
+} // end struct PostgresStore
+
+impl PostgresStore {
+  /// Creates a new data store.
+  ///
+  /// The database server may still be starting, so failed connections are retried with
+  /// an exponential backoff and some random jitter until the deadline passes. The read
+  /// replica is connected to lazily, so the store starts even when it is unreachable.
+  ///
+  /// # Arguments
+  ///
+  /// * `db_url`: URL of the database server.
+  /// * `replica_url`: URL of a read replica of the database server, if any.
+  /// * `connect_deadline`: Longest time to keep trying to connect to the database.
+  /// * `statement_timeout`: Longest time a statement may run before the database
+  ///   cancels it, or zero for no limit.
+  /// * `slow_statement`: Running time from which statements are logged as slow.
+  /// * `schema_compat`: Whether the old columns of the schema transitions are still
+  ///   written.
+  /// * `replica_policy`: What to do with the reads when the read replica is
+  ///   unreachable.
+  pub async fn new(
+    db_url: &str,
+    replica_url: Option<&str>,
+    connect_deadline: Duration,
+    statement_timeout: Duration,
+    slow_statement: Duration,
+    schema_compat: bool,
+    replica_policy: DegradationPolicy,
+  ) -> Self {
+    let options = match Self::connect_options(db_url, statement_timeout, slow_statement) {
+      Ok(options) => options,
+      Err(err) => panic!("Database URL is not valid. {}", err),
+    };
+    let deadline = Instant::now() + connect_deadline;
+    let mut delay = CONNECT_INITIAL_DELAY;
+    let mut attempt: u32 = 1;
+
+    // The pool retries failed connections on its own without reporting them, so a
+    // single connection is opened first to find out when the database is up.
+    loop {
+      let remaining = deadline.saturating_duration_since(Instant::now());
+      let err = match tokio::time::timeout(remaining, PgConnection::connect_with(&options)).await {
+        Ok(Ok(connection)) => {
+          let _ = connection.close().await;
+          break;
+        }
+        Ok(Err(err)) => err.to_string(),
+        Err(_) => "the connection attempt timed out".to_string(),
+      };
+
+      // Wait between half and all of the delay, so many servers restarted at once do
+      // not retry in lockstep.
+      let wait = rand::thread_rng().gen_range(delay / 2..=delay);
+      if Instant::now() + wait >= deadline {
+        panic!(
+          "Database connection failed after {} attempts. {}",
+          attempt, err
+        );
+      }
+      tracing::event!(
+        tracing::Level::WARN,
+        "Database connection attempt {} failed, retrying in {:?}. {}",
+        attempt,
+        wait,
+        err
+      );
+
+      tokio::time::sleep(wait).await;
+      delay = (delay * 2).min(CONNECT_MAX_DELAY);
+      attempt += 1;
+    }
+
+    let db_pool = match PgPoolOptions::new()
+      .max_connections(5)
+      .connect_with(options)
+      .await
+    {
+      Ok(pool) => pool,
+      Err(err) => panic!("Database connection failed. {}", err),
+    };
+
+    let replica_pool = replica_url.map(|replica_url| {
+      match Self::connect_options(replica_url, statement_timeout, slow_statement) {
+        Ok(options) => PgPoolOptions::new()
+          .max_connections(5)
+          .acquire_timeout(REPLICA_ACQUIRE_TIMEOUT)
+          .connect_lazy_with(options),
+        Err(err) => panic!("Database replica URL is not valid. {}", err),
+      }
+    });
+
+    Self {
+      connection: db_pool,
+      replica: replica_pool,
+      survey_results: Arc::new(Mutex::new(HashMap::new())),
+      schema_compat,
+      replica_policy,
+    }
+  } // end fn new()
+
+  /// Gets the options to connect to a database, with a limit on the running time of
+  /// the statements and a threshold to log slow statements, along with their SQL.
+  ///
+  /// # Arguments
+  ///
+  /// * `db_url`: URL of the database server.
+  /// * `statement_timeout`: Longest time a statement may run, or zero for no limit.
+  /// * `slow_statement`: Running time from which statements are logged as slow.
+  fn connect_options(
+    db_url: &str,
+    statement_timeout: Duration,
+    slow_statement: Duration,
+  ) -> Result<PgConnectOptions, sqlx::Error> {
+    let mut options: PgConnectOptions = db_url.parse()?;
+    options.log_slow_statements(LevelFilter::Warn, slow_statement);
+
+    // The database takes the timeout in milliseconds, where 0 means no limit.
+    Ok(options.options([("statement_timeout", statement_timeout.as_millis())]))
+  } // end fn connect_options()
+
+  /// Restores the questions and answers of an export, keeping their IDs.
+  ///
+  /// Existing rows with the same IDs are overwritten, and the rows are written within a
+  /// single transaction. In a dry run, the transaction is rolled back, so only the
+  /// report of what would change is returned.
+  ///
+  /// # Arguments
+  ///
+  /// * `rows`: Rows of the export, already validated.
+  /// * `dry_run`: Whether to discard the changes after computing the report.
+  pub async fn import(
+    &self,
+    rows: Vec<ExportRow>,
+    dry_run: bool,
+  ) -> Result<ImportReport, QError> {
+    let mut transaction = self.connection.begin().await.map_err(Self::database_error)?;
+    let mut report = ImportReport {
+      dry_run,
+      ..ImportReport::default()
+    };
+
+    // The dump has no options, so only the questions that exist can be of a choice
+    // or rating kind.
+    let question_ids: Vec<i32> = rows.iter().map(|row| row.question_id.value()).collect();
+    let existing: Vec<i32> =
+      sqlx::query_scalar("SELECT id FROM questions WHERE id = ANY($1)")
+        .bind(&question_ids)
+        .fetch_all(&mut transaction)
+        .await
+        .map_err(Self::database_error)?;
+
+    let mut imported: Vec<i32> = Vec::new();
+    for row in rows.iter() {
+      let id = row.question_id.value();
+      if imported.contains(&id) {
+        continue;
+      }
+      imported.push(id);
+
+      if !existing.contains(&id) && row.kind != QuestionKind::Text.as_str() {
+        return Err(QError::InvalidParameter(format!(
+          "question {} does not exist, and {} questions cannot be created without options",
+          id, row.kind
+        )));
+      }
+
+      // The kind of existing questions is kept, since their options are not imported.
+      // They are updated rather than upserted, as the checks of their kind would fail
+      // on a new row without options.
+      let written: Option<bool> = match existing.contains(&id) {
+        true => sqlx::query_scalar(
+          r#"UPDATE questions 
+          SET title = $2, content = $3, status = $4, created_on = $5, deleted_at = $6, 
+            version = version + 1 
+          WHERE id = $1 
+            AND (title, content, status, created_on, deleted_at) 
+              IS DISTINCT FROM ($2, $3, $4, $5, $6) 
+          RETURNING false AS inserted"#,
+        ),
+        false => sqlx::query_scalar(
+          r#"INSERT INTO questions (id, title, content, status, created_on, deleted_at) 
+          VALUES ($1, $2, $3, $4, $5, $6) 
+          RETURNING true AS inserted"#,
+        ),
+      }
+      .bind(id)
+      .bind(&row.title)
+      .bind(&row.content)
+      .bind(&row.status)
+      .bind(row.question_created_on)
+      .bind(row.question_deleted_at)
+      .fetch_optional(&mut transaction)
+      .await
+      .map_err(Self::database_error)?;
+
+      let sql = format!("SELECT {} FROM questions q WHERE q.id = $1", QUESTION_TAGS_SQL);
+      let tags: Option<Vec<String>> = sqlx::query_scalar(&sql)
+        .bind(id)
+        .fetch_one(&mut transaction)
+        .await
+        .map_err(Self::database_error)?;
+      let mut tags = tags.unwrap_or_default();
+      tags.sort();
+      let mut new_tags = row.tags.clone().unwrap_or_default();
+      new_tags.sort();
+      new_tags.dedup();
+      let tags_changed = tags != new_tags;
+      if tags_changed {
+        Self::set_question_tags(&mut transaction, id, new_tags).await?;
+      }
+      // A change of the tags alone is a new version too, logged as a change.
+      if tags_changed && written.is_none() {
+        sqlx::query("UPDATE questions SET version = version + 1 WHERE id = $1")
+          .bind(id)
+          .execute(&mut transaction)
+          .await
+          .map_err(Self::database_error)?;
+      }
+
+      if written.is_some() || tags_changed {
+        Self::record_revision(&mut transaction, id).await?;
+      }
+      match (written, tags_changed) {
+        (Some(true), _) => report.questions_created += 1,
+        (Some(false), _) | (None, true) => report.questions_updated += 1,
+        (None, false) => report.questions_unchanged += 1,
+      }
+    }
+
+    let question_columns = self.answer_question_columns().1;
+    for row in rows.iter() {
+      let (answer_id, content, created_on) =
+        match (row.answer_id, &row.answer_content, row.answer_created_on) {
+          (Some(answer_id), Some(content), Some(created_on)) => {
+            (answer_id, content, created_on)
+          }
+          _ => continue,
+        };
+
+      let sql = format!(
+        r#"INSERT INTO answers (id, content, {}, created_on) 
+        VALUES ($1, $2, {}, $4) 
+        ON CONFLICT (id) DO UPDATE 
+        SET content = EXCLUDED.content, {}, created_on = EXCLUDED.created_on 
+        WHERE (answers.content, {}, answers.created_on) 
+          IS DISTINCT FROM (EXCLUDED.content, {}, EXCLUDED.created_on) 
+        RETURNING xmax = 0 AS inserted"#,
+        question_columns.join(", "),
+        vec!["$3"; question_columns.len()].join(", "),
+        Self::column_list(question_columns, "{0} = EXCLUDED.{0}"),
+        Self::column_list(question_columns, "answers.{0}"),
+        Self::column_list(question_columns, "EXCLUDED.{0}")
+      );
+      let written: Option<bool> = sqlx::query_scalar(&sql)
+      .bind(answer_id.value())
+      .bind(content)
+      .bind(row.question_id.value())
+      .bind(created_on)
+      .fetch_optional(&mut transaction)
+      .await
+      .map_err(Self::database_error)?;
+
+      if written.is_some() {
+        Self::set_code_blocks(&mut transaction, answer_id.value(), content).await?;
+      }
+      match written {
+        Some(true) => report.answers_created += 1,
+        Some(false) => report.answers_updated += 1,
+        None => report.answers_unchanged += 1,
+      }
+    }
+
+    if dry_run {
+      transaction.rollback().await.map_err(Self::database_error)?;
+      return Ok(report);
+    }
+
+    Self::sync_id_sequences(&mut transaction).await?;
+
+    transaction.commit().await.map_err(Self::database_error)?;
+
+    Ok(report)
+  } // end fn import()
+
+  /// Moves the ID sequences of the questions and the answers past their highest IDs,
+  /// after rows were written with given IDs.
+  ///
+  /// # Arguments
+  ///
+  /// * `transaction`: Transaction the rows were written in.
+  async fn sync_id_sequences(
+    transaction: &mut Transaction<'_, Postgres>
+  ) -> Result<(), QError> {
+    for table in ["questions", "answers"] {
+      let sql = format!(
+        r#"SELECT setval(pg_get_serial_sequence('{0}', 'id'), 
+          GREATEST((SELECT MAX(id) FROM {0}), 1))"#,
+        table
+      );
+      sqlx::query(&sql)
+        .execute(&mut *transaction)
+        .await
+        .map_err(Self::database_error)?;
+    }
+
+    Ok(())
+  } // end fn sync_id_sequences()
+
+  /// Searches the questions whose title or content match the given keywords.
+  ///
+  /// Matches in the title weigh more than matches in the content, and the questions
+  /// are ordered from the most to the least relevant. The amount of matching questions
+  /// across all the pages is returned along with the page.
+  ///
+  /// # Arguments
+  ///
+  /// * `keywords`: Search terms, in the syntax of web search engines.
+  /// * `offset`: Start index of a set of results, i.e. offset.
+  /// * `limit`: Amount of elements of the set of results. i.e. limit.
+  pub async fn search_questions(
+    &self,
+    keywords: &str,
+    offset: i32,
+    limit: Option<i32>,
+  ) -> Result<(Vec<Question>, i64), QError> {
+    let sql = format!(
+      r#"SELECT q.id, q.title, q.content, {}, q.status, q.version, {}, 
+        COUNT(*) OVER () AS total_count 
+      FROM questions q, websearch_to_tsquery('english', $1) AS search_query 
+      WHERE q.deleted_at IS NULL AND q.search_vector @@ search_query 
+      ORDER BY ts_rank(q.search_vector, search_query) DESC, q.id 
+      LIMIT $2 OFFSET $3"#,
+      QUESTION_TAGS_SQL,
+      QUESTION_KIND_SQL
+    );
+    let search = |offset: i32, limit: Option<i32>| {
+      sqlx::query(&sql)
+        .bind(keywords)
+        .bind(limit)
+        .bind(offset)
+        .try_map(|row: PgRow| {
+          Ok((Question::from_row(&row)?, row.try_get("total_count")?))
+        })
+        .fetch_all(&self.connection)
+    };
+
+    let rows: Vec<(Question, i64)> = search(offset, limit)
+      .await
+      .map_err(Self::database_error)?;
+    // A page past the last one has no rows to read the total from, so the first
+    // result is fetched for it.
+    let total = match rows.first() {
+      Some((_, total)) => *total,
+      None if offset > 0 => search(0, Some(1))
+        .await
+        .map_err(Self::database_error)?
+        .first()
+        .map_or(0, |(_, total)| *total),
+      None => 0,
+    };
+
+    Ok((rows.into_iter().map(|(question, _)| question).collect(), total))
+  } // end fn search_questions()
+
+  /// Gets the SQL condition and ordering of the given listing preset.
+  ///
+  /// # Arguments
+  ///
+  /// * `filter`: Preset that selects and orders the questions, if any.
+  fn filter_clauses(
+    &self,
+    filter: Option<QuestionFilter>,
+  ) -> (String, String) {
+    let column = self.answer_question_columns().0;
+    match filter {
+      None => (String::new(), String::new()),
+      Some(QuestionFilter::Newest) => (
+        String::new(),
+        "ORDER BY q.created_on DESC, q.id DESC".to_string(),
+      ),
+      Some(QuestionFilter::Unanswered) => (
+        format!("AND NOT EXISTS (SELECT 1 FROM answers a WHERE a.{} = q.id)", column),
+        "ORDER BY q.created_on DESC, q.id DESC".to_string(),
+      ),
+      Some(QuestionFilter::Active) => (
+        String::new(),
+        format!(
+          r#"ORDER BY GREATEST(q.created_on, 
+          (SELECT MAX(a.created_on) FROM answers a WHERE a.{} = q.id)) DESC, 
+          q.id DESC"#,
+          column
+        ),
+      ),
+    }
+  } // end fn filter_clauses()
+  /// Gets the titles of the questions and the names of the tags that contain the given
+  /// text, those that start with it first. The trigram indexes of both are used.
+  ///
+  /// # Arguments
+  ///
+  /// * `text`: Text typed so far.
+  /// * `limit`: Maximum amount of titles, and of tags, to get.
+  pub async fn suggest(
+    &self,
+    text: &str,
+    limit: i32,
+  ) -> Result<Suggestions, QError> {
+    // The wildcards of LIKE typed by the user are matched literally.
+    let escaped = text
+      .replace('\\', "\\\\")
+      .replace('%', "\\%")
+      .replace('_', "\\_");
+    let contains = format!("%{}%", escaped);
+    let starts_with = format!("{}%", escaped);
+
+    let titles: Vec<TitleSuggestion> = sqlx::query_as(
+      r#"SELECT q.id, q.title 
+      FROM questions q 
+      WHERE q.deleted_at IS NULL AND q.title ILIKE $1 
+      ORDER BY q.title ILIKE $2 DESC, similarity(q.title, $3) DESC, q.id 
+      LIMIT $4"#,
+    )
+    .bind(&contains)
+    .bind(&starts_with)
+    .bind(text)
+    .bind(limit)
+    .fetch_all(&self.connection)
+    .await
+    .map_err(Self::database_error)?;
+
+    let tags: Vec<String> = sqlx::query_scalar(
+      r#"SELECT t.name 
+      FROM tags t 
+      LEFT JOIN question_tags qt ON qt.tag_id = t.id 
+      LEFT JOIN questions q ON q.id = qt.question_id AND q.deleted_at IS NULL 
+      WHERE t.name ILIKE $1 
+      GROUP BY t.id, t.name 
+      ORDER BY t.name ILIKE $2 DESC, COUNT(q.id) DESC, t.name 
+      LIMIT $3"#,
+    )
+    .bind(&contains)
+    .bind(&starts_with)
+    .bind(limit)
+    .fetch_all(&self.connection)
+    .await
+    .map_err(Self::database_error)?;
+
+    Ok(Suggestions { titles, tags })
+  } // end fn suggest()
+  /// Gets a random question, optionally among the ones with the given tag.
+  ///
+  /// Instead of sorting the whole table by a random value, a random ID is picked
+  /// between the lowest and highest ones, and the first question from it is taken, so
+  /// both queries use the primary key. Questions after a gap of deleted IDs are picked
+  /// more often, which is fine for quizzes.
+  ///
+  /// # Arguments
+  ///
+  /// * `tag`: Name of the tag the question must have, if any.
+  pub async fn get_random_question(
+    &self,
+    tag: Option<&str>,
+  ) -> Result<Question, QError> {
+    let tag_condition = r#"($1::varchar IS NULL OR EXISTS (SELECT 1 
+      FROM question_tags qt JOIN tags t ON t.id = qt.tag_id 
+      WHERE qt.question_id = q.id AND t.name = $1))"#;
+    let mut connection = self.read_connection().await?;
+
+    let sql = format!(
+      r#"SELECT MIN(q.id) AS min_id, MAX(q.id) AS max_id 
+      FROM questions q 
+      WHERE q.deleted_at IS NULL AND {}"#,
+      tag_condition
+    );
+    let (min_id, max_id): (Option<i32>, Option<i32>) = sqlx::query_as(&sql)
+      .bind(tag)
+      .fetch_one(&mut *connection)
+      .await
+      .map_err(Self::database_error)?;
+    let start = match (min_id, max_id) {
+      (Some(min_id), Some(max_id)) => rand::thread_rng().gen_range(min_id..=max_id),
+      _ => return Err(QError::QuestionNotFound),
+    };
+
+    let sql = format!(
+      r#"SELECT q.id, q.title, q.content, {}, q.status, q.version, {} 
+      FROM questions q 
+      WHERE q.deleted_at IS NULL AND {} AND q.id >= $2 
+      ORDER BY q.id 
+      LIMIT 1"#,
+      QUESTION_TAGS_SQL,
+      QUESTION_KIND_SQL,
+      tag_condition
+    );
+    // The question with the highest ID may be deleted in between, leaving none.
+    sqlx::query_as::<_, Question>(&sql)
+      .bind(tag)
+      .bind(start)
+      .fetch_optional(&mut *connection)
+      .await
+      .map_err(Self::database_error)?
+      .ok_or(QError::QuestionNotFound)
+  } // end fn get_random_question()
+
+  /// Gets the questions created, updated and deleted since the given change cursor,
+  /// along with the cursor to get the next changes with.
+  ///
+  /// The cursor is the oldest transaction still running when the changes are read.
+  /// The changes are listed by the transaction that made them, so those of the
+  /// transactions running meanwhile are listed with the next cursor instead of being
+  /// skipped.
+  ///
+  /// # Arguments
+  ///
+  /// * `since`: Cursor of a previous reply, or 0 to get all the changes.
+  pub async fn get_question_changes(
+    &self,
+    since: i64,
+  ) -> Result<QuestionChanges, QError> {
+    let mut connection = self.read_connection().await?;
+
+    let cursor: i64 = sqlx::query_scalar(
+      "SELECT pg_snapshot_xmin(pg_current_snapshot())::text::bigint AS cursor",
+    )
+    .fetch_one(&mut *connection)
+    .await
+    .map_err(Self::database_error)?;
+    let mut changes = QuestionChanges {
+      cursor: cursor.max(since),
+      ..QuestionChanges::default()
+    };
+
+    let rows: Vec<(i32, String, bool)> = sqlx::query_as(
+      r#"SELECT c.question_id, 
+        (array_agg(c.change ORDER BY c.id DESC))[1] AS last_change, 
+        bool_or(c.change = 'created') AS created 
+      FROM question_changes c 
+      WHERE c.transaction_id >= $1::text::xid8 AND c.transaction_id < $2::text::xid8 
+      GROUP BY c.question_id 
+      ORDER BY c.question_id"#,
+    )
+    .bind(since.to_string())
+    .bind(cursor.to_string())
+    .fetch_all(&mut *connection)
+    .await
+    .map_err(Self::database_error)?;
+
+    for (id, last_change, created) in rows {
+      let id = QuestionId::new(id);
+      match (last_change.as_str(), created) {
+        ("deleted", _) => changes.deleted.push(id),
+        (_, true) => changes.created.push(id),
+        _ => changes.updated.push(id),
+      }
+    }
+
+    Ok(changes)
+  } // end fn get_question_changes()
+  /// Sets the redirect of the links to a replaced question to the question that
+  /// replaced it, replacing the previous redirect, if any.
+  ///
+  /// # Arguments
+  ///
+  /// * `from_id`: Unique identifier (ID) of the replaced question.
+  /// * `target_id`: Unique identifier (ID) of the question that replaced it.
+  pub async fn set_question_redirect(
+    &self,
+    from_id: i32,
+    target_id: i32,
+  ) -> Result<QuestionRedirect, QError> {
+    if from_id == target_id {
+      return Err(QError::InvalidParameter(
+        "a question cannot be redirected to itself".to_string(),
+      ));
+    }
+
+    sqlx::query_as(
+      r#"INSERT INTO question_redirects (from_id, target_id) 
+      VALUES ($1, $2) 
+      ON CONFLICT (from_id) DO UPDATE 
+      SET target_id = EXCLUDED.target_id, created_on = now() 
+      RETURNING from_id, target_id, created_on"#,
+    )
+    .bind(from_id)
+    .bind(target_id)
+    .fetch_one(&self.connection)
+    .await
+    .map_err(Self::database_error)
+  } // end fn set_question_redirect()
+
+  /// Removes the redirect of the links to a replaced question.
+  ///
+  /// Returns the amount of redirects removed.
+  ///
+  /// # Arguments
+  ///
+  /// * `from_id`: Unique identifier (ID) of the replaced question.
+  pub async fn delete_question_redirect(
+    &self,
+    from_id: i32,
+  ) -> Result<u64, QError> {
+    sqlx::query("DELETE FROM question_redirects WHERE from_id = $1")
+      .bind(from_id)
+      .execute(&self.connection)
+      .await
+      .map(|result| result.rows_affected())
+      .map_err(Self::database_error)
+  } // end fn delete_question_redirect()
+
+  /// Checks that the database replies to a trivial query in time.
+  ///
+  /// Returns the time the database took to reply.
+  pub async fn health_check(&self) -> Result<Duration, QError> {
+    Self::check_pool(&self.connection).await
+  } // end fn health_check()
+
+  /// Checks that the read replica replies to a trivial query in time, if there is one.
+  ///
+  /// Returns the time the replica took to reply.
+  pub async fn replica_health_check(&self) -> Option<Result<Duration, QError>> {
+    match &self.replica {
+      Some(replica) => Some(Self::check_pool(replica).await),
+      None => None,
+    }
+  } // end fn replica_health_check()
+
+  /// Checks that the database of a pool replies to a trivial query in time.
+  ///
+  /// # Arguments
+  ///
+  /// * `pool`: Pool of connections to the database.
+  async fn check_pool(pool: &PgPool) -> Result<Duration, QError> {
+    let started = Instant::now();
+    let query = sqlx::query("SELECT 1").execute(pool);
+
+    match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, query).await {
+      Ok(Ok(_)) => Ok(started.elapsed()),
+      Ok(Err(err)) => Err(Self::database_error(err)),
+      Err(_) => Err(QError::DatabaseTimeout),
+    }
+  } // end fn check_pool()
+
+  /// Gets the versions of the migrations applied to the database along with their
+  /// checksums, or none when no migration was ever run.
+  pub async fn applied_migrations(&self) -> Result<Vec<(i64, Vec<u8>)>, QError> {
+    let exists: bool =
+      sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL AS found")
+        .fetch_one(&self.connection)
+        .await
+        .map_err(Self::database_error)?;
+    if !exists {
+      return Ok(Vec::new());
+    }
+
+    sqlx::query_as(
+      "SELECT version, checksum FROM _sqlx_migrations WHERE success ORDER BY version",
+    )
+    .fetch_all(&self.connection)
+    .await
+    .map_err(Self::database_error)
+  } // end fn applied_migrations()
+
+  /// Gets the usage of the pools of connections to the primary database and to the read
+  /// replica, if any.
+  pub fn pool_status(&self) -> (PoolStatus, Option<PoolStatus>) {
+    let status = |pool: &PgPool| {
+      let idle = pool.num_idle();
+      PoolStatus {
+        size: pool.size(),
+        idle,
+        active: (pool.size() as usize).saturating_sub(idle),
+      }
+    };
+
+    (status(&self.connection), self.replica.as_ref().map(status))
+  } // end fn pool_status()
+
+  /// Gets the column of the answers with their question that the reads use, and the
+  /// ones the writes set, all to the same value.
+  ///
+  /// `answers.corresponding_question` is being replaced by `answers.question_id`, so
+  /// the old column is read and written as long as the compatibility mode is on.
+  fn answer_question_columns(&self) -> (&'static str, &'static [&'static str]) {
+    match MigrationPhase::from_compat_mode(self.schema_compat) {
+      MigrationPhase::Expand => (
+        "corresponding_question",
+        &["corresponding_question", "question_id"],
+      ),
+      MigrationPhase::Contract => ("question_id", &["question_id"]),
+    }
+  } // end fn answer_question_columns()
+
+  /// Joins the given columns with commas, each one formatted with a template where
+  /// `{0}` is replaced with the column name.
+  ///
+  /// # Arguments
+  ///
+  /// * `columns`: Names of the columns.
+  /// * `template`: SQL written for each column.
+  fn column_list(
+    columns: &[&str],
+    template: &str,
+  ) -> String {
+    columns
+      .iter()
+      .map(|column| template.replace("{0}", column))
+      .collect::<Vec<_>>()
+      .join(", ")
+  } // end fn column_list()
+
+  /// Fills in the new columns of the schema transitions for the rows written by builds
+  /// that only know the old columns, so the new columns can be read alone.
+  ///
+  /// Returns the amount of rows filled in.
+  pub async fn backfill_transitions(&self) -> Result<u64, QError> {
+    sqlx::query(
+      r#"UPDATE answers SET question_id = corresponding_question 
+      WHERE question_id IS NULL AND corresponding_question IS NOT NULL"#,
+    )
+    .execute(&self.connection)
+    .await
+    .map(|done| done.rows_affected())
+    .map_err(Self::database_error)
+  } // end fn backfill_transitions()
+
+  /// Gets the latest migration applied to the database and the phase of the schema
+  /// transitions, with the amount of rows still missing a value in the new columns.
+  pub async fn migration_status(&self) -> Result<MigrationStatus, QError> {
+    let latest_migration: Option<i64> = sqlx::query_scalar(
+      "SELECT MAX(version) AS version FROM _sqlx_migrations WHERE success",
+    )
+    .fetch_one(&self.connection)
+    .await
+    .map_err(Self::database_error)?;
+
+    let pending_rows: i64 = sqlx::query_scalar(
+      "SELECT COUNT(*) AS pending FROM answers WHERE question_id IS NULL",
+    )
+    .fetch_one(&self.connection)
+    .await
+    .map_err(Self::database_error)?;
+
+    Ok(MigrationStatus {
+      latest_migration,
+      compat_mode: self.schema_compat,
+      transitions: vec![ColumnTransition {
+        table: "answers".to_string(),
+        old_column: "corresponding_question".to_string(),
+        new_column: "question_id".to_string(),
+        phase: MigrationPhase::from_compat_mode(self.schema_compat),
+        pending_rows,
+      }],
+    })
+  } // end fn migration_status()
+
+  /// Acquires a connection for read-only queries, from the read replica when there is
+  /// one, or from the primary database when there is none. When the replica is
+  /// unreachable, the reads fail or go to the primary database, following its policy.
+  ///
+  /// The replica may lag behind the primary database, so only the reads that can
+  /// return slightly stale data should use this connection.
+  async fn read_connection(&self) -> Result<PoolConnection<Postgres>, QError> {
+    if let Some(replica) = &self.replica {
+      match (replica.acquire().await, self.replica_policy) {
+        (Ok(connection), _) => return Ok(connection),
+        (Err(err), DegradationPolicy::Fail) => {
+          tracing::event!(
+            tracing::Level::ERROR,
+            "Database replica is unreachable. {}",
+            err
+          );
+          return Err(QError::DependencyUnavailable("database replica".to_string()));
+        }
+        (Err(err), policy) => Dependency::Replica.log_degraded(policy, err),
+      }
+    }
+
+    self.connection.acquire().await.map_err(Self::database_error)
+  } // end fn read_connection()
+
+  /// Fetches the question specified by the given id, along with its tags.
+  ///
+  /// # Arguments
+  ///
+  /// * `executor`: Connection pool or transaction to run the query on.
+  /// * `id`: Unique identifier (ID) of the question.
+  async fn fetch_question<'e, E>(
+    executor: E,
+    id: i32,
+  ) -> Result<Option<Question>, QError>
+  where
+    E: Executor<'e, Database = Postgres>,
+  {
+    let sql = format!(
+      r#"SELECT q.id, q.title, q.content, {}, q.status, q.version, {} 
+      FROM questions q 
+      WHERE q.id = $1 AND q.deleted_at IS NULL"#,
+      QUESTION_TAGS_SQL,
+      QUESTION_KIND_SQL
+    );
+
+    sqlx::query_as::<_, Question>(&sql)
+      .bind(id)
+      .fetch_optional(executor)
+      .await
+      .map_err(Self::database_error)
+  } // end fn fetch_question()
+
+  /// Replaces the tags of a question, creating the tags that do not exist yet.
+  ///
+  /// # Arguments
+  ///
+  /// * `transaction`: Transaction the question is being written in.
+  /// * `id`: Unique identifier (ID) of the question.
+  /// * `tags`: Names of the tags for the question.
+  async fn set_question_tags(
+    transaction: &mut Transaction<'_, Postgres>,
+    id: i32,
+    tags: Vec<String>,
+  ) -> Result<(), QError> {
+    sqlx::query("DELETE FROM question_tags WHERE question_id = $1")
+      .bind(id)
+      .execute(&mut *transaction)
+      .await
+      .map_err(Self::database_error)?;
+
+    if tags.is_empty() {
+      return Ok(());
+    }
+
+    sqlx::query(
+      r#"INSERT INTO tags (name) 
+      SELECT DISTINCT unnest($1::text[]) 
+      ON CONFLICT (name) DO NOTHING"#,
+    )
+    .bind(&tags)
+    .execute(&mut *transaction)
+    .await
+    .map_err(Self::database_error)?;
+
+    sqlx::query(
+      r#"INSERT INTO question_tags (question_id, tag_id) 
+      SELECT $1, id FROM tags WHERE name = ANY($2)"#,
+    )
+    .bind(id)
+    .bind(&tags)
+    .execute(&mut *transaction)
+    .await
+    .map_err(Self::database_error)?;
+
+    Ok(())
+  } // end fn set_question_tags()
+
+  /// Records the title, content and tags of the current version of a question, and
+  /// forgets the revisions older than the ones kept.
+  ///
+  /// # Arguments
+  ///
+  /// * `transaction`: Transaction the question is being written in.
+  /// * `id`: Unique identifier (ID) of the question.
+  async fn record_revision(
+    transaction: &mut Transaction<'_, Postgres>,
+    id: i32,
+  ) -> Result<(), QError> {
+    sqlx::query(
+      r#"INSERT INTO question_revisions (question_id, version, title, content, tags) 
+      SELECT q.id, q.version, q.title, q.content, 
+        ARRAY(SELECT t.name FROM question_tags qt JOIN tags t ON t.id = qt.tag_id 
+          WHERE qt.question_id = q.id ORDER BY t.name) 
+      FROM questions q 
+      WHERE q.id = $1 
+      ON CONFLICT (question_id, version) DO UPDATE 
+      SET title = EXCLUDED.title, content = EXCLUDED.content, tags = EXCLUDED.tags"#,
+    )
+    .bind(id)
+    .execute(&mut *transaction)
+    .await
+    .map_err(Self::database_error)?;
+
+    sqlx::query(
+      r#"DELETE FROM question_revisions 
+      WHERE question_id = $1 AND version <= 
+        (SELECT version FROM questions WHERE id = $1) - $2"#,
+    )
+    .bind(id)
+    .bind(REVISIONS_KEPT)
+    .execute(&mut *transaction)
+    .await
+    .map_err(Self::database_error)?;
+
+    Ok(())
+  } // end fn record_revision()
+
+  /// Replaces the code blocks of an answer with the ones of its contents, and marks
+  /// them as parsed.
+  ///
+  /// Returns the code blocks of the contents.
+  ///
+  /// # Arguments
+  ///
+  /// * `transaction`: Transaction the answer is being written in.
+  /// * `id`: Unique identifier (ID) of the answer.
+  /// * `content`: Contents of the answer.
+  async fn set_code_blocks(
+    transaction: &mut Transaction<'_, Postgres>,
+    id: i32,
+    content: &str,
+  ) -> Result<Vec<CodeBlock>, QError> {
+    let code_blocks = extract_code_blocks(content);
+
+    sqlx::query("DELETE FROM answer_code_blocks WHERE answer_id = $1")
+      .bind(id)
+      .execute(&mut *transaction)
+      .await
+      .map_err(Self::database_error)?;
+
+    if !code_blocks.is_empty() {
+      let languages: Vec<Option<String>> =
+        code_blocks.iter().map(|block| block.language.clone()).collect();
+      let start_lines: Vec<i32> =
+        code_blocks.iter().map(|block| block.start_line).collect();
+      let line_counts: Vec<i32> =
+        code_blocks.iter().map(|block| block.line_count).collect();
+      sqlx::query(
+        r#"INSERT INTO answer_code_blocks 
+          (answer_id, position, language, start_line, line_count) 
+        SELECT $1, c.position, c.language, c.start_line, c.line_count 
+        FROM unnest($2::varchar[], $3::integer[], $4::integer[]) 
+          WITH ORDINALITY AS c (language, start_line, line_count, position)"#,
+      )
+      .bind(id)
+      .bind(languages)
+      .bind(start_lines)
+      .bind(line_counts)
+      .execute(&mut *transaction)
+      .await
+      .map_err(Self::database_error)?;
+    }
+
+    sqlx::query("UPDATE answers SET code_parsed = true WHERE id = $1")
+      .bind(id)
+      .execute(&mut *transaction)
+      .await
+      .map_err(Self::database_error)?;
+
+    Ok(code_blocks)
+  } // end fn set_code_blocks()
+
+  /// Parses the code blocks of the answers that were written before they were parsed,
+  /// or by builds that do not parse them, in batches.
+  ///
+  /// Returns the amount of answers parsed.
+  pub async fn parse_code_blocks(&self) -> Result<u64, QError> {
+    let mut parsed = 0;
+
+    loop {
+      let mut transaction = self.connection.begin().await.map_err(Self::database_error)?;
+      let answers: Vec<(i32, String)> = sqlx::query_as(
+        r#"SELECT id, content 
+        FROM answers 
+        WHERE NOT code_parsed 
+        ORDER BY id 
+        LIMIT $1 
+        FOR UPDATE SKIP LOCKED"#,
+      )
+      .bind(CODE_PARSE_BATCH_SIZE)
+      .fetch_all(&mut transaction)
+      .await
+      .map_err(Self::database_error)?;
+      if answers.is_empty() {
+        return Ok(parsed);
+      }
+
+      for (id, content) in answers {
+        Self::set_code_blocks(&mut transaction, id, &content).await?;
+        parsed += 1;
+      }
+      transaction.commit().await.map_err(Self::database_error)?;
+    }
+  } // end fn parse_code_blocks()
+
+  /// Loads the questions and answers of a fixture file, keeping their IDs.
+  ///
+  /// Existing rows with the same IDs are updated instead of duplicated, and deleted
+  /// questions are restored, so the same file can be loaded again and again.
+  ///
+  /// # Arguments
+  ///
+  /// * `questions`: Questions of the fixture file, by their ID.
+  pub async fn seed(
+    &self,
+    questions: SeedQuestions,
+  ) -> Result<SeedSummary, QError> {
+    let mut questions: Vec<_> = questions.into_values().collect();
+    questions.sort_by(|a, b| a.id.cmp(&b.id));
+
+    let mut transaction = self.connection.begin().await.map_err(Self::database_error)?;
+    let mut summary = SeedSummary {
+      questions: 0,
+      answers: 0,
+    };
+    let question_columns = self.answer_question_columns().1;
+
+    for question in questions {
+      let id = question.id.parse::<QuestionId>()?.value();
+      sqlx::query(
+        r#"INSERT INTO questions (id, title, content) 
+        VALUES ($1, $2, $3) 
+        ON CONFLICT (id) DO UPDATE 
+        SET title = EXCLUDED.title, content = EXCLUDED.content, deleted_at = NULL, 
+          version = questions.version + 1"#,
+      )
+      .bind(id)
+      .bind(question.title)
+      .bind(question.content)
+      .execute(&mut transaction)
+      .await
+      .map_err(Self::database_error)?;
+
+      Self::set_question_tags(&mut transaction, id, question.tags.unwrap_or_default())
+        .await?;
+      Self::record_revision(&mut transaction, id).await?;
+      summary.questions += 1;
+
+      for answer in question.answers {
+        let answer_id = answer.id.parse::<AnswerId>()?.value();
+        sqlx::query(&format!(
+          r#"INSERT INTO answers (id, content, {}) 
+          VALUES ($1, $2, {}) 
+          ON CONFLICT (id) DO UPDATE 
+          SET content = EXCLUDED.content, {}"#,
+          question_columns.join(", "),
+          vec!["$3"; question_columns.len()].join(", "),
+          Self::column_list(question_columns, "{0} = EXCLUDED.{0}")
+        ))
+        .bind(answer_id)
+        .bind(&answer.content)
+        .bind(id)
+        .execute(&mut transaction)
+        .await
+        .map_err(Self::database_error)?;
+        Self::set_code_blocks(&mut transaction, answer_id, &answer.content).await?;
+        summary.answers += 1;
+      }
+    }
+
+    Self::sync_id_sequences(&mut transaction).await?;
+
+    transaction.commit().await.map_err(Self::database_error)?;
+
+    Ok(summary)
+  } // end fn seed()
+
+  /// Gets all the tags along with the amount of questions using each one.
+  pub async fn get_tags(&self) -> Result<Vec<Tag>, QError> {
+    sqlx::query_as(
+      r#"SELECT t.name, COUNT(q.id) AS usage_count 
+      FROM tags t 
+      LEFT JOIN question_tags qt ON qt.tag_id = t.id 
+      LEFT JOIN questions q ON q.id = qt.question_id AND q.deleted_at IS NULL 
+      GROUP BY t.id, t.name 
+      ORDER BY usage_count DESC, t.name"#,
+    )
+    .fetch_all(&self.connection)
+    .await
+    .map_err(Self::database_error)
+  } // end fn get_tags()
+
+  /// Gets the questions that have the given tag.
+  ///
+  /// # Arguments
+  ///
+  /// * `name`: Name of the tag.
+  pub async fn get_tag_questions(
+    &self,
+    name: &str,
+  ) -> Result<Vec<Question>, QError> {
+    let tag_id: Option<i32> = sqlx::query_scalar("SELECT id FROM tags WHERE name = $1")
+      .bind(name)
+      .fetch_optional(&self.connection)
+      .await
+      .map_err(Self::database_error)?;
+
+    let tag_id = match tag_id {
+      Some(tag_id) => tag_id,
+      None => return Err(QError::TagNotFound),
+    };
+
+    let sql = format!(
+      r#"SELECT q.id, q.title, q.content, {}, q.status, q.version, {} 
+      FROM questions q JOIN question_tags qt ON qt.question_id = q.id 
+      WHERE qt.tag_id = $1 AND q.deleted_at IS NULL 
+      ORDER BY q.id"#,
+      QUESTION_TAGS_SQL,
+      QUESTION_KIND_SQL
+    );
+
+    sqlx::query_as::<_, Question>(&sql)
+      .bind(tag_id)
+      .fetch_all(&self.connection)
+      .await
+      .map_err(Self::database_error)
+  } // end fn get_tag_questions()
+
+  /// Changes the status of the question specified by the given id.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the question.
+  /// * `status`: New status of the question.
+  pub async fn set_question_status(
+    &self,
+    id: i32,
+    status: QuestionStatus,
+  ) -> Result<Question, QError> {
+    let sql = format!(
+      r#"UPDATE questions q 
+      SET status = $1, version = q.version + 1 
+      WHERE q.id = $2 AND q.deleted_at IS NULL 
+      RETURNING q.id, q.title, q.content, {}, q.status, q.version, {}"#,
+      QUESTION_TAGS_SQL,
+      QUESTION_KIND_SQL
+    );
+
+    match sqlx::query_as::<_, Question>(&sql)
+      .bind(status.as_str())
+      .bind(id)
+      .fetch_optional(&self.connection)
+      .await
+    {
+      Ok(Some(question)) => Ok(question),
+      Ok(None) => Err(QError::QuestionNotFound),
+      Err(err) => {
+        tracing::event!(tracing::Level::ERROR, "{:?}", err);
+        Err(QError::DatabaseQueryError(err))
+      }
+    }
+  } // end fn set_question_status()
+
+  /// Gets the surveys that link the question specified by the given id, along with
+  /// the position and required flag the question has in each one.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the question.
+  pub async fn get_question_surveys(
+    &self,
+    id: i32,
+  ) -> Result<Vec<QuestionSurvey>, QError> {
+    if Self::fetch_question(&self.connection, id).await?.is_none() {
+      return Err(QError::QuestionNotFound);
+    }
+
+    sqlx::query_as::<_, QuestionSurvey>(
+      r#"SELECT s.id AS survey_id, s.title, sq.position, sq.required, 
+        s.closed_on IS NOT NULL OR COALESCE(s.closes_on <= now(), false) AS closed 
+      FROM surveys s JOIN survey_questions sq ON sq.survey_id = s.id 
+      WHERE sq.question_id = $1 
+      ORDER BY s.id"#,
+    )
+    .bind(id)
+    .fetch_all(&self.connection)
+    .await
+    .map_err(Self::database_error)
+  } // end fn get_question_surveys()
+
+  /// Restores a deleted question that has not been purged yet.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the question to be restored.
+  pub async fn restore_question(
+    &self,
+    id: i32,
+  ) -> Result<Question, QError> {
+    let sql = format!(
+      r#"UPDATE questions q 
+      SET deleted_at = NULL, version = q.version + 1 
+      WHERE q.id = $1 AND q.deleted_at IS NOT NULL 
+      RETURNING q.id, q.title, q.content, {}, q.status, q.version, {}"#,
+      QUESTION_TAGS_SQL,
+      QUESTION_KIND_SQL
+    );
+
+    match sqlx::query_as::<_, Question>(&sql)
+      .bind(id)
+      .fetch_optional(&self.connection)
+      .await
+    {
+      Ok(Some(question)) => Ok(question),
+      Ok(None) => Err(QError::QuestionNotFound),
+      Err(err) => {
+        tracing::event!(tracing::Level::ERROR, "{:?}", err);
+        Err(QError::DatabaseQueryError(err))
+      }
+    }
+  } // end fn restore_question()
+
+  /// Removes the data older than the retention period of the given rule.
+  ///
+  /// In dry-run mode, nothing is removed, and the rows matching the same conditions as
+  /// a real run are counted instead.
+  ///
+  /// Returns the amount of rows removed, or that would be removed.
+  ///
+  /// # Arguments
+  ///
+  /// * `rule`: Retention rule applied.
+  /// * `retention_days`: Days the data is kept.
+  /// * `dry_run`: Whether to only count the rows instead of removing them.
+  pub async fn apply_retention_rule(
+    &self,
+    rule: RetentionRule,
+    retention_days: i32,
+    dry_run: bool,
+  ) -> Result<u64, QError> {
+    if dry_run {
+      let sql = match rule {
+        RetentionRule::DeletedQuestions => {
+          format!("SELECT COUNT(*) FROM questions q WHERE {}", PURGED_QUESTIONS_SQL)
+        }
+        RetentionRule::Drafts => {
+          format!("SELECT COUNT(*) FROM survey_drafts WHERE {}", PURGED_DRAFTS_SQL)
+        }
+        RetentionRule::QuestionChanges => {
+          format!("SELECT COUNT(*) FROM question_changes WHERE {}", PURGED_CHANGES_SQL)
+        }
+      };
+      let count: i64 = sqlx::query_scalar(&sql)
+        .bind(retention_days)
+        .fetch_one(&self.connection)
+        .await
+        .map_err(Self::database_error)?;
+
+      Ok(count as u64)
+    } else {
+      let mut transaction =
+        self.connection.begin().await.map_err(Self::database_error)?;
+
+      let removed = match rule {
+        RetentionRule::DeletedQuestions => {
+          Self::purge_deleted_questions(&mut transaction, retention_days).await?
+        }
+        RetentionRule::Drafts => {
+          Self::purge_drafts(&mut transaction, retention_days).await?
+        }
+        RetentionRule::QuestionChanges => {
+          Self::purge_question_changes(&mut transaction, retention_days).await?
+        }
+      };
+
+      transaction.commit().await.map_err(Self::database_error)?;
+
+      Ok(removed)
+    }
+  } // end fn apply_retention_rule()
+
+  /// Permanently removes the questions deleted longer ago than the given amount of
+  /// days, along with their answers.
+  ///
+  /// The questions linked to surveys are kept, so the surveys keep their responses.
+  ///
+  /// Returns the amount of purged questions.
+  ///
+  /// # Arguments
+  ///
+  /// * `transaction`: Transaction the questions are removed within.
+  /// * `retention_days`: Days a deleted question can still be restored.
+  async fn purge_deleted_questions(
+    transaction: &mut Transaction<'_, Postgres>,
+    retention_days: i32,
+  ) -> Result<u64, QError> {
+    // Answers written by either build are deleted, whichever column they have
+    let sql = format!(
+      r#"DELETE FROM answers 
+      WHERE COALESCE(question_id, corresponding_question) IN (
+        SELECT q.id FROM questions q WHERE {}
+      )"#,
+      PURGED_QUESTIONS_SQL
+    );
+    sqlx::query(&sql)
+      .bind(retention_days)
+      .execute(&mut *transaction)
+      .await
+      .map_err(Self::database_error)?;
+
+    let sql = format!("DELETE FROM questions q WHERE {}", PURGED_QUESTIONS_SQL);
+    let purged = sqlx::query(&sql)
+      .bind(retention_days)
+      .execute(&mut *transaction)
+      .await
+      .map_err(Self::database_error)?;
+
+    Ok(purged.rows_affected())
+  } // end fn purge_deleted_questions()
+
+  /// Deletes the drafts last saved longer ago than the given amount of days, and the
+  /// ones past their expiry date.
+  ///
+  /// Returns the amount of deleted drafts.
+  ///
+  /// # Arguments
+  ///
+  /// * `transaction`: Transaction the drafts are deleted within.
+  /// * `retention_days`: Days a draft is kept since it was last saved.
+  async fn purge_drafts(
+    transaction: &mut Transaction<'_, Postgres>,
+    retention_days: i32,
+  ) -> Result<u64, QError> {
+    let sql = format!("DELETE FROM survey_drafts WHERE {}", PURGED_DRAFTS_SQL);
+    let purged = sqlx::query(&sql)
+      .bind(retention_days)
+      .execute(&mut *transaction)
+      .await
+      .map_err(Self::database_error)?;
+
+    Ok(purged.rows_affected())
+  } // end fn purge_drafts()
+
+  /// Deletes the changes of the questions made longer ago than the given amount of
+  /// days.
+  ///
+  /// Returns the amount of deleted changes.
+  ///
+  /// # Arguments
+  ///
+  /// * `transaction`: Transaction the changes are deleted within.
+  /// * `retention_days`: Days a change is kept.
+  async fn purge_question_changes(
+    transaction: &mut Transaction<'_, Postgres>,
+    retention_days: i32,
+  ) -> Result<u64, QError> {
+    let sql = format!("DELETE FROM question_changes WHERE {}", PURGED_CHANGES_SQL);
+    let purged = sqlx::query(&sql)
+      .bind(retention_days)
+      .execute(&mut *transaction)
+      .await
+      .map_err(Self::database_error)?;
+
+    Ok(purged.rows_affected())
+  } // end fn purge_question_changes()
+
+  /// Closes the surveys whose closing date has passed, so they are listed as closed
+  /// from then on.
+  ///
+  /// Returns the amount of closed surveys.
+  pub async fn close_expired_surveys(&self) -> Result<u64, QError> {
+    let closed = sqlx::query(
+      r#"UPDATE surveys 
+      SET closed_on = closes_on 
+      WHERE closed_on IS NULL AND closes_on <= now()"#,
+    )
+    .execute(&self.connection)
+    .await
+    .map_err(Self::database_error)?;
+
+    Ok(closed.rows_affected())
+  } // end fn close_expired_surveys()
+
+  /// Adds a new response to a choice or rating question.
+  ///
+  /// The response and its choices are inserted within a single transaction.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the question.
+  /// * `response`: Response to be added.
+  pub async fn add_response(
+    &self,
+    id: i32,
+    response: NewResponse,
+  ) -> Result<(), QError> {
+    let mut transaction = self.connection.begin().await.map_err(Self::database_error)?;
+
+    // Lock the question, so it is not closed or deleted while the response is added.
+    sqlx::query("SELECT id FROM questions WHERE id = $1 FOR SHARE")
+      .bind(id)
+      .execute(&mut transaction)
+      .await
+      .map_err(Self::database_error)?;
+
+    let question = match Self::fetch_question(&mut transaction, id).await? {
+      Some(question) => question,
+      None => return Err(QError::QuestionNotFound),
+    };
+
+    if question.status != QuestionStatus::Open {
+      return Err(QError::QuestionClosed);
+    }
+    response.validate(&question)?;
+
+    Self::insert_response(&mut transaction, id, response, None).await?;
+
+    transaction.commit().await.map_err(Self::database_error)
+  } // end fn add_response()
+
+  /// Inserts a validated response and its choices.
+  ///
+  /// # Arguments
+  ///
+  /// * `transaction`: Transaction the response is being written in.
+  /// * `id`: Unique identifier (ID) of the question.
+  /// * `response`: Response to be inserted.
+  /// * `submission_id`: Unique identifier (ID) of the survey submission the response
+  ///   belongs to, if any.
+  async fn insert_response(
+    transaction: &mut Transaction<'_, Postgres>,
+    id: i32,
+    response: NewResponse,
+    submission_id: Option<i32>,
+  ) -> Result<(), QError> {
+    let response_id: i32 = sqlx::query_scalar(
+      r#"INSERT INTO responses (question_id, rating, submission_id) 
+      VALUES ($1, $2, $3) 
+      RETURNING id"#,
+    )
+    .bind(id)
+    .bind(response.rating)
+    .bind(submission_id)
+    .fetch_one(&mut *transaction)
+    .await
+    .map_err(Self::database_error)?;
+
+    if let Some(choices) = response.choices {
+      sqlx::query(
+        r#"INSERT INTO response_choices (response_id, option_id) 
+        SELECT $1, o.id FROM question_options o 
+        WHERE o.question_id = $2 AND o.position = ANY($3)"#,
+      )
+      .bind(response_id)
+      .bind(id)
+      .bind(choices)
+      .execute(&mut *transaction)
+      .await
+      .map_err(Self::database_error)?;
+    }
+
+    Ok(())
+  } // end fn insert_response()
+
+  /// Gets the aggregated responses to a choice or rating question.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the question.
+  pub async fn get_question_results(
+    &self,
+    id: i32,
+  ) -> Result<QuestionResults, QError> {
+    let question = self.get_question_by_id(id).await?;
+
+    self.question_results(&question, None).await
+  } // end fn get_question_results()
+
+  /// Aggregates the responses to the given choice or rating question.
+  ///
+  /// # Arguments
+  ///
+  /// * `question`: Question whose responses are aggregated.
+  /// * `survey_id`: Unique identifier (ID) of the survey to aggregate only the
+  ///   responses submitted to it, or `None` to aggregate all the responses.
+  async fn question_results(
+    &self,
+    question: &Question,
+    survey_id: Option<i32>,
+  ) -> Result<QuestionResults, QError> {
+    let mut results = QuestionResults {
+      question_id: question.id,
+      kind: question.kind,
+      responses: 0,
+      options: None,
+      ratings: None,
+      average_rating: None,
+    };
+
+    match question.kind {
+      QuestionKind::Text => {
+        return Err(QError::InvalidParameter(
+          "text questions take answers, not responses".to_string(),
+        ));
+      }
+      QuestionKind::SingleChoice | QuestionKind::MultipleChoice => {
+        let sql = format!(
+          "SELECT COUNT(*) AS count FROM responses r WHERE r.question_id = $1 {}",
+          SURVEY_RESPONSES_SQL
+        );
+        results.responses = sqlx::query_scalar(&sql)
+          .bind(question.id)
+          .bind(survey_id)
+          .fetch_one(&self.connection)
+          .await
+          .map_err(Self::database_error)?;
+
+        let sql = format!(
+          r#"SELECT o.position, o.label, (
+            SELECT COUNT(*) FROM response_choices rc 
+            JOIN responses r ON r.id = rc.response_id 
+            WHERE rc.option_id = o.id {}
+          ) AS count 
+          FROM question_options o 
+          WHERE o.question_id = $1 
+          ORDER BY o.position"#,
+          SURVEY_RESPONSES_SQL
+        );
+        let mut options: Vec<OptionResult> = sqlx::query_as(&sql)
+          .bind(question.id)
+          .bind(survey_id)
+          .fetch_all(&self.connection)
+          .await
+          .map_err(Self::database_error)?;
+        for option in options.iter_mut() {
+          option.percentage = percentage(option.count, results.responses);
+        }
+
+        results.options = Some(options);
+      }
+      QuestionKind::Rating => {
+        let sql = format!(
+          r#"SELECT s.rating, COUNT(r.id) AS count 
+          FROM generate_series(1, $3) AS s(rating) 
+          LEFT JOIN responses r 
+            ON r.question_id = $1 AND r.rating = s.rating {} 
+          GROUP BY s.rating 
+          ORDER BY s.rating"#,
+          SURVEY_RESPONSES_SQL
+        );
+        let mut ratings: Vec<RatingResult> = sqlx::query_as(&sql)
+          .bind(question.id)
+          .bind(survey_id)
+          .bind(question.rating_max.unwrap_or_default())
+          .fetch_all(&self.connection)
+          .await
+          .map_err(Self::database_error)?;
+
+        results.responses = ratings.iter().map(|result| result.count).sum();
+        if results.responses > 0 {
+          let total: i64 = ratings
+            .iter()
+            .map(|result| result.rating as i64 * result.count)
+            .sum();
+          results.average_rating = Some(total as f64 / results.responses as f64);
+        }
+        for result in ratings.iter_mut() {
+          result.percentage = percentage(result.count, results.responses);
+        }
+        results.ratings = Some(ratings);
+      }
+    }
+
+    Ok(results)
+  } // end fn question_results()
+
+  /// Adds a new survey with the given questions.
+  ///
+  /// The survey, its questions, and its rules are inserted within a single transaction.
+  ///
+  /// # Arguments
+  ///
+  /// * `survey`: Survey to be added.
+  pub async fn add_survey(
+    &self,
+    survey: NewSurvey,
+  ) -> Result<Survey, QError> {
+    let mut transaction = self.connection.begin().await.map_err(Self::database_error)?;
+
+    let question_ids: Vec<i32> = survey.question_ids.iter().map(|id| id.value()).collect();
+    let kinds: Vec<String> = sqlx::query_scalar(
+      r#"SELECT kind FROM questions 
+      WHERE id = ANY($1) AND deleted_at IS NULL 
+      FOR SHARE"#,
+    )
+    .bind(&question_ids)
+    .fetch_all(&mut transaction)
+    .await
+    .map_err(Self::database_error)?;
+
+    if kinds.len() != question_ids.len() {
+      return Err(QError::QuestionNotFound);
+    }
+    if kinds.iter().any(|kind| kind == QuestionKind::Text.as_str()) {
+      return Err(QError::InvalidParameter(
+        "surveys only take choice and rating questions".to_string(),
+      ));
+    }
+
+    let id: i32 = sqlx::query_scalar(
+      r#"INSERT INTO surveys 
+        (title, opens_on, closes_on, max_submissions, invitation_only, quiz, embed_origins) 
+      VALUES ($1, $2, $3, $4, $5, $6, $7) 
+      RETURNING id"#,
+    )
+    .bind(survey.title)
+    .bind(survey.opens_on)
+    .bind(survey.closes_on)
+    .bind(survey.max_submissions)
+    .bind(survey.invitation_only)
+    .bind(survey.quiz)
+    .bind(survey.embed_origins)
+    .fetch_one(&mut transaction)
+    .await
+    .map_err(Self::database_error)?;
+
+    let optional_question_ids: Vec<i32> = survey
+      .optional_question_ids
+      .iter()
+      .map(|id| id.value())
+      .collect();
+    sqlx::query(
+      r#"INSERT INTO survey_questions (survey_id, question_id, position, required) 
+      SELECT $1, sq.question_id, sq.position, NOT (sq.question_id = ANY($3)) 
+      FROM unnest($2::integer[]) WITH ORDINALITY AS sq(question_id, position)"#,
+    )
+    .bind(id)
+    .bind(&question_ids)
+    .bind(&optional_question_ids)
+    .execute(&mut transaction)
+    .await
+    .map_err(Self::database_error)?;
+
+    for rule in survey.rules {
+      sqlx::query(
+        r#"INSERT INTO survey_question_rules (survey_id, question_id, depends_on, any_of) 
+        VALUES ($1, $2, $3, $4)"#,
+      )
+      .bind(id)
+      .bind(rule.question_id)
+      .bind(rule.depends_on)
+      .bind(rule.any_of)
+      .execute(&mut transaction)
+      .await
+      .map_err(Self::database_error)?;
+    }
+
+    let survey = match Self::fetch_survey(&mut transaction, id).await? {
+      Some(survey) => survey,
+      None => return Err(QError::SurveyNotFound),
+    };
+    for rule in survey.rules.iter() {
+      if let Some(depends_on) =
+        survey.questions.iter().find(|question| question.id == rule.depends_on)
+      {
+        rule.validate(depends_on)?;
+      }
+    }
+    if survey.quiz && survey.questions.iter().all(|question| question.points.is_none()) {
+      return Err(QError::InvalidParameter(
+        "quiz surveys need at least one question with options worth points".to_string(),
+      ));
+    }
+
+    transaction.commit().await.map_err(Self::database_error)?;
+
+    Ok(survey.hide_points())
+  } // end fn add_survey()
+
+  /// Gets the survey specified by the given id, along with its questions.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the survey.
+  pub async fn get_survey(
+    &self,
+    id: i32,
+  ) -> Result<Survey, QError> {
+    let mut connection = self.connection.acquire().await.map_err(Self::database_error)?;
+
+    match Self::fetch_survey(&mut connection, id).await? {
+      Some(survey) => Ok(survey.hide_points()),
+      None => Err(QError::SurveyNotFound),
+    }
+  } // end fn get_survey()
+
+  /// Fetches the survey specified by the given id, along with its questions.
+  ///
+  /// # Arguments
+  ///
+  /// * `connection`: Connection or transaction to run the queries on.
+  /// * `id`: Unique identifier (ID) of the survey.
+  async fn fetch_survey(
+    connection: &mut PgConnection,
+    id: i32,
+  ) -> Result<Option<Survey>, QError> {
+    let survey = sqlx::query(
+      r#"SELECT id, title, opens_on, closes_on, max_submissions, submission_count, 
+        closed_on, invitation_only, quiz, embed_origins 
+      FROM surveys 
+      WHERE id = $1"#,
+    )
+    .bind(id)
+    .map(|row: PgRow| Survey {
+      id: row.get("id"),
+      title: row.get("title"),
+      opens_on: row.get("opens_on"),
+      closes_on: row.get("closes_on"),
+      max_submissions: row.get("max_submissions"),
+      submission_count: row.get("submission_count"),
+      closed_on: row.get("closed_on"),
+      invitation_only: row.get("invitation_only"),
+      quiz: row.get("quiz"),
+      embed_origins: row.get("embed_origins"),
+      questions: Vec::new(),
+      optional_question_ids: Vec::new(),
+      rules: Vec::new(),
+    })
+    .fetch_optional(&mut *connection)
+    .await
+    .map_err(Self::database_error)?;
+
+    let mut survey = match survey {
+      Some(survey) => survey,
+      None => return Ok(None),
+    };
+
+    let sql = format!(
+      r#"SELECT q.id, q.title, q.content, {}, q.status, q.version, {} 
+      FROM survey_questions sq JOIN questions q ON q.id = sq.question_id 
+      WHERE sq.survey_id = $1 AND q.deleted_at IS NULL 
+      ORDER BY sq.position"#,
+      QUESTION_TAGS_SQL,
+      QUESTION_KIND_SQL
+    );
+
+    survey.questions = sqlx::query_as::<_, Question>(&sql)
+      .bind(id)
+      .fetch_all(&mut *connection)
+      .await
+      .map_err(Self::database_error)?;
+
+    survey.optional_question_ids = sqlx::query_scalar(
+      r#"SELECT sq.question_id 
+      FROM survey_questions sq JOIN questions q ON q.id = sq.question_id 
+      WHERE sq.survey_id = $1 AND NOT sq.required AND q.deleted_at IS NULL 
+      ORDER BY sq.position"#,
+    )
+    .bind(id)
+    .fetch_all(&mut *connection)
+    .await
+    .map_err(Self::database_error)?;
+
+    // Rules that depend on a deleted question are left out, so the question is shown.
+    survey.rules = sqlx::query_as::<_, DisplayRule>(
+      r#"SELECT r.question_id, r.depends_on, r.any_of 
+      FROM survey_question_rules r 
+      JOIN questions q ON q.id = r.depends_on 
+      JOIN survey_questions sq ON sq.survey_id = r.survey_id AND sq.question_id = r.question_id 
+      WHERE r.survey_id = $1 AND q.deleted_at IS NULL 
+      ORDER BY sq.position"#,
+    )
+    .bind(id)
+    .fetch_all(&mut *connection)
+    .await
+    .map_err(Self::database_error)?;
+
+    Ok(Some(survey))
+  } // end fn fetch_survey()
+
+  /// Adds the responses of a respondent to all the questions of a survey.
+  ///
+  /// The submission and its responses are inserted within a single transaction. For
+  /// invitation-only surveys, the invitation token of the submission is used up in
+  /// the same transaction.
+  ///
+  /// Returns the score of the responses, for quiz surveys.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the survey.
+  /// * `submission`: Responses to the questions of the survey.
+  pub async fn add_survey_submission(
+    &self,
+    id: i32,
+    submission: NewSubmission,
+  ) -> Result<Option<QuizResult>, QError> {
+    let mut transaction = self.connection.begin().await.map_err(Self::database_error)?;
+
+    // The submission is counted first, which locks the survey until the end of the
+    // transaction, so concurrent submissions never go over the highest amount. The
+    // survey is closed by the submission that reaches it.
+    let sql = format!(
+      r#"UPDATE surveys 
+      SET submission_count = submission_count + 1, 
+        closed_on = CASE WHEN submission_count + 1 >= max_submissions 
+          THEN now() ELSE closed_on END 
+      WHERE id = $1 AND {} 
+        AND (max_submissions IS NULL OR submission_count < max_submissions)"#,
+      SURVEY_OPEN_SQL
+    );
+    let counted = sqlx::query(&sql)
+      .bind(id)
+      .execute(&mut transaction)
+      .await
+      .map_err(Self::database_error)?;
+
+    let survey = match counted.rows_affected() {
+      // Tell a survey not found from a closed one
+      0 => {
+        Self::fetch_open_survey(&mut transaction, id).await?;
+        return Err(QError::SurveyClosed);
+      }
+      _ => match Self::fetch_survey(&mut transaction, id).await? {
+        Some(survey) => survey,
+        None => return Err(QError::SurveyNotFound),
+      },
+    };
+    submission.validate(&survey)?;
+
+    if survey.invitation_only {
+      let token = match &submission.token {
+        Some(token) => token,
+        None => return Err(QError::MissingParameters),
+      };
+
+      // Nothing links the invitation to the submission, so the responses stay anonymous.
+      let used = sqlx::query(
+        r#"UPDATE survey_invitations 
+        SET used = true 
+        WHERE token_hash = $1 AND survey_id = $2 AND NOT used"#,
+      )
+      .bind(hash_token(token))
+      .bind(id)
+      .execute(&mut transaction)
+      .await
+      .map_err(Self::database_error)?;
+
+      if used.rows_affected() == 0 {
+        return Err(QError::InvalidInvitation);
+      }
+    }
+
+    let submission_id: i32 = sqlx::query_scalar(
+      r#"INSERT INTO survey_submissions (survey_id) 
+      VALUES ($1) 
+      RETURNING id"#,
+    )
+    .bind(id)
+    .fetch_one(&mut transaction)
+    .await
+    .map_err(Self::database_error)?;
+
+    let result = match survey.quiz {
+      true => Some(QuizResult::new(submission_id, &survey, &submission.responses)),
+      false => None,
+    };
+    if let Some(result) = &result {
+      sqlx::query("UPDATE survey_submissions SET score = $1 WHERE id = $2")
+        .bind(result.score)
+        .bind(submission_id)
+        .execute(&mut transaction)
+        .await
+        .map_err(Self::database_error)?;
+    }
+
+    for answer in submission.responses {
+      Self::insert_response(
+        &mut transaction,
+        answer.question_id.value(),
+        answer.response,
+        Some(submission_id),
+      )
+      .await?;
+    }
+
+    if let Some(key) = &submission.draft {
+      sqlx::query(
+        r#"DELETE FROM survey_drafts 
+        WHERE key_hash = $1 AND survey_id = $2"#,
+      )
+      .bind(hash_token(key))
+      .bind(id)
+      .execute(&mut transaction)
+      .await
+      .map_err(Self::database_error)?;
+    }
+
+    transaction.commit().await.map_err(Self::database_error)?;
+
+    self.survey_results_cache().remove(&id);
+
+    Ok(result)
+  } // end fn add_survey_submission()
+
+  /// Gets the scores of all the submissions to a quiz survey, along with how many
+  /// submissions got each score.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the survey.
+  pub async fn get_quiz_scores(
+    &self,
+    id: i32,
+  ) -> Result<QuizScores, QError> {
+    let mut connection = self.connection.acquire().await.map_err(Self::database_error)?;
+
+    let survey = match Self::fetch_survey(&mut connection, id).await? {
+      Some(survey) => survey,
+      None => return Err(QError::SurveyNotFound),
+    };
+    if !survey.quiz {
+      return Err(QError::InvalidParameter(format!(
+        "survey {} is not a quiz",
+        id
+      )));
+    }
+
+    let scores: Vec<SubmissionScore> = sqlx::query_as(
+      r#"SELECT id AS submission_id, score, created_on AS submitted_on 
+      FROM survey_submissions 
+      WHERE survey_id = $1 AND score IS NOT NULL 
+      ORDER BY created_on, id"#,
+    )
+    .bind(id)
+    .fetch_all(&mut *connection)
+    .await
+    .map_err(Self::database_error)?;
+
+    let mut distribution: Vec<ScoreCount> = Vec::new();
+    for submission in scores.iter() {
+      match distribution.iter_mut().find(|count| count.score == submission.score) {
+        Some(count) => count.count += 1,
+        None => distribution.push(ScoreCount {
+          score: submission.score,
+          count: 1,
+        }),
+      }
+    }
+    distribution.sort_by_key(|count| count.score);
+
+    let total: i64 = scores.iter().map(|submission| submission.score as i64).sum();
+    Ok(QuizScores {
+      survey_id: survey.id,
+      max_score: survey.questions.iter().map(max_points).sum(),
+      submissions: scores.len() as i64,
+      average_score: match scores.len() {
+        0 => None,
+        count => Some(total as f64 / count as f64),
+      },
+      distribution,
+      scores,
+    })
+  } // end fn get_quiz_scores()
+
+  /// Fetches the survey specified by the given id, checking that it accepts responses
+  /// at the moment.
+  ///
+  /// The survey is locked until the end of the transaction, so its dates do not change
+  /// while the responses are saved.
+  ///
+  /// # Arguments
+  ///
+  /// * `transaction`: Transaction to run the queries on.
+  /// * `id`: Unique identifier (ID) of the survey.
+  async fn fetch_open_survey(
+    transaction: &mut Transaction<'_, Postgres>,
+    id: i32,
+  ) -> Result<Survey, QError> {
+    let sql = format!(
+      r#"SELECT {} AS is_open 
+      FROM surveys 
+      WHERE id = $1 
+      FOR SHARE"#,
+      SURVEY_OPEN_SQL
+    );
+    let is_open: Option<bool> = sqlx::query_scalar(&sql)
+    .bind(id)
+    .fetch_optional(&mut *transaction)
+    .await
+    .map_err(Self::database_error)?;
+
+    match is_open {
+      Some(true) => (),
+      Some(false) => return Err(QError::SurveyClosed),
+      None => return Err(QError::SurveyNotFound),
+    }
+
+    match Self::fetch_survey(transaction, id).await? {
+      Some(survey) => Ok(survey),
+      None => Err(QError::SurveyNotFound),
+    }
+  } // end fn fetch_open_survey()
+
+  /// Saves a new draft of the responses to a survey, to resume it later.
+  ///
+  /// The draft expires after `DRAFT_LIFETIME_DAYS` days unless it is saved again.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the survey.
+  /// * `draft`: Responses saved so far.
+  pub async fn add_survey_draft(
+    &self,
+    id: i32,
+    draft: NewDraft,
+  ) -> Result<Draft, QError> {
+    let mut transaction = self.connection.begin().await.map_err(Self::database_error)?;
+
+    let survey = Self::fetch_open_survey(&mut transaction, id).await?;
+    draft.validate(&survey)?;
+
+    // The token is only checked here, it is used up when the responses are submitted.
+    if survey.invitation_only {
+      let token = match &draft.token {
+        Some(token) => token,
+        None => return Err(QError::MissingParameters),
+      };
+
+      let valid = sqlx::query(
+        r#"SELECT token_hash FROM survey_invitations 
+        WHERE token_hash = $1 AND survey_id = $2 AND NOT used"#,
+      )
+      .bind(hash_token(token))
+      .bind(id)
+      .fetch_optional(&mut transaction)
+      .await
+      .map_err(Self::database_error)?;
+
+      if valid.is_none() {
+        return Err(QError::InvalidInvitation);
+      }
+    }
+
+    let key = generate_token();
+    let (updated_on, expires_on): (NaiveDateTime, NaiveDateTime) = sqlx::query_as(
+      r#"INSERT INTO survey_drafts (key_hash, survey_id, responses, expires_on) 
+      VALUES ($1, $2, $3::jsonb, now() + make_interval(days => $4)) 
+      RETURNING updated_on, expires_on"#,
+    )
+    .bind(hash_token(&key))
+    .bind(id)
+    .bind(Self::draft_responses_json(&draft.responses)?)
+    .bind(DRAFT_LIFETIME_DAYS)
+    .fetch_one(&mut transaction)
+    .await
+    .map_err(Self::database_error)?;
+
+    transaction.commit().await.map_err(Self::database_error)?;
+
+    Ok(Draft {
+      key,
+      survey_id: SurveyId::new(id),
+      responses: draft.responses,
+      updated_on,
+      expires_on,
+    })
+  } // end fn add_survey_draft()
+
+  /// Gets the draft of the responses to a survey specified by the given key, unless it
+  /// expired.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the survey.
+  /// * `key`: Secret key of the draft.
+  pub async fn get_survey_draft(
+    &self,
+    id: i32,
+    key: &str,
+  ) -> Result<Draft, QError> {
+    let row: Option<(String, NaiveDateTime, NaiveDateTime)> = sqlx::query_as(
+      r#"SELECT responses::text AS responses, updated_on, expires_on 
+      FROM survey_drafts 
+      WHERE key_hash = $1 AND survey_id = $2 AND expires_on > now()"#,
+    )
+    .bind(hash_token(key))
+    .bind(id)
+    .fetch_optional(&self.connection)
+    .await
+    .map_err(Self::database_error)?;
+
+    let (responses, updated_on, expires_on) = match row {
+      Some(row) => row,
+      None => return Err(QError::DraftNotFound),
+    };
+    let responses = serde_json::from_str(&responses)
+      .map_err(|err| Self::database_error(sqlx::Error::Decode(Box::new(err))))?;
+
+    Ok(Draft {
+      key: key.to_string(),
+      survey_id: SurveyId::new(id),
+      responses,
+      updated_on,
+      expires_on,
+    })
+  } // end fn get_survey_draft()
+
+  /// Replaces the responses of the draft specified by the given key, unless it expired,
+  /// and extends its expiry date.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the survey.
+  /// * `key`: Secret key of the draft.
+  /// * `draft`: Responses saved so far.
+  pub async fn update_survey_draft(
+    &self,
+    id: i32,
+    key: &str,
+    draft: NewDraft,
+  ) -> Result<Draft, QError> {
+    let mut transaction = self.connection.begin().await.map_err(Self::database_error)?;
+
+    let survey = Self::fetch_open_survey(&mut transaction, id).await?;
+    draft.validate(&survey)?;
+
+    let saved: Option<(NaiveDateTime, NaiveDateTime)> = sqlx::query_as(
+      r#"UPDATE survey_drafts 
+      SET responses = $1::jsonb, updated_on = now(), 
+        expires_on = now() + make_interval(days => $4) 
+      WHERE key_hash = $2 AND survey_id = $3 AND expires_on > now() 
+      RETURNING updated_on, expires_on"#,
+    )
+    .bind(Self::draft_responses_json(&draft.responses)?)
+    .bind(hash_token(key))
+    .bind(id)
+    .bind(DRAFT_LIFETIME_DAYS)
+    .fetch_optional(&mut transaction)
+    .await
+    .map_err(Self::database_error)?;
+
+    let (updated_on, expires_on) = match saved {
+      Some(saved) => saved,
+      None => return Err(QError::DraftNotFound),
+    };
+
+    transaction.commit().await.map_err(Self::database_error)?;
+
+    Ok(Draft {
+      key: key.to_string(),
+      survey_id: SurveyId::new(id),
+      responses: draft.responses,
+      updated_on,
+      expires_on,
+    })
+  } // end fn update_survey_draft()
+
+  /// Encodes the responses of a draft as JSON text, as stored in the database.
+  ///
+  /// # Arguments
+  ///
+  /// * `responses`: Responses saved in the draft.
+  fn draft_responses_json(responses: &[SurveyQuestionResponse]) -> Result<String, QError> {
+    serde_json::to_string(responses).map_err(|err| {
+      QError::InvalidParameter(format!("responses cannot be saved: {}", err))
+    })
+  } // end fn draft_responses_json()
+
+  /// Generates new single-use invitations to respond to an invitation-only survey.
+  ///
+  /// Only the hashes of the tokens are stored, so the returned tokens cannot be
+  /// retrieved again.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the survey.
+  /// * `count`: Amount of invitations to generate.
+  pub async fn add_survey_invitations(
+    &self,
+    id: i32,
+    count: i32,
+  ) -> Result<Vec<String>, QError> {
+    let invitation_only: Option<bool> =
+      sqlx::query_scalar("SELECT invitation_only FROM surveys WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&self.connection)
+        .await
+        .map_err(Self::database_error)?;
+
+    match invitation_only {
+      Some(true) => (),
+      Some(false) => {
+        return Err(QError::InvalidParameter(
+          "only invitation-only surveys take invitations".to_string(),
+        ));
+      }
+      None => return Err(QError::SurveyNotFound),
+    }
+
+    let tokens: Vec<String> = (0..count).map(|_| generate_token()).collect();
+    let hashes: Vec<String> = tokens.iter().map(|token| hash_token(token)).collect();
+
+    sqlx::query(
+      r#"INSERT INTO survey_invitations (token_hash, survey_id) 
+      SELECT unnest($2::text[]), $1"#,
+    )
+    .bind(id)
+    .bind(hashes)
+    .execute(&self.connection)
+    .await
+    .map_err(Self::database_error)?;
+
+    Ok(tokens)
+  } // end fn add_survey_invitations()
+
+  /// Gets the aggregated responses submitted to all the questions of a survey.
+  ///
+  /// The results are cached for a short time, and dropped from the cache when a new
+  /// submission is added to the survey.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the survey.
+  pub async fn get_survey_results(
+    &self,
+    id: i32,
+  ) -> Result<SurveyResults, QError> {
+    if let Some((computed_on, results)) = self.survey_results_cache().get(&id) {
+      if computed_on.elapsed() < SURVEY_RESULTS_TTL {
+        return Ok(results.clone());
+      }
+    }
+
+    let results = self.compute_survey_results(id).await?;
+
+    let mut cache = self.survey_results_cache();
+    cache.retain(|_, (computed_on, _)| computed_on.elapsed() < SURVEY_RESULTS_TTL);
+    cache.insert(id, (Instant::now(), results.clone()));
+
+    Ok(results)
+  } // end fn get_survey_results()
+
+  /// Computes the aggregated responses submitted to all the questions of a survey.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the survey.
+  async fn compute_survey_results(
+    &self,
+    id: i32,
+  ) -> Result<SurveyResults, QError> {
+    let survey = self.get_survey(id).await?;
+
+    let submissions: i64 = sqlx::query_scalar(
+      "SELECT COUNT(*) AS count FROM survey_submissions WHERE survey_id = $1",
+    )
+    .bind(id)
+    .fetch_one(&self.connection)
+    .await
+    .map_err(Self::database_error)?;
+
+    let mut questions = Vec::with_capacity(survey.questions.len());
+    for question in &survey.questions {
+      questions.push(self.question_results(question, Some(id)).await?);
+    }
+
+    Ok(SurveyResults {
+      survey_id: survey.id,
+      submissions,
+      questions,
+    })
+  } // end fn compute_survey_results()
+
+  /// Locks the cache of survey results.
+  fn survey_results_cache(&self) -> MutexGuard<'_, HashMap<i32, (Instant, SurveyResults)>> {
+    // The cache only holds copies of query results, so it is still usable after a
+    // panic while it was locked.
+    self
+      .survey_results
+      .lock()
+      .unwrap_or_else(|poisoned| poisoned.into_inner())
+  } // end fn survey_results_cache()
+
+  /// Logs the given database error and converts it into a query error. Rows that
+  /// break a constraint of the tables are converted into the matching error, and any
+  /// other error is wrapped as is.
+  ///
+  /// # Arguments
+  ///
+  /// * `err`: Error returned by the database.
+  fn database_error(err: sqlx::Error) -> QError {
+    let db_err = match err.as_database_error() {
+      Some(db_err) => db_err,
+      None => {
+        tracing::event!(tracing::Level::ERROR, "{:?}", err);
+        return QError::DatabaseQueryError(err);
+      }
+    };
+    let constraint = db_err.constraint().unwrap_or("unknown").to_string();
+
+    let error = match db_err.code().as_deref() {
+      Some(QUERY_CANCELED) => QError::DatabaseTimeout,
+      Some(UNIQUE_VIOLATION) => QError::Conflict(constraint),
+      Some(FOREIGN_KEY_VIOLATION) => QError::InvalidReference(constraint),
+      Some(NOT_NULL_VIOLATION) => QError::MissingValue(
+        db_err
+          .try_downcast_ref::<PgDatabaseError>()
+          .and_then(PgDatabaseError::column)
+          .unwrap_or("unknown")
+          .to_string(),
+      ),
+      Some(CHECK_VIOLATION) => QError::InvalidParameter(format!(
+        "the value does not satisfy the {} constraint",
+        constraint
+      )),
+      _ => {
+        tracing::event!(tracing::Level::ERROR, "{:?}", err);
+        return QError::DatabaseQueryError(err);
+      }
+    };
+
+    tracing::event!(tracing::Level::WARN, "{:?}", err);
+    error
+  } // end fn database_error()
+}
+
+#[async_trait]
+impl QuestionStore for PostgresStore {
+  /// Gets the collection of questions as a stream of rows, each one along with the
+  /// amount of questions that match the query across all the pages.
+  ///
+  /// Rows are fetched from the database as the stream is polled, so a large page
+  /// never needs to be held in memory as a whole.
+  ///
+  /// # Arguments
+  ///
+  /// * `query`: Pagination, filters and tags of the set of questions to retrieve. With
+  ///   keyset pagination, the questions after the given ID are listed by their ID.
+  fn get_questions(
+    &self,
+    query: QuestionQuery,
+  ) -> BoxStream<'_, Result<(Question, i64), QError>> {
+    Box::pin(async_stream::try_stream! {
+      let (condition, order) = self.filter_clauses(query.filter);
+
+      let mut builder: QueryBuilder<Postgres> = QueryBuilder::new(format!(
+        "SELECT q.id, q.title, q.content, {}, q.status, q.version, {}, 
+          COUNT(*) OVER () AS total_count 
+        FROM questions q 
+        WHERE q.deleted_at IS NULL ",
+        QUESTION_TAGS_SQL,
+        QUESTION_KIND_SQL
+      ));
+
+      if let Some(status) = query.status {
+        builder.push("AND q.status = ").push_bind(status.as_str()).push(" ");
+      }
+
+      if let Some(created_after) = query.created_after {
+        builder.push("AND q.created_on > ").push_bind(created_after).push(" ");
+      }
+      if let Some(created_before) = query.created_before {
+        builder.push("AND q.created_on < ").push_bind(created_before).push(" ");
+      }
+
+      if !query.tags.is_empty() {
+        builder.push(
+          r#"AND (SELECT COUNT(*) FROM question_tags qt JOIN tags t ON t.id = qt.tag_id 
+          WHERE qt.question_id = q.id AND t.name = ANY("#,
+        );
+        builder.push_bind(query.tags.clone());
+        match query.tag_match {
+          TagMatch::All => builder.push(")) = ").push_bind(query.tags.len() as i64),
+          TagMatch::Any => builder.push(")) > 0"),
+        };
+        builder.push(" ");
+      }
+
+      if let Some(language) = &query.code_language {
+        builder.push(format!(
+          r#"AND EXISTS (SELECT 1 FROM answers a 
+          JOIN answer_code_blocks c ON c.answer_id = a.id 
+          WHERE a.{} = q.id AND c.language = "#,
+          self.answer_question_columns().0
+        ));
+        builder.push_bind(language.clone()).push(") ");
+      }
+
+      if let Some(after) = query.pagination.after {
+        builder.push("AND q.id > ").push_bind(after).push(" ");
+      }
+
+      builder.push(condition).push(" ");
+      match (query.sorting, query.pagination.after) {
+        (Some(sorting), _) => builder.push(sorting.order_by()),
+        (None, Some(_)) => builder.push("ORDER BY q.id"),
+        (None, None) => builder.push(order),
+      };
+      builder.push(" LIMIT ").push_bind(query.pagination.limit);
+      builder.push(" OFFSET ").push_bind(query.pagination.offset);
+
+      let mut connection = self.read_connection().await?;
+      let mut rows = builder
+        .build()
+        .try_map(|row: PgRow| {
+          Ok((Question::from_row(&row)?, row.try_get("total_count")?))
+        })
+        .fetch(&mut *connection)
+        .map_err(Self::database_error);
+
+      while let Some(row) = rows.try_next().await? {
+        yield row;
+      }
+    })
+  } // end fn get_questions()
+
+  /// Gets all the questions, deleted ones included, joined with their answers as a
+  /// stream of rows, to export the data store.
+  ///
+  /// Each question has one row per answer, or a single row without an answer when it
+  /// has none. The rows are fetched as the stream is polled.
+  fn export_rows(&self) -> BoxStream<'_, Result<ExportRow, QError>> {
+    let sql = format!(
+      r#"SELECT q.id AS question_id, q.title, q.content, {}, q.status, q.kind, 
+        q.created_on AS question_created_on, q.deleted_at AS question_deleted_at, 
+        a.id AS answer_id, a.content AS answer_content, a.created_on AS answer_created_on 
+      FROM questions q LEFT JOIN answers a ON a.{} = q.id 
+      ORDER BY q.id, a.id"#,
+      QUESTION_TAGS_SQL,
+      self.answer_question_columns().0
+    );
+
+    Box::pin(async_stream::try_stream! {
+      let mut transaction = self.connection.begin().await.map_err(Self::database_error)?;
+
+      // The rows are sent as fast as the client reads them, so a large export may run
+      // longer than the statement timeout.
+      sqlx::query("SET LOCAL statement_timeout = 0")
+        .execute(&mut transaction)
+        .await
+        .map_err(Self::database_error)?;
+
+      {
+        let mut rows = sqlx::query_as::<_, ExportRow>(&sql)
+          .fetch(&mut transaction)
+          .map_err(Self::database_error);
+
+        while let Some(row) = rows.try_next().await? {
+          yield row;
+        }
+      }
+
+      transaction.commit().await.map_err(Self::database_error)?;
+    })
+  } // end fn export_rows()
+
+  /// Gets the question specified by the given id.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the question.
+  async fn get_question_by_id(
+    &self,
+    id: i32,
+  ) -> Result<Question, QError> {
+    let mut connection = self.read_connection().await?;
+    match Self::fetch_question(&mut *connection, id).await? {
+      Some(question) => Ok(question),
+      None => Err(QError::QuestionNotFound),
+    }
+  } // end fn get_question_by_id()
+
+  /// Adds a new question to the system.
+  ///
+  /// The question, its tags and its options are inserted within a single transaction.
+  ///
+  /// # Arguments
+  ///
+  /// * `question`: Question to be added.
+  async fn add_question(
+    &self,
+    question: NewQuestion,
+  ) -> Result<Vec<Question>, QError> {
+    let mut transaction = self.connection.begin().await.map_err(Self::database_error)?;
+
+    let id: i32 = sqlx::query_scalar(
+      r#"INSERT INTO questions (title, content, kind, rating_max) 
+      VALUES ($1, $2, $3, $4) 
+      RETURNING id"#,
+    )
+    .bind(question.title)
+    .bind(question.content)
+    .bind(question.kind.as_str())
+    .bind(question.rating_max)
+    .fetch_one(&mut transaction)
+    .await
+    .map_err(Self::database_error)?;
+
+    Self::set_question_tags(&mut transaction, id, question.tags.unwrap_or_default()).await?;
+    Self::record_revision(&mut transaction, id).await?;
+
+    if let Some(options) = question.options {
+      sqlx::query(
+        r#"INSERT INTO question_options (question_id, position, label, points) 
+        SELECT $1, o.position, o.label, COALESCE(o.points, 0) 
+        FROM unnest($2::varchar[], $3::integer[]) WITH ORDINALITY 
+          AS o(label, points, position)"#,
+      )
+      .bind(id)
+      .bind(options)
+      .bind(question.points.unwrap_or_default())
+      .execute(&mut transaction)
+      .await
+      .map_err(Self::database_error)?;
+    }
+
+    let questions: Vec<Question> = Self::fetch_question(&mut transaction, id)
+      .await?
+      .into_iter()
+      .collect();
+
+    transaction.commit().await.map_err(Self::database_error)?;
+
+    Ok(questions)
+  } // end fn add_question()
+
+  /// Updates an existing question in the datastore, if it is still at the given
+  /// version, and moves it to the next version.
+  ///
+  /// The question and its tags are updated within a single transaction. An update based
+  /// on an older version is refused with the hint to merge it with the current version.
+  ///
+  /// # Arguments
+  ///
+  /// * `question`: Question data.
+  /// * `id`: Unique identifier (ID) of the question.
+  /// * `version`: Version of the question the update is based on.
+  async fn update_question(
+    &self,
+    question: Question,
+    id: i32,
+    version: i32,
+  ) -> Result<Vec<Question>, QError> {
+    let mut transaction = self.connection.begin().await.map_err(Self::database_error)?;
+
+    let updated = sqlx::query(
+      r#"UPDATE questions 
+      SET title = $1, content = $2, version = version + 1 
+      WHERE id = $3 AND deleted_at IS NULL AND version = $4"#,
+    )
+    .bind(&question.title)
+    .bind(&question.content)
+    .bind(id)
+    .bind(version)
+    .execute(&mut transaction)
+    .await
+    .map_err(Self::database_error)?;
+
+    if updated.rows_affected() == 0 {
+      // Either the question does not exist, or another update changed it first.
+      let current = match Self::fetch_question(&mut transaction, id).await? {
+        Some(current) => current,
+        None => return Err(QError::QuestionNotFound),
+      };
+      // The versions that only changed the status have no revision of their own, so
+      // the latest revision up to the base version has their fields.
+      let base: Option<QuestionFields> = sqlx::query_as(
+        r#"SELECT title, content, tags 
+        FROM question_revisions 
+        WHERE question_id = $1 AND version <= $2 
+        ORDER BY version DESC 
+        LIMIT 1"#,
+      )
+      .bind(id)
+      .bind(version)
+      .fetch_optional(&mut transaction)
+      .await
+      .map_err(Self::database_error)?;
+
+      let hint = merge_hint(version, base, &current, &question);
+      return Err(QError::VersionConflict(Box::new(hint)));
+    }
+
+    Self::set_question_tags(&mut transaction, id, question.tags.unwrap_or_default()).await?;
+    Self::record_revision(&mut transaction, id).await?;
+
+    let questions: Vec<Question> = Self::fetch_question(&mut transaction, id)
+      .await?
+      .into_iter()
+      .collect();
+
+    transaction.commit().await.map_err(Self::database_error)?;
+
+    Ok(questions)
+  } // end fn update_question()
+
+  /// Deletes the questions specified by the given id from the datastore.
+  ///
+  /// The question is only marked as deleted, so it can be restored until it is purged.
+  /// Questions are shared by the surveys that link them, so a question linked to a
+  /// survey that is not closed yet cannot be deleted.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the question to be deleted.
+  async fn delete_question(
+    &self,
+    id: i32,
+  ) -> Result<(), QError> {
+    let mut transaction = self.connection.begin().await.map_err(Self::database_error)?;
+
+    // The question is locked first, so no survey links it while it is checked.
+    let deleted = sqlx::query(
+      r#"UPDATE questions 
+      SET deleted_at = now() 
+      WHERE id = $1 AND deleted_at IS NULL"#,
+    )
+    .bind(id)
+    .execute(&mut transaction)
+    .await
+    .map_err(Self::database_error)?;
+
+    let survey_ids: Vec<i32> = sqlx::query_scalar(
+      r#"SELECT s.id 
+      FROM surveys s JOIN survey_questions sq ON sq.survey_id = s.id 
+      WHERE sq.question_id = $1 AND s.closed_on IS NULL 
+        AND (s.closes_on IS NULL OR s.closes_on > now()) 
+      ORDER BY s.id"#,
+    )
+    .bind(id)
+    .fetch_all(&mut transaction)
+    .await
+    .map_err(Self::database_error)?;
+
+    if deleted.rows_affected() == 0 {
+      return Err(QError::QuestionNotFound);
+    }
+    if !survey_ids.is_empty() {
+      return Err(QError::QuestionInUse(survey_ids));
+    }
+
+    transaction.commit().await.map_err(Self::database_error)?;
+
+    Ok(())
+  } // end fn delete_question()
+
+  /// Adds a new answer to the datastore.
+  ///
+  /// The question is checked and locked within the same transaction as the insertion,
+  /// so an answer cannot reference a question that does not exist, is being deleted, or
+  /// is no longer open.
+  ///
+  /// # Arguments
+  ///
+  /// * `answer`: Answer to be added.
+  async fn add_answer(
+    &self,
+    answer: NewAnswer,
+  ) -> Result<Answer, QError> {
+    let mut transaction = self.connection.begin().await.map_err(Self::database_error)?;
+
+    let status = sqlx::query_scalar::<_, String>(
+      r#"SELECT status 
+      FROM questions 
+      WHERE id = $1 AND deleted_at IS NULL 
+      FOR SHARE"#,
+    )
+    .bind(answer.question_id)
+    .fetch_optional(&mut transaction)
+    .await
+    .map_err(Self::database_error)?;
+    match status {
+      Some(status) if status == QuestionStatus::Open.as_str() => (),
+      Some(_) => return Err(QError::QuestionClosed),
+      None => return Err(QError::QuestionNotFound),
+    }
+
+    let question_columns = self.answer_question_columns().1;
+    let sql = format!(
+      r#"INSERT INTO answers (content, {}) 
+      VALUES ($1, {}) 
+      RETURNING id"#,
+      question_columns.join(", "),
+      vec!["$2"; question_columns.len()].join(", ")
+    );
+    let id: i32 = sqlx::query_scalar(&sql)
+      .bind(&answer.content)
+      .bind(answer.question_id)
+      .fetch_one(&mut transaction)
+      .await
+      .map_err(Self::database_error)?;
+    let code_blocks = Self::set_code_blocks(&mut transaction, id, &answer.content).await?;
+
+    transaction.commit().await.map_err(Self::database_error)?;
+
+    Ok(Answer {
+      id: AnswerId::new(id),
+      content: answer.content,
+      question_id: answer.question_id,
+      code_blocks,
+    })
+  } // end fn add_answer()
+
+
+  /// Gets the questions whose title is similar to the given one, from the most to the
+  /// least similar.
+  ///
+  /// # Arguments
+  ///
+  /// * `title`: Title to compare with the titles of the existing questions.
+  /// * `threshold`: Minimum trigram similarity, from 0 to 1, of a similar title.
+  /// * `limit`: Maximum amount of questions to get.
+  async fn find_similar_questions(
+    &self,
+    title: &str,
+    threshold: f32,
+    limit: i32,
+  ) -> Result<Vec<Question>, QError> {
+    let sql = format!(
+      r#"SELECT q.id, q.title, q.content, {}, q.status, q.version, {} 
+      FROM questions q 
+      WHERE q.deleted_at IS NULL AND similarity(q.title, $1) >= $2 
+      ORDER BY similarity(q.title, $1) DESC, q.id 
+      LIMIT $3"#,
+      QUESTION_TAGS_SQL,
+      QUESTION_KIND_SQL
+    );
+
+    sqlx::query_as::<_, Question>(&sql)
+      .bind(title)
+      .bind(threshold)
+      .bind(limit)
+      .fetch_all(&self.connection)
+      .await
+      .map_err(Self::database_error)
+  } // end fn find_similar_questions()
+
+
+  /// Gets the answers of a question whose content is similar to the given one, from the
+  /// most to the least similar.
+  ///
+  /// # Arguments
+  ///
+  /// * `question_id`: Unique identifier (ID) of the question of the answers.
+  /// * `content`: Content to compare with the contents of the existing answers.
+  /// * `threshold`: Minimum trigram similarity, from 0 to 1, of a similar content.
+  /// * `limit`: Maximum amount of answers to get.
+  async fn find_similar_answers(
+    &self,
+    question_id: QuestionId,
+    content: &str,
+    threshold: f32,
+    limit: i32,
+  ) -> Result<Vec<Answer>, QError> {
+    let (read_column, _) = self.answer_question_columns();
+    let sql = format!(
+      r#"SELECT a.id, a.content, a.{0} AS question_id, {1} 
+      FROM answers a 
+      WHERE a.{0} = $1 AND similarity(a.content, $2) >= $3 
+      ORDER BY similarity(a.content, $2) DESC, a.id 
+      LIMIT $4"#,
+      read_column,
+      ANSWER_CODE_SQL
+    );
+
+    sqlx::query_as::<_, Answer>(&sql)
+      .bind(question_id)
+      .bind(content)
+      .bind(threshold)
+      .bind(limit)
+      .fetch_all(&self.connection)
+      .await
+      .map_err(Self::database_error)
+  } // end fn find_similar_answers()
+
+
+  /// Gets the ID of the question that replaced the given one, following the chain of
+  /// redirects up to the last question that is not deleted, or `None` when the
+  /// question was not replaced.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the question not found.
+  async fn get_question_redirect(
+    &self,
+    id: i32,
+  ) -> Result<Option<i32>, QError> {
+    let mut connection = self.read_connection().await?;
+
+    sqlx::query_scalar(
+      r#"WITH RECURSIVE chain (target_id, hops) AS (
+        SELECT r.target_id, 1 FROM question_redirects r WHERE r.from_id = $1 
+        UNION ALL 
+        SELECT r.target_id, c.hops + 1 
+        FROM question_redirects r JOIN chain c ON r.from_id = c.target_id 
+        WHERE c.hops < $2
+      ) 
+      SELECT c.target_id 
+      FROM chain c JOIN questions q ON q.id = c.target_id 
+      WHERE q.deleted_at IS NULL 
+      ORDER BY c.hops DESC 
+      LIMIT 1"#,
+    )
+    .bind(id)
+    .bind(REDIRECT_MAX_HOPS)
+    .fetch_optional(&mut *connection)
+    .await
+    .map_err(Self::database_error)
+  } // end fn get_question_redirect()
+}
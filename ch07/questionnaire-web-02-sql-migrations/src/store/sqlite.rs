@@ -0,0 +1,592 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use futures::stream::BoxStream;
+use futures::TryStreamExt;
+use sqlx::migrate::Migrator;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions, SqliteRow};
+use sqlx::{Executor, QueryBuilder, Row, Sqlite, Transaction};
+
+use handle_errors::errors::QError;
+
+use super::QuestionStore;
+use crate::types::answer::{Answer, AnswerId, NewAnswer};
+use crate::types::code::extract_code_blocks;
+use crate::types::export::ExportRow;
+use crate::types::query::{QuestionQuery, TagMatch};
+use crate::types::question::{NewQuestion, Question, QuestionId, QuestionStatus};
+use crate::types::revision::merge_hint;
+
+/// Migrations of the SQLite schema, embedded from the `migrations-sqlite` directory.
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations-sqlite");
+
+/// SQL expression with the tag names of the question aliased as `q`, in order, as a
+/// JSON array.
+const QUESTION_TAGS_SQL: &str = r#"(SELECT json_group_array(name) FROM (SELECT t.name
+  FROM question_tags qt JOIN tags t ON t.id = qt.tag_id
+  WHERE qt.question_id = q.id ORDER BY t.name)) AS tags"#;
+
+/// SQL expressions with the columns of the question aliased as `q`, with its option
+/// labels and option points, in order, as JSON arrays. The tags are selected apart with
+/// `QUESTION_TAGS_SQL`.
+const QUESTION_COLUMNS_SQL: &str = r#"q.id, q.title, q.content, q.status, q.kind,
+  q.rating_max, q.version,
+  (SELECT json_group_array(label) FROM (SELECT o.label FROM question_options o
+    WHERE o.question_id = q.id ORDER BY o.position)) AS options,
+  (SELECT json_group_array(points) FROM (SELECT o.points FROM question_options o
+    WHERE o.question_id = q.id ORDER BY o.position)) AS points"#;
+
+/// Extended result code of the errors for rows that break a `CHECK` constraint.
+const CONSTRAINT_CHECK: &str = "275";
+
+/// Extended result code of the errors for rows that reference rows that do not exist.
+const CONSTRAINT_FOREIGNKEY: &str = "787";
+
+/// Extended result code of the errors for rows that break a `NOT NULL` constraint.
+const CONSTRAINT_NOTNULL: &str = "1299";
+
+/// Extended result code of the errors for rows that break a unique constraint.
+const CONSTRAINT_UNIQUE: &str = "2067";
+
+/// Represents the data store of the application in a SQLite database, so the examples
+/// can run without a PostgreSQL server.
+///
+/// Only the questions and their answers are stored. The features that rely on
+/// PostgreSQL, like the surveys or the full-text search, are not available.
+#[derive(Debug, Clone)]
+pub struct SqliteStore {
+  /// Pool for database connections.
+  pub connection: SqlitePool,
+} // end struct SqliteStore
+
+impl SqliteStore {
+  /// Creates a new data store, creating the database file if it does not exist, and
+  /// runs the pending migrations of the SQLite schema on it.
+  ///
+  /// # Arguments
+  ///
+  /// * `db_url`: URL of the database, like `sqlite://questions.db`.
+  pub async fn new(db_url: &str) -> Result<Self, QError> {
+    let options = SqliteConnectOptions::from_str(db_url)
+      .map_err(Self::database_error)?
+      .create_if_missing(true)
+      .foreign_keys(true);
+    let connection = SqlitePoolOptions::new()
+      .max_connections(5)
+      .connect_with(options)
+      .await
+      .map_err(Self::database_error)?;
+
+    if let Err(err) = MIGRATOR.run(&connection).await {
+      tracing::event!(tracing::Level::ERROR, "{:?}", err);
+      return Err(QError::DatabaseQueryError(err.into()));
+    }
+
+    Ok(SqliteStore { connection })
+  } // end fn new()
+
+  /// Gets the question specified by the given id within the given executor, or `None`
+  /// if it does not exist or was deleted.
+  ///
+  /// # Arguments
+  ///
+  /// * `executor`: Connection or transaction the question is read with.
+  /// * `id`: Unique identifier (ID) of the question.
+  async fn fetch_question<'e, E>(
+    executor: E,
+    id: i32,
+  ) -> Result<Option<Question>, QError>
+  where
+    E: Executor<'e, Database = Sqlite>,
+  {
+    let sql = format!(
+      r#"SELECT {}, {}
+      FROM questions q
+      WHERE q.id = ? AND q.deleted_at IS NULL"#,
+      QUESTION_COLUMNS_SQL, QUESTION_TAGS_SQL
+    );
+
+    sqlx::query(&sql)
+      .bind(id)
+      .try_map(|row: SqliteRow| question_from_row(&row))
+      .fetch_optional(executor)
+      .await
+      .map_err(Self::database_error)
+  } // end fn fetch_question()
+
+  /// Replaces the tags of a question, creating the tags that do not exist yet.
+  ///
+  /// # Arguments
+  ///
+  /// * `transaction`: Transaction the question is being written in.
+  /// * `id`: Unique identifier (ID) of the question.
+  /// * `tags`: Names of the tags for the question.
+  async fn set_question_tags(
+    transaction: &mut Transaction<'_, Sqlite>,
+    id: i32,
+    tags: Vec<String>,
+  ) -> Result<(), QError> {
+    sqlx::query("DELETE FROM question_tags WHERE question_id = ?")
+      .bind(id)
+      .execute(&mut *transaction)
+      .await
+      .map_err(Self::database_error)?;
+
+    for tag in tags {
+      sqlx::query("INSERT INTO tags (name) VALUES (?) ON CONFLICT (name) DO NOTHING")
+        .bind(&tag)
+        .execute(&mut *transaction)
+        .await
+        .map_err(Self::database_error)?;
+      sqlx::query(
+        r#"INSERT OR IGNORE INTO question_tags (question_id, tag_id)
+        SELECT ?, id FROM tags WHERE name = ?"#,
+      )
+      .bind(id)
+      .bind(&tag)
+      .execute(&mut *transaction)
+      .await
+      .map_err(Self::database_error)?;
+    }
+
+    Ok(())
+  } // end fn set_question_tags()
+
+  /// Converts an error of the database into the error replied to the client. The
+  /// errors for broken constraints are replied as client errors, and every other error
+  /// is wrapped as is.
+  ///
+  /// # Arguments
+  ///
+  /// * `err`: Error returned by the database.
+  fn database_error(err: sqlx::Error) -> QError {
+    let db_err = match err.as_database_error() {
+      Some(db_err) => db_err,
+      None => {
+        tracing::event!(tracing::Level::ERROR, "{:?}", err);
+        return QError::DatabaseQueryError(err);
+      }
+    };
+    // SQLite names the columns of the constraint in the message, not the constraint.
+    let constraint = db_err.message().to_string();
+
+    let error = match db_err.code().as_deref() {
+      Some(CONSTRAINT_UNIQUE) => QError::Conflict(constraint),
+      Some(CONSTRAINT_FOREIGNKEY) => QError::InvalidReference(constraint),
+      Some(CONSTRAINT_NOTNULL) => QError::MissingValue(constraint),
+      Some(CONSTRAINT_CHECK) => QError::InvalidParameter(format!(
+        "the value does not satisfy the constraint: {}",
+        constraint
+      )),
+      _ => {
+        tracing::event!(tracing::Level::ERROR, "{:?}", err);
+        return QError::DatabaseQueryError(err);
+      }
+    };
+
+    tracing::event!(tracing::Level::WARN, "{:?}", err);
+    error
+  } // end fn database_error()
+}
+
+#[async_trait]
+impl QuestionStore for SqliteStore {
+  /// Gets the collection of questions as a stream of rows, each one along with the
+  /// amount of questions that match the query across all the pages.
+  ///
+  /// Only the status, creation dates, tags, code language and pagination of the query
+  /// are applied, and the questions are listed by their ID.
+  ///
+  /// # Arguments
+  ///
+  /// * `query`: Pagination, filters and tags of the set of questions to retrieve. With
+  ///   keyset pagination, the questions after the given ID are listed.
+  fn get_questions(
+    &self,
+    query: QuestionQuery,
+  ) -> BoxStream<'_, Result<(Question, i64), QError>> {
+    Box::pin(async_stream::try_stream! {
+      let mut builder: QueryBuilder<Sqlite> = QueryBuilder::new(format!(
+        r#"SELECT {}, {}, COUNT(*) OVER () AS total_count
+        FROM questions q
+        WHERE q.deleted_at IS NULL"#,
+        QUESTION_COLUMNS_SQL, QUESTION_TAGS_SQL
+      ));
+
+      if let Some(status) = query.status {
+        builder.push(" AND q.status = ").push_bind(status.as_str());
+      }
+      if let Some(created_after) = query.created_after {
+        builder.push(" AND q.created_on > ").push_bind(created_after);
+      }
+      if let Some(created_before) = query.created_before {
+        builder.push(" AND q.created_on < ").push_bind(created_before);
+      }
+      if !query.tags.is_empty() {
+        let tags: HashSet<&String> = query.tags.iter().collect();
+        builder.push(
+          r#" AND (SELECT COUNT(*) FROM question_tags qt JOIN tags t ON t.id = qt.tag_id
+          WHERE qt.question_id = q.id AND t.name IN ("#,
+        );
+        let mut names = builder.separated(", ");
+        for tag in &tags {
+          names.push_bind(tag.to_string());
+        }
+        match query.tag_match {
+          TagMatch::All => builder.push(")) = ").push_bind(tags.len() as i64),
+          TagMatch::Any => builder.push(")) > 0"),
+        };
+      }
+      if let Some(language) = query.code_language.clone() {
+        builder
+          .push(
+            r#" AND EXISTS (SELECT 1 FROM answers a
+            JOIN answer_code_blocks c ON c.answer_id = a.id
+            WHERE a.question_id = q.id AND c.language = "#,
+          )
+          .push_bind(language)
+          .push(")");
+      }
+      if let Some(after) = query.pagination.after {
+        builder.push(" AND q.id > ").push_bind(after);
+      }
+
+      // SQLite only takes an offset after a limit, where -1 means no limit.
+      let limit = query.pagination.limit.unwrap_or(-1);
+      builder.push(" ORDER BY q.id LIMIT ").push_bind(limit);
+      builder.push(" OFFSET ").push_bind(query.pagination.offset);
+
+      let mut rows = builder
+        .build()
+        .try_map(|row: SqliteRow| {
+          Ok((question_from_row(&row)?, row.try_get("total_count")?))
+        })
+        .fetch(&self.connection)
+        .map_err(Self::database_error);
+
+      while let Some(row) = rows.try_next().await? {
+        yield row;
+      }
+    })
+  } // end fn get_questions()
+
+  /// Gets all the questions joined with their answers as a stream of rows, to export
+  /// the data store. The rows are fetched as the stream is polled.
+  fn export_rows(&self) -> BoxStream<'_, Result<ExportRow, QError>> {
+    let sql = format!(
+      r#"SELECT q.id AS question_id, q.title, q.content, {}, q.status, q.kind,
+        q.created_on AS question_created_on, q.deleted_at AS question_deleted_at,
+        a.id AS answer_id, a.content AS answer_content, a.created_on AS answer_created_on
+      FROM questions q LEFT JOIN answers a ON a.question_id = q.id
+      ORDER BY q.id, a.id"#,
+      QUESTION_TAGS_SQL
+    );
+
+    Box::pin(async_stream::try_stream! {
+      let mut rows = sqlx::query(&sql)
+        .try_map(|row: SqliteRow| {
+          let tags: Vec<String> = json_column(&row, "tags")?;
+          Ok(ExportRow {
+            question_id: QuestionId::new(row.try_get("question_id")?),
+            title: row.try_get("title")?,
+            content: row.try_get("content")?,
+            tags: (!tags.is_empty()).then_some(tags),
+            status: row.try_get("status")?,
+            kind: row.try_get("kind")?,
+            question_created_on: row.try_get("question_created_on")?,
+            question_deleted_at: row.try_get("question_deleted_at")?,
+            answer_id: row.try_get::<Option<i32>, _>("answer_id")?.map(AnswerId::new),
+            answer_content: row.try_get("answer_content")?,
+            answer_created_on: row.try_get("answer_created_on")?,
+          })
+        })
+        .fetch(&self.connection)
+        .map_err(Self::database_error);
+
+      while let Some(row) = rows.try_next().await? {
+        yield row;
+      }
+    })
+  } // end fn export_rows()
+
+  /// Gets the question specified by the given id.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the question.
+  async fn get_question_by_id(
+    &self,
+    id: i32,
+  ) -> Result<Question, QError> {
+    match Self::fetch_question(&self.connection, id).await? {
+      Some(question) => Ok(question),
+      None => Err(QError::QuestionNotFound),
+    }
+  } // end fn get_question_by_id()
+
+  /// Adds a new question to the system.
+  ///
+  /// The question, its tags and its options are inserted within a single transaction.
+  ///
+  /// # Arguments
+  ///
+  /// * `question`: Question to be added.
+  async fn add_question(
+    &self,
+    question: NewQuestion,
+  ) -> Result<Vec<Question>, QError> {
+    let mut transaction = self.connection.begin().await.map_err(Self::database_error)?;
+
+    let id: i32 = sqlx::query_scalar(
+      r#"INSERT INTO questions (title, content, kind, rating_max)
+      VALUES (?, ?, ?, ?)
+      RETURNING id"#,
+    )
+    .bind(question.title)
+    .bind(question.content)
+    .bind(question.kind.as_str())
+    .bind(question.rating_max)
+    .fetch_one(&mut transaction)
+    .await
+    .map_err(Self::database_error)?;
+
+    let tags = question.tags.unwrap_or_default();
+    Self::set_question_tags(&mut transaction, id, tags).await?;
+
+    let points = question.points.unwrap_or_default();
+    for (index, label) in question.options.unwrap_or_default().into_iter().enumerate() {
+      sqlx::query(
+        r#"INSERT INTO question_options (question_id, position, label, points)
+        VALUES (?, ?, ?, ?)"#,
+      )
+      .bind(id)
+      .bind(index as i32 + 1)
+      .bind(label)
+      .bind(points.get(index).copied().unwrap_or_default())
+      .execute(&mut transaction)
+      .await
+      .map_err(Self::database_error)?;
+    }
+
+    let questions: Vec<Question> = Self::fetch_question(&mut transaction, id)
+      .await?
+      .into_iter()
+      .collect();
+
+    transaction.commit().await.map_err(Self::database_error)?;
+
+    Ok(questions)
+  } // end fn add_question()
+
+  /// Updates the title, contents and tags of a question, if it is still at the given
+  /// version, and moves it to the next version.
+  ///
+  /// The question and its tags are updated within a single transaction.
+  ///
+  /// # Arguments
+  ///
+  /// * `question`: Question data.
+  /// * `id`: Unique identifier (ID) of the question.
+  /// * `version`: Version of the question the update is based on.
+  async fn update_question(
+    &self,
+    question: Question,
+    id: i32,
+    version: i32,
+  ) -> Result<Vec<Question>, QError> {
+    let mut transaction = self.connection.begin().await.map_err(Self::database_error)?;
+
+    let updated = sqlx::query(
+      r#"UPDATE questions
+      SET title = ?, content = ?, version = version + 1
+      WHERE id = ? AND version = ? AND deleted_at IS NULL"#,
+    )
+    .bind(&question.title)
+    .bind(&question.content)
+    .bind(id)
+    .bind(version)
+    .execute(&mut transaction)
+    .await
+    .map_err(Self::database_error)?;
+
+    if updated.rows_affected() == 0 {
+      return match Self::fetch_question(&mut transaction, id).await? {
+        Some(current) => {
+          let hint = merge_hint(version, None, &current, &question);
+          Err(QError::VersionConflict(Box::new(hint)))
+        }
+        None => Err(QError::QuestionNotFound),
+      };
+    }
+
+    let tags = question.tags.unwrap_or_default();
+    Self::set_question_tags(&mut transaction, id, tags).await?;
+
+    let questions: Vec<Question> = Self::fetch_question(&mut transaction, id)
+      .await?
+      .into_iter()
+      .collect();
+
+    transaction.commit().await.map_err(Self::database_error)?;
+
+    Ok(questions)
+  } // end fn update_question()
+
+  /// Marks the question specified by the given id as deleted, which hides its answers
+  /// too.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the question to be deleted.
+  async fn delete_question(
+    &self,
+    id: i32,
+  ) -> Result<(), QError> {
+    let deleted = sqlx::query(
+      r#"UPDATE questions SET deleted_at = CURRENT_TIMESTAMP
+      WHERE id = ? AND deleted_at IS NULL"#,
+    )
+    .bind(id)
+    .execute(&self.connection)
+    .await
+    .map_err(Self::database_error)?;
+
+    match deleted.rows_affected() {
+      0 => Err(QError::QuestionNotFound),
+      _ => Ok(()),
+    }
+  } // end fn delete_question()
+
+  /// Adds a new answer to the datastore.
+  ///
+  /// The question is checked within the same transaction as the insertion, and SQLite
+  /// allows a single writer at a time, so an answer cannot reference a question that
+  /// does not exist or is no longer open.
+  ///
+  /// # Arguments
+  ///
+  /// * `answer`: Answer to be added.
+  async fn add_answer(
+    &self,
+    answer: NewAnswer,
+  ) -> Result<Answer, QError> {
+    let mut transaction = self.connection.begin().await.map_err(Self::database_error)?;
+
+    let status: Option<String> = sqlx::query_scalar(
+      "SELECT status FROM questions WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(answer.question_id.value())
+    .fetch_optional(&mut transaction)
+    .await
+    .map_err(Self::database_error)?;
+    match status {
+      Some(status) if status == QuestionStatus::Open.as_str() => (),
+      Some(_) => return Err(QError::QuestionClosed),
+      None => return Err(QError::QuestionNotFound),
+    }
+
+    let id: i32 = sqlx::query_scalar(
+      r#"INSERT INTO answers (content, question_id)
+      VALUES (?, ?)
+      RETURNING id"#,
+    )
+    .bind(&answer.content)
+    .bind(answer.question_id.value())
+    .fetch_one(&mut transaction)
+    .await
+    .map_err(Self::database_error)?;
+
+    let code_blocks = extract_code_blocks(&answer.content);
+    for (index, block) in code_blocks.iter().enumerate() {
+      sqlx::query(
+        r#"INSERT INTO answer_code_blocks
+          (answer_id, position, language, start_line, line_count)
+        VALUES (?, ?, ?, ?, ?)"#,
+      )
+      .bind(id)
+      .bind(index as i32 + 1)
+      .bind(&block.language)
+      .bind(block.start_line)
+      .bind(block.line_count)
+      .execute(&mut transaction)
+      .await
+      .map_err(Self::database_error)?;
+    }
+
+    transaction.commit().await.map_err(Self::database_error)?;
+
+    Ok(Answer {
+      id: AnswerId::new(id),
+      content: answer.content,
+      question_id: answer.question_id,
+      code_blocks,
+    })
+  } // end fn add_answer()
+}
+
+/// Builds a question from a row with the columns of `QUESTION_COLUMNS_SQL` and
+/// `QUESTION_TAGS_SQL`. The tags and options are `None` when the question has none, and
+/// the points when no option is worth points, like the PostgreSQL store replies them.
+///
+/// # Arguments
+///
+/// * `row`: Row of the results of a question query.
+fn question_from_row(row: &SqliteRow) -> Result<Question, sqlx::Error> {
+  let tags: Vec<String> = json_column(row, "tags")?;
+  let options: Vec<String> = json_column(row, "options")?;
+  let points: Vec<i32> = json_column(row, "points")?;
+
+  Ok(Question {
+    id: QuestionId::new(row.try_get("id")?),
+    title: row.try_get("title")?,
+    content: row.try_get("content")?,
+    tags: (!tags.is_empty()).then_some(tags),
+    status: text_column(row, "status")?,
+    kind: text_column(row, "kind")?,
+    options: (!options.is_empty()).then_some(options),
+    rating_max: row.try_get("rating_max")?,
+    points: points.iter().any(|points| *points != 0).then_some(points),
+    version: row.try_get("version")?,
+  })
+} // end fn question_from_row()
+
+/// Decodes a column with a JSON array into its elements.
+///
+/// # Arguments
+///
+/// * `row`: Row that has the column.
+/// * `column`: Name of the column.
+fn json_column<T: serde::de::DeserializeOwned>(
+  row: &SqliteRow,
+  column: &str,
+) -> Result<Vec<T>, sqlx::Error> {
+  let json: String = row.try_get(column)?;
+  serde_json::from_str(&json).map_err(|err| sqlx::Error::ColumnDecode {
+    index: column.to_string(),
+    source: Box::new(err),
+  })
+} // end fn json_column()
+
+/// Decodes a column stored as text into a value parsed from that text.
+///
+/// # Arguments
+///
+/// * `row`: Row that has the column.
+/// * `column`: Name of the column.
+fn text_column<T>(
+  row: &SqliteRow,
+  column: &str,
+) -> Result<T, sqlx::Error>
+where
+  T: std::str::FromStr,
+  T::Err: std::error::Error + Send + Sync + 'static,
+{
+  row
+    .try_get::<String, _>(column)?
+    .parse()
+    .map_err(|err: T::Err| sqlx::Error::ColumnDecode {
+      index: column.to_string(),
+      source: Box::new(err),
+    })
+} // end fn text_column()
@@ -0,0 +1,101 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use crate::types::health::TaskStatus;
+
+/// Seconds a background task may run late, on top of twice its interval, before it is
+/// considered stuck or dead.
+const TASK_GRACE_SECS: u64 = 30;
+
+/// Represents what is known about a background task.
+#[derive(Debug, Clone)]
+struct TaskState {
+  /// Time between runs of the task.
+  interval: Duration,
+  /// When the task was registered.
+  registered: Instant,
+  /// When the task last finished a run, if it did.
+  last_run: Option<Instant>,
+  /// Description of the problem of the last run, if it failed.
+  last_error: Option<String>,
+} // end struct TaskState
+
+/// Represents the monitor of the background tasks, which report each run so the
+/// readiness check can tell when one of them stopped running.
+#[derive(Debug, Clone, Default)]
+pub struct TaskMonitor {
+  /// States of the tasks, by their name.
+  tasks: Arc<Mutex<BTreeMap<&'static str, TaskState>>>,
+} // end struct TaskMonitor
+
+impl TaskMonitor {
+  /// Registers a background task that runs at the given interval.
+  ///
+  /// # Arguments
+  ///
+  /// * `name`: Name of the task.
+  /// * `interval`: Time between runs of the task.
+  pub fn register(
+    &self,
+    name: &'static str,
+    interval: Duration,
+  ) {
+    self.tasks().insert(
+      name,
+      TaskState {
+        interval,
+        registered: Instant::now(),
+        last_run: None,
+        last_error: None,
+      },
+    );
+  } // end fn register()
+
+  /// Records that a run of a background task finished.
+  ///
+  /// # Arguments
+  ///
+  /// * `name`: Name of the task.
+  /// * `error`: Description of the problem of the run, if it failed.
+  pub fn record_run(
+    &self,
+    name: &'static str,
+    error: Option<String>,
+  ) {
+    if let Some(task) = self.tasks().get_mut(name) {
+      task.last_run = Some(Instant::now());
+      task.last_error = error;
+    }
+  } // end fn record_run()
+
+  /// Gets the status of each background task. A task that has not finished a run for
+  /// more than twice its interval, plus some grace, is not running: it panicked or is
+  /// stuck. A failed run does not make it unhealthy, since it is retried.
+  pub fn statuses(&self) -> Vec<TaskStatus> {
+    let now = Instant::now();
+    self
+      .tasks()
+      .iter()
+      .map(|(name, task)| {
+        let since = now.duration_since(task.last_run.unwrap_or(task.registered));
+        TaskStatus {
+          name: name.to_string(),
+          running: since <= task.interval * 2 + Duration::from_secs(TASK_GRACE_SECS),
+          interval_secs: task.interval.as_secs(),
+          last_run_secs_ago: task.last_run.map(|last_run| {
+            now.duration_since(last_run).as_secs_f64()
+          }),
+          last_error: task.last_error.clone(),
+        }
+      })
+      .collect()
+  } // end fn statuses()
+
+  /// Locks the states of the tasks.
+  fn tasks(&self) -> MutexGuard<'_, BTreeMap<&'static str, TaskState>> {
+    // The states are only updated whole, so they are still usable after a panic while
+    // they were locked.
+    self.tasks.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+  } // end fn tasks()
+}
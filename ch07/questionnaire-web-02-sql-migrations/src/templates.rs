@@ -0,0 +1,22 @@
+use crate::types::survey::SurveyId;
+
+/// HTML page of the survey widget, with a `{{survey_id}}` placeholder.
+const SURVEY_EMBED_HTML: &str = include_str!("../templates/survey-embed.html");
+
+/// Script of the survey widget, which renders the survey and posts the responses.
+pub const SURVEY_EMBED_SCRIPT: &str = include_str!("../templates/survey-embed.js");
+
+/// Style sheet of the survey widget.
+pub const SURVEY_EMBED_STYLE: &str = include_str!("../templates/survey-embed.css");
+
+/// Renders the HTML page of the survey widget for the given survey.
+///
+/// The page has no inline scripts or styles, so it can be served with a strict Content
+/// Security Policy.
+///
+/// # Arguments
+///
+/// * `id`: Unique identifier (ID) of the survey.
+pub fn survey_embed(id: SurveyId) -> String {
+  SURVEY_EMBED_HTML.replace("{{survey_id}}", &id.value().to_string())
+} // end fn survey_embed()
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use futures::future::BoxFuture;
+use serde::{Deserialize, Serialize};
+use warp::http::header::CONTENT_TYPE;
+use warp::http::{Request, StatusCode};
+use warp::hyper::body::to_bytes;
+use warp::hyper::client::HttpConnector;
+use warp::hyper::{Body, Client};
+
+use handle_errors::errors::QError;
+
+use crate::types::degradation::{DegradationPolicy, Dependency};
+use crate::types::question::Question;
+
+/// Amount of translated questions kept in the cache. It is emptied when it gets full.
+const CACHE_CAPACITY: usize = 1000;
+
+/// Translated titles and contents, by question ID, language and question version.
+type Cache = HashMap<(i32, String, i32), (String, String)>;
+
+/// Translates texts into other languages, through an external service or an offline
+/// model.
+pub trait Translator: Debug + Send + Sync {
+  /// Translates the given texts, from whatever language they are in, into the given
+  /// language. The translations are returned in the same order as the texts.
+  ///
+  /// # Arguments
+  ///
+  /// * `texts`: Texts to translate.
+  /// * `language`: Code of the language to translate into, like `es`.
+  fn translate<'a>(
+    &'a self,
+    texts: &'a [String],
+    language: &'a str,
+  ) -> BoxFuture<'a, Result<Vec<String>, QError>>;
+}
+
+/// Represents the body of a request to a LibreTranslate compatible service.
+#[derive(Debug, Serialize)]
+struct TranslateRequest<'a> {
+  /// Texts to translate.
+  q: &'a [String],
+  /// Language of the texts, detected by the service.
+  source: &'a str,
+  /// Language to translate into.
+  target: &'a str,
+  /// Format of the texts.
+  format: &'a str,
+  /// Key to use the service, if it requires one.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  api_key: Option<&'a str>,
+} // end struct TranslateRequest
+
+/// Represents the body of a response of a LibreTranslate compatible service.
+#[derive(Debug, Deserialize)]
+struct TranslateResponse {
+  /// Translations of the texts, in the same order.
+  #[serde(rename = "translatedText")]
+  translated_text: Vec<String>,
+} // end struct TranslateResponse
+
+/// Represents a translation service with the API of LibreTranslate, served over plain
+/// HTTP, like a self-hosted instance next to this application.
+#[derive(Debug, Clone)]
+pub struct HttpTranslator {
+  /// HTTP client to send the requests with.
+  client: Client<HttpConnector>,
+  /// Base URL of the service, like `http://localhost:5000`.
+  url: String,
+  /// Key to use the service, if it requires one.
+  api_key: Option<String>,
+} // end struct HttpTranslator
+
+impl HttpTranslator {
+  /// Creates a client of the translation service at the given URL.
+  ///
+  /// # Arguments
+  ///
+  /// * `url`: Base URL of the service.
+  /// * `api_key`: Key to use the service, if it requires one.
+  pub fn new(
+    url: &str,
+    api_key: Option<String>,
+  ) -> Self {
+    HttpTranslator {
+      client: Client::new(),
+      url: url.trim_end_matches('/').to_string(),
+      api_key,
+    }
+  } // end fn new()
+
+  /// Sends the texts to the service and gets their translations.
+  ///
+  /// # Arguments
+  ///
+  /// * `texts`: Texts to translate.
+  /// * `language`: Code of the language to translate into.
+  async fn send(
+    &self,
+    texts: &[String],
+    language: &str,
+  ) -> Result<Vec<String>, String> {
+    let body = serde_json::to_vec(&TranslateRequest {
+      q: texts,
+      source: "auto",
+      target: language,
+      format: "text",
+      api_key: self.api_key.as_deref(),
+    })
+    .map_err(|err| err.to_string())?;
+    let request = Request::post(format!("{}/translate", self.url))
+      .header(CONTENT_TYPE, "application/json")
+      .body(Body::from(body))
+      .map_err(|err| err.to_string())?;
+
+    let response = self.client.request(request).await.map_err(|err| err.to_string())?;
+    let status = response.status();
+    let body = to_bytes(response.into_body()).await.map_err(|err| err.to_string())?;
+    if status != StatusCode::OK {
+      return Err(format!("status {}: {}", status, String::from_utf8_lossy(&body)));
+    }
+
+    let translations: TranslateResponse =
+      serde_json::from_slice(&body).map_err(|err| err.to_string())?;
+    match translations.translated_text.len() == texts.len() {
+      true => Ok(translations.translated_text),
+      false => Err("the amount of translations does not match the texts".to_string()),
+    }
+  } // end fn send()
+}
+
+impl Translator for HttpTranslator {
+  fn translate<'a>(
+    &'a self,
+    texts: &'a [String],
+    language: &'a str,
+  ) -> BoxFuture<'a, Result<Vec<String>, QError>> {
+    Box::pin(async move {
+      self.send(texts, language).await.map_err(|err| {
+        tracing::event!(tracing::Level::WARN, "Cannot translate into {}. {}", language, err);
+        QError::TranslationFailed
+      })
+    })
+  }
+}
+
+/// Represents the translations of the questions, made by a translator and cached per
+/// question, language and version, so each version is translated once per language.
+#[derive(Debug, Clone)]
+pub struct Translations {
+  /// Translator of the questions.
+  translator: Arc<dyn Translator>,
+  /// What to do with the requests when the translator fails.
+  policy: DegradationPolicy,
+  /// Translations made so far.
+  cache: Arc<Mutex<Cache>>,
+} // end struct Translations
+
+impl Translations {
+  /// Creates the translations made by the given translator.
+  ///
+  /// # Arguments
+  ///
+  /// * `translator`: Translator of the questions.
+  /// * `policy`: What to do with the requests when the translator fails.
+  pub fn new(
+    translator: Arc<dyn Translator>,
+    policy: DegradationPolicy,
+  ) -> Self {
+    Translations {
+      translator,
+      policy,
+      cache: Arc::new(Mutex::new(HashMap::new())),
+    }
+  } // end fn new()
+
+  /// Gets the translations from the service set in the `TRANSLATOR_URL` and
+  /// `TRANSLATOR_API_KEY` environment variables, or `None` when no service is set.
+  ///
+  /// # Arguments
+  ///
+  /// * `policy`: What to do with the requests when the translator fails.
+  pub fn from_env(policy: DegradationPolicy) -> Option<Self> {
+    let url = std::env::var("TRANSLATOR_URL").ok()?;
+    let api_key = std::env::var("TRANSLATOR_API_KEY").ok();
+
+    Some(Translations::new(Arc::new(HttpTranslator::new(&url, api_key)), policy))
+  } // end fn from_env()
+
+  /// Gets the given question translated into the given language, following the policy
+  /// of the translator when it fails, along with whether it was translated:
+  ///
+  /// * `Fail`: The error of the translator is returned.
+  /// * `Degrade`: The question is returned as is.
+  /// * `Queue`: The question is returned as is, unless its translation is cached, and
+  ///   it is translated in the background for the next requests.
+  ///
+  /// # Arguments
+  ///
+  /// * `question`: Question to translate.
+  /// * `language`: Code of the language to translate into, like `es`.
+  pub async fn localize(
+    &self,
+    question: Question,
+    language: &str,
+  ) -> Result<(Question, bool), QError> {
+    match self.policy {
+      DegradationPolicy::Fail => {
+        let question = self.translate_question(question, language).await?;
+        Ok((question, true))
+      }
+      DegradationPolicy::Degrade => {
+        match self.translate_question(question.clone(), language).await {
+          Ok(translated) => Ok((translated, true)),
+          Err(err) => {
+            Dependency::Translator.log_degraded(self.policy, err);
+            Ok((question, false))
+          }
+        }
+      }
+      DegradationPolicy::Queue => {
+        let key = (question.id.value(), language.to_string(), question.version);
+        if let Some((title, content)) = self.cache().get(&key).cloned() {
+          return Ok((Question { title, content, ..question }, true));
+        }
+
+        let translations = self.clone();
+        let (pending, language) = (question.clone(), language.to_string());
+        tokio::spawn(async move {
+          if let Err(err) = translations.translate_question(pending, &language).await {
+            Dependency::Translator.log_degraded(translations.policy, err);
+          }
+        });
+        Ok((question, false))
+      }
+    }
+  } // end fn localize()
+
+  /// Gets the given question with its title and content translated into the given
+  /// language.
+  ///
+  /// # Arguments
+  ///
+  /// * `question`: Question to translate.
+  /// * `language`: Code of the language to translate into, like `es`.
+  pub async fn translate_question(
+    &self,
+    mut question: Question,
+    language: &str,
+  ) -> Result<Question, QError> {
+    let key = (question.id.value(), language.to_string(), question.version);
+    let cached = self.cache().get(&key).cloned();
+
+    let (title, content) = match cached {
+      Some(translation) => translation,
+      None => {
+        let texts = [question.title.clone(), question.content.clone()];
+        let mut translations = self.translator.translate(&texts, language).await?;
+        let content = translations.pop().unwrap_or_default();
+        let title = translations.pop().unwrap_or_default();
+
+        let mut cache = self.cache();
+        // The translations of the older versions are not served anymore.
+        cache.retain(|(id, _, version), _| *id != key.0 || *version >= key.2);
+        if cache.len() >= CACHE_CAPACITY {
+          cache.clear();
+        }
+        cache.insert(key, (title.clone(), content.clone()));
+        (title, content)
+      }
+    };
+
+    question.title = title;
+    question.content = content;
+    Ok(question)
+  } // end fn translate_question()
+
+  /// Locks the cache of translations.
+  fn cache(&self) -> MutexGuard<'_, Cache> {
+    // The cache only holds copies of translations, so it is still usable after a
+    // panic while it was locked.
+    self.cache.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+  } // end fn cache()
+}
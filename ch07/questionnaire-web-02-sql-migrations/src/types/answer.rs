@@ -1,10 +1,16 @@
 use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
 
+use handle_errors::errors::QError;
+
+use crate::types::code::CodeBlock;
+use crate::types::id::Id;
 use crate::types::question::QuestionId;
+use crate::types::validation::{FieldErrors, CONTENT_MAX_CHARS};
 
 /// Represents the unique identifier (ID) of an answer.
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Hash)]
-pub struct AnswerId(pub i32);
+pub type AnswerId = Id<Answer>;
 
 /// Represents an answer to a given question.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,13 +21,104 @@ pub struct Answer {
   pub content: String,
   /// Unique identifier (ID) of the question this answer belongs to.
   pub question_id: QuestionId,
+  /// Fenced code blocks of the contents, in order.
+  #[serde(default)]
+  pub code_blocks: Vec<CodeBlock>,
 } // end struct Answer
 
+impl<'r> FromRow<'r, PgRow> for Answer {
+  /// Builds an answer from a row with the `id`, `content`, `question_id`,
+  /// `code_languages`, `code_start_lines` and `code_line_counts` columns. The code
+  /// columns have one element per code block, in order.
+  ///
+  /// # Arguments
+  ///
+  /// * `row`: Row of the results of an answer query.
+  fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+    let languages: Vec<Option<String>> = row.try_get("code_languages")?;
+    let start_lines: Vec<i32> = row.try_get("code_start_lines")?;
+    let line_counts: Vec<i32> = row.try_get("code_line_counts")?;
+
+    Ok(Answer {
+      id: row.try_get("id")?,
+      content: row.try_get("content")?,
+      question_id: row.try_get("question_id")?,
+      code_blocks: languages
+        .into_iter()
+        .zip(start_lines)
+        .zip(line_counts)
+        .map(|((language, start_line), line_count)| CodeBlock {
+          language,
+          start_line,
+          line_count,
+        })
+        .collect(),
+    })
+  } // end fn from_row()
+}
+
+/// Represents an answer just added, along with the existing answers of the same question
+/// with a similar content, which it may duplicate.
+#[derive(Debug, Clone, Serialize)]
+pub struct AddedAnswer {
+  /// Answer added.
+  pub answer: Answer,
+  /// Existing answers with a similar content, from the most to the least similar.
+  pub possible_duplicates: Vec<Answer>,
+} // end struct AddedAnswer
+
 /// Represents an new answer to be added to the system.
+///
+/// Unknown fields are rejected, so typos like `contents` are not ignored.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct NewAnswer {
   /// Text contents of the answer.
   pub content: String,
   /// Unique identifier (ID) of the question this answer belongs to.
   pub question_id: QuestionId,
-} // end struct Answer
\ No newline at end of file
+} // end struct Answer
+
+/// Represents the form fields of a new answer as sent, so the missing ones are all
+/// reported at once instead of failing on the first one.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AnswerForm {
+  /// Text contents of the answer.
+  pub content: Option<String>,
+  /// Unique identifier (ID) of the question this answer belongs to.
+  pub question_id: Option<QuestionId>,
+} // end struct AnswerForm
+
+impl AnswerForm {
+  /// Gets the new answer of the form, or a `MissingFields` error listing the fields
+  /// that were not given.
+  pub fn into_answer(self) -> Result<NewAnswer, QError> {
+    match (self.content, self.question_id) {
+      (Some(content), Some(question_id)) => Ok(NewAnswer {
+        content,
+        question_id,
+      }),
+      (content, question_id) => {
+        let mut missing = Vec::new();
+        if content.is_none() {
+          missing.push("content".to_string());
+        }
+        if question_id.is_none() {
+          missing.push("question_id".to_string());
+        }
+        Err(QError::MissingFields(missing))
+      }
+    }
+  } // end fn into_answer()
+}
+
+impl NewAnswer {
+  /// Checks that the content of the answer is not empty nor too long.
+  pub fn validate(&self) -> Result<(), QError> {
+    let mut errors = FieldErrors::default();
+    errors.check_text("content", &self.content, CONTENT_MAX_CHARS);
+
+    errors.into_result()
+  } // end fn validate()
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::question::QuestionId;
+
+/// Represents the questions changed since a change cursor, for the clients that sync
+/// them incrementally, like offline-capable clients and search indexers.
+///
+/// Each question is listed once: as deleted when its last change deleted it, as
+/// created when it was created since the cursor, or else as updated.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QuestionChanges {
+  /// IDs of the questions created, in order.
+  pub created: Vec<QuestionId>,
+  /// IDs of the questions updated, restored ones included, in order.
+  pub updated: Vec<QuestionId>,
+  /// IDs of the questions deleted, in order.
+  pub deleted: Vec<QuestionId>,
+  /// Cursor to get the next changes with, as the `since` parameter.
+  pub cursor: i64,
+} // end struct QuestionChanges
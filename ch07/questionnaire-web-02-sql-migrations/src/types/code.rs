@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+
+/// Longest language name of a code block kept, in characters. Longer ones are cut.
+pub const LANGUAGE_MAX_CHARS: usize = 32;
+
+/// Represents a fenced code block of the content of an answer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CodeBlock {
+  /// Language of the code, in lowercase, as given after the opening fence, like `rust`,
+  /// or `None` when no language is given.
+  pub language: Option<String>,
+  /// Line of the content, starting at 1, where the code starts, after the opening
+  /// fence.
+  pub start_line: i32,
+  /// Amount of lines of code, without the fences.
+  pub line_count: i32,
+} // end struct CodeBlock
+
+/// Represents the opening fence of a code block.
+struct Fence {
+  /// Character of the fence, a backtick or a tilde.
+  marker: char,
+  /// Amount of characters of the fence.
+  length: usize,
+} // end struct Fence
+
+/// Gets the fenced code blocks of the given Markdown content, in order.
+///
+/// The fences follow CommonMark: three or more backticks or tildes, indented by up to
+/// three spaces, closed by a fence of the same character that is at least as long. A
+/// block that is not closed runs until the end of the content. Fences within block
+/// quotes and list items are not recognized.
+///
+/// # Arguments
+///
+/// * `content`: Markdown content of an answer.
+///
+/// # Example Usage
+///
+/// ```rust
+/// let content = "Try this:\n```Rust\nfn main() {}\n```\n";
+/// let blocks = types::code::extract_code_blocks(content);
+/// assert_eq!(blocks[0].language.as_deref(), Some("rust"));
+/// assert_eq!((blocks[0].start_line, blocks[0].line_count), (3, 1));
+/// ```
+pub fn extract_code_blocks(content: &str) -> Vec<CodeBlock> {
+  let mut blocks = Vec::new();
+  let mut open: Option<(Fence, CodeBlock)> = None;
+
+  for (index, line) in content.lines().enumerate() {
+    let number = index as i32 + 1;
+    match open.take() {
+      None => {
+        open = opening_fence(line).map(|(fence, language)| {
+          let block = CodeBlock {
+            language,
+            start_line: number + 1,
+            line_count: 0,
+          };
+          (fence, block)
+        });
+      }
+      Some((fence, block)) if closes(line, &fence) => blocks.push(block),
+      Some((fence, mut block)) => {
+        block.line_count += 1;
+        open = Some((fence, block));
+      }
+    }
+  }
+  blocks.extend(open.map(|(_, block)| block));
+
+  blocks
+} // end fn extract_code_blocks()
+
+/// Gets the fence and the language of the given line, if it opens a code block.
+///
+/// # Arguments
+///
+/// * `line`: Line of the content.
+fn opening_fence(line: &str) -> Option<(Fence, Option<String>)> {
+  let rest = unindent(line)?;
+  let marker = rest.chars().next().filter(|c| *c == '`' || *c == '~')?;
+  let length = rest.chars().take_while(|c| *c == marker).count();
+  let info = rest[length..].trim();
+  // A backtick in the info string makes the line inline code instead.
+  if length < 3 || (marker == '`' && info.contains('`')) {
+    return None;
+  }
+
+  let language = info
+    .split_whitespace()
+    .next()
+    .map(|word| word.to_lowercase().chars().take(LANGUAGE_MAX_CHARS).collect());
+  Some((Fence { marker, length }, language))
+} // end fn opening_fence()
+
+/// Checks whether the given line closes the code block opened by the given fence.
+///
+/// # Arguments
+///
+/// * `line`: Line of the content.
+/// * `fence`: Opening fence of the code block.
+fn closes(
+  line: &str,
+  fence: &Fence,
+) -> bool {
+  let rest = match unindent(line) {
+    Some(rest) => rest,
+    None => return false,
+  };
+  let length = rest.chars().take_while(|c| *c == fence.marker).count();
+
+  length >= fence.length && rest[length..].trim().is_empty()
+} // end fn closes()
+
+/// Gets the given line without its indentation, if it is indented by up to three
+/// spaces, as a fence may be.
+///
+/// # Arguments
+///
+/// * `line`: Line of the content.
+fn unindent(line: &str) -> Option<&str> {
+  let rest = line.trim_start_matches(' ');
+  (line.len() - rest.len() <= 3).then_some(rest)
+} // end fn unindent()
@@ -0,0 +1,160 @@
+/// Represents what to do with the requests that need a dependency that is down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DegradationPolicy {
+  /// The requests fail with an error.
+  Fail,
+  /// The requests are replied without the dependency, like the read replica being
+  /// replaced by the primary database.
+  Degrade,
+  /// The requests are replied without the dependency, and the work is queued to be
+  /// done once it is back, like a translation being cached for the next request.
+  Queue,
+} // end enum DegradationPolicy
+
+impl std::fmt::Display for DegradationPolicy {
+  fn fmt(
+    &self,
+    f: &mut std::fmt::Formatter<'_>,
+  ) -> std::fmt::Result {
+    match self {
+      DegradationPolicy::Fail => write!(f, "fail"),
+      DegradationPolicy::Degrade => write!(f, "degrade"),
+      DegradationPolicy::Queue => write!(f, "queue"),
+    }
+  }
+}
+
+/// Represents a dependency of the application that may be down.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dependency {
+  /// Read replica of the database.
+  Replica,
+  /// Translation service of the questions.
+  Translator,
+} // end enum Dependency
+
+impl Dependency {
+  /// Gets the name of the dependency, as given in `DEGRADATION_POLICIES`.
+  pub fn name(&self) -> &'static str {
+    match self {
+      Dependency::Replica => "replica",
+      Dependency::Translator => "translator",
+    }
+  } // end fn name()
+
+  /// Gets the policies the dependency supports. Reads from the replica cannot be done
+  /// later, so they cannot be queued.
+  fn policies(&self) -> &'static [DegradationPolicy] {
+    match self {
+      Dependency::Replica => &[DegradationPolicy::Fail, DegradationPolicy::Degrade],
+      Dependency::Translator => &[
+        DegradationPolicy::Fail,
+        DegradationPolicy::Degrade,
+        DegradationPolicy::Queue,
+      ],
+    }
+  } // end fn policies()
+
+  /// Logs that a request goes on without the dependency, and why.
+  ///
+  /// # Arguments
+  ///
+  /// * `policy`: Policy applied.
+  /// * `error`: Problem with the dependency.
+  pub fn log_degraded(
+    &self,
+    policy: DegradationPolicy,
+    error: impl std::fmt::Display,
+  ) {
+    tracing::event!(
+      tracing::Level::WARN,
+      "The {} is down, the request goes on without it ({} policy). {}",
+      self.name(),
+      policy,
+      error
+    );
+  } // end fn log_degraded()
+}
+
+/// Represents the policy applied to each dependency when it is down, so every route
+/// needing it behaves the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DegradationPolicies {
+  /// Policy of the read replica. The reads go to the primary database by default.
+  pub replica: DegradationPolicy,
+  /// Policy of the translation service. The translations fail by default.
+  pub translator: DegradationPolicy,
+} // end struct DegradationPolicies
+
+impl Default for DegradationPolicies {
+  fn default() -> Self {
+    DegradationPolicies {
+      replica: DegradationPolicy::Degrade,
+      translator: DegradationPolicy::Fail,
+    }
+  }
+}
+
+impl DegradationPolicies {
+  /// Gets the policies from the `DEGRADATION_POLICIES` environment variable, or the
+  /// default ones when it is not set or not valid.
+  ///
+  /// `DEGRADATION_POLICIES` holds comma separated policies like
+  /// `replica=fail,translator=queue`. The dependencies not given keep their default.
+  pub fn from_env() -> Self {
+    std::env::var("DEGRADATION_POLICIES")
+      .ok()
+      .and_then(|spec| Self::parse(&spec).ok())
+      .unwrap_or_default()
+  } // end fn from_env()
+
+  /// Gets the policies of the given specification.
+  ///
+  /// # Arguments
+  ///
+  /// * `spec`: Comma separated policies, like `replica=fail,translator=queue`.
+  pub fn parse(spec: &str) -> Result<Self, String> {
+    let mut policies = DegradationPolicies::default();
+
+    for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+      let (name, policy) = entry
+        .split_once('=')
+        .ok_or_else(|| format!("'{}' is not like dependency=policy", entry))?;
+      let dependency = match name.trim() {
+        "replica" => Dependency::Replica,
+        "translator" => Dependency::Translator,
+        name => {
+          return Err(format!(
+            "dependency must be one of replica or translator, found '{}'",
+            name
+          ))
+        }
+      };
+      let policy = match policy.trim() {
+        "fail" => DegradationPolicy::Fail,
+        "degrade" => DegradationPolicy::Degrade,
+        "queue" => DegradationPolicy::Queue,
+        policy => {
+          return Err(format!(
+            "policy must be one of fail, degrade or queue, found '{}'",
+            policy
+          ))
+        }
+      };
+      if !dependency.policies().contains(&policy) {
+        return Err(format!(
+          "the {} does not support the {} policy",
+          dependency.name(),
+          policy
+        ));
+      }
+
+      match dependency {
+        Dependency::Replica => policies.replica = policy,
+        Dependency::Translator => policies.translator = policy,
+      }
+    }
+
+    Ok(policies)
+  } // end fn parse()
+}
@@ -0,0 +1,89 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use handle_errors::errors::QError;
+
+use crate::types::survey::{Survey, SurveyId, SurveyQuestionResponse};
+
+/// Represents the responses saved so far by a respondent, to resume a survey later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewDraft {
+  /// Responses to some of the questions of the survey.
+  pub responses: Vec<SurveyQuestionResponse>,
+  /// Single-use invitation token, for invitation-only surveys. It is checked, but only
+  /// used up when the responses are submitted.
+  pub token: Option<String>,
+} // end struct NewDraft
+
+impl NewDraft {
+  /// Checks that the saved responses are valid for the given survey. Unlike a
+  /// submission, a draft may leave questions without a response.
+  ///
+  /// # Arguments
+  ///
+  /// * `survey`: Survey the draft is saved for.
+  pub fn validate(
+    &self,
+    survey: &Survey,
+  ) -> Result<(), QError> {
+    let errors = survey.response_errors(&self.responses);
+
+    match errors.is_empty() {
+      true => Ok(()),
+      false => Err(QError::InvalidResponses(errors)),
+    }
+  } // end fn validate()
+}
+
+/// Represents a draft of the responses to a survey.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Draft {
+  /// Secret key to resume the draft. It is only known by the respondent.
+  pub key: String,
+  /// Unique identifier (ID) of the survey.
+  pub survey_id: SurveyId,
+  /// Responses saved so far.
+  pub responses: Vec<SurveyQuestionResponse>,
+  /// Date and time the draft was last saved.
+  pub updated_on: NaiveDateTime,
+  /// Date and time the draft expires, unless it is saved again before.
+  pub expires_on: NaiveDateTime,
+} // end struct Draft
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+  use crate::types::testing::{choices, question, survey};
+
+  /// Builds a draft with the given responses.
+  ///
+  /// # Arguments
+  ///
+  /// * `responses`: Responses saved so far.
+  fn draft(responses: Vec<SurveyQuestionResponse>) -> NewDraft {
+    NewDraft {
+      responses,
+      token: None,
+    }
+  }
+
+  #[test]
+  fn saves_a_draft_without_the_required_responses() {
+    let options = json!({ "kind": "single_choice", "options": ["a", "b"] });
+    let survey = survey(vec![question(1, options.clone()), question(2, options)]);
+
+    assert!(draft(Vec::new()).validate(&survey).is_ok());
+    assert!(draft(vec![choices(1, vec![2])]).validate(&survey).is_ok());
+  }
+
+  #[test]
+  fn rejects_a_draft_with_invalid_responses() {
+    let options = json!({ "kind": "single_choice", "options": ["a", "b"] });
+    let survey = survey(vec![question(1, options)]);
+
+    let result = draft(vec![choices(1, vec![3])]).validate(&survey);
+    assert!(matches!(result, Err(QError::InvalidResponses(errors)) if errors.len() == 1));
+  }
+}
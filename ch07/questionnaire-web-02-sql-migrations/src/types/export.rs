@@ -0,0 +1,319 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use handle_errors::errors::QError;
+
+use crate::types::answer::AnswerId;
+use crate::types::question::{QuestionId, QuestionKind, QuestionStatus};
+
+/// Media type for CSV bodies.
+pub const CSV_MEDIA_TYPE: &str = "text/csv; charset=utf-8";
+
+/// Names of the columns of a CSV export, in order.
+pub const CSV_HEADER: &str = "question_id,title,content,tags,status,kind,\
+question_created_on,question_deleted_at,answer_id,answer_content,answer_created_on\r\n";
+
+/// Represents the format of an export of the data store.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+  /// JSON array with one object per row, the default format.
+  #[default]
+  Json,
+  /// CSV text with a header and one record per row.
+  Csv,
+} // end enum ExportFormat
+
+impl ExportFormat {
+  /// Gets the file name suggested to save an export in this format.
+  pub fn file_name(&self) -> &'static str {
+    match self {
+      ExportFormat::Json => "questionnaire-export.json",
+      ExportFormat::Csv => "questionnaire-export.csv",
+    }
+  } // end fn file_name()
+}
+
+impl std::str::FromStr for ExportFormat {
+  type Err = QError;
+
+  fn from_str(format: &str) -> Result<Self, Self::Err> {
+    match format {
+      "json" => Ok(ExportFormat::Json),
+      "csv" => Ok(ExportFormat::Csv),
+      _ => Err(QError::InvalidParameter(format!(
+        "format must be json or csv, found '{}'",
+        format
+      ))),
+    }
+  }
+}
+
+/// Represents a question joined with one of its answers, or with no answer when it has
+/// none, as exported from the data store.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ExportRow {
+  /// Unique identifier (ID) of the question.
+  pub question_id: QuestionId,
+  /// Title of the question.
+  pub title: String,
+  /// Text contents of the question.
+  pub content: String,
+  /// Tag names of the question, if any.
+  pub tags: Option<Vec<String>>,
+  /// Stage of the lifecycle of the question.
+  pub status: String,
+  /// Kind of answers the question takes.
+  pub kind: String,
+  /// Date and time the question was created.
+  pub question_created_on: NaiveDateTime,
+  /// Date and time the question was deleted, if it was.
+  pub question_deleted_at: Option<NaiveDateTime>,
+  /// Unique identifier (ID) of the answer, if any.
+  pub answer_id: Option<AnswerId>,
+  /// Text contents of the answer, if any.
+  pub answer_content: Option<String>,
+  /// Date and time the answer was created, if any.
+  pub answer_created_on: Option<NaiveDateTime>,
+} // end struct ExportRow
+
+impl ExportRow {
+  /// Writes this row as a CSV record, in the order of the columns of `CSV_HEADER`.
+  ///
+  /// # Arguments
+  ///
+  /// * `buffer`: Buffer the record is appended to.
+  pub fn write_csv(
+    &self,
+    buffer: &mut Vec<u8>,
+  ) {
+    let fields = [
+      self.question_id.value().to_string(),
+      self.title.clone(),
+      self.content.clone(),
+      self.tags.as_deref().unwrap_or_default().join(","),
+      self.status.clone(),
+      self.kind.clone(),
+      self.question_created_on.to_string(),
+      self.question_deleted_at.map(|date| date.to_string()).unwrap_or_default(),
+      self.answer_id.map(|id| id.value().to_string()).unwrap_or_default(),
+      self.answer_content.clone().unwrap_or_default(),
+      self.answer_created_on.map(|date| date.to_string()).unwrap_or_default(),
+    ];
+
+    for (index, field) in fields.iter().enumerate() {
+      if index > 0 {
+        buffer.push(b',');
+      }
+      write_csv_field(buffer, field);
+    }
+    buffer.extend_from_slice(b"\r\n");
+  } // end fn write_csv()
+}
+
+/// Checks that the rows of a JSON export can be imported: each question has the same
+/// data in all its rows, valid status and kind, and a title, and no answer appears in
+/// more than one row.
+///
+/// # Arguments
+///
+/// * `rows`: Rows of the export to import.
+pub fn validate_import(rows: &[ExportRow]) -> Result<(), QError> {
+  let mut questions: HashMap<i32, &ExportRow> = HashMap::new();
+  let mut answer_ids: Vec<i32> = Vec::new();
+
+  for (index, row) in rows.iter().enumerate() {
+    let problem = |message: String| {
+      QError::InvalidParameter(format!("row {}: {}", index + 1, message))
+    };
+
+    row.status.parse::<QuestionStatus>().map_err(|err| problem(err.to_string()))?;
+    row.kind.parse::<QuestionKind>().map_err(|err| problem(err.to_string()))?;
+    if row.title.trim().is_empty() {
+      return Err(problem("the title of the question is empty".to_string()));
+    }
+
+    let question_id = row.question_id.value();
+    match questions.get(&question_id) {
+      Some(first)
+        if (&first.title, &first.content, &first.tags, &first.status, &first.kind)
+          != (&row.title, &row.content, &row.tags, &row.status, &row.kind)
+          || (first.question_created_on, first.question_deleted_at)
+            != (row.question_created_on, row.question_deleted_at) =>
+      {
+        return Err(problem(format!(
+          "question {} has different data than in an earlier row",
+          question_id
+        )));
+      }
+      Some(_) => {}
+      None => {
+        questions.insert(question_id, row);
+      }
+    }
+
+    match (row.answer_id, &row.answer_content, row.answer_created_on) {
+      (None, None, None) => {}
+      (Some(answer_id), Some(_), Some(_)) => {
+        if answer_ids.contains(&answer_id.value()) {
+          return Err(problem(format!(
+            "answer {} is in more than one row",
+            answer_id.value()
+          )));
+        }
+        answer_ids.push(answer_id.value());
+      }
+      _ => {
+        return Err(problem(
+          "answer_id, answer_content and answer_created_on must be all given or all null"
+            .to_string(),
+        ));
+      }
+    }
+  }
+
+  Ok(())
+} // end fn validate_import()
+
+/// Represents what an import changed in the data store, or would change in a dry run.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct ImportReport {
+  /// Whether the changes were only checked and then discarded.
+  pub dry_run: bool,
+  /// Amount of questions added.
+  pub questions_created: u64,
+  /// Amount of existing questions changed.
+  pub questions_updated: u64,
+  /// Amount of existing questions that already had the same data.
+  pub questions_unchanged: u64,
+  /// Amount of answers added.
+  pub answers_created: u64,
+  /// Amount of existing answers changed.
+  pub answers_updated: u64,
+  /// Amount of existing answers that already had the same data.
+  pub answers_unchanged: u64,
+} // end struct ImportReport
+
+/// Writes a CSV field, quoting it when it has commas, quotes or line breaks.
+///
+/// # Arguments
+///
+/// * `buffer`: Buffer the field is appended to.
+/// * `field`: Text of the field.
+fn write_csv_field(
+  buffer: &mut Vec<u8>,
+  field: &str,
+) {
+  if !field.contains([',', '"', '\r', '\n']) {
+    buffer.extend_from_slice(field.as_bytes());
+    return;
+  }
+
+  buffer.push(b'"');
+  buffer.extend_from_slice(field.replace('"', "\"\"").as_bytes());
+  buffer.push(b'"');
+} // end fn write_csv_field()
+
+#[cfg(test)]
+mod tests {
+  use chrono::NaiveDate;
+
+  use super::*;
+
+  /// Builds a row of an open text question, joined with an answer when given.
+  ///
+  /// # Arguments
+  ///
+  /// * `question_id`: Unique identifier (ID) of the question.
+  /// * `answer_id`: Unique identifier (ID) of the answer, if any.
+  fn row(
+    question_id: i32,
+    answer_id: Option<i32>,
+  ) -> ExportRow {
+    let created_on = NaiveDate::from_ymd_opt(2023, 4, 1)
+      .and_then(|date| date.and_hms_opt(10, 0, 0))
+      .expect("valid date");
+    ExportRow {
+      question_id: QuestionId::new(question_id),
+      title: format!("Question {}", question_id),
+      content: "Content".to_string(),
+      tags: None,
+      status: "open".to_string(),
+      kind: "text".to_string(),
+      question_created_on: created_on,
+      question_deleted_at: None,
+      answer_id: answer_id.map(AnswerId::new),
+      answer_content: answer_id.map(|id| format!("Answer {}", id)),
+      answer_created_on: answer_id.map(|_| created_on),
+    }
+  }
+
+  #[test]
+  fn accepts_a_question_repeated_with_each_answer() {
+    let rows = [row(1, Some(1)), row(1, Some(2)), row(2, None)];
+    assert!(validate_import(&rows).is_ok());
+  }
+
+  #[test]
+  fn rejects_a_question_with_different_data_in_a_later_row() {
+    let mut changed = row(1, Some(2));
+    changed.title = "Other title".to_string();
+
+    let err = validate_import(&[row(1, Some(1)), changed]).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Invalid parameter: row 2: question 1 has different data than in an earlier row"
+    );
+  }
+
+  #[test]
+  fn rejects_an_answer_in_more_than_one_row() {
+    let err = validate_import(&[row(1, Some(1)), row(2, Some(1))]).unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "Invalid parameter: row 2: answer 1 is in more than one row"
+    );
+  }
+
+  #[test]
+  fn rejects_an_answer_with_only_some_of_its_fields() {
+    let mut partial = row(1, Some(1));
+    partial.answer_content = None;
+    assert!(validate_import(&[partial]).is_err());
+  }
+
+  #[test]
+  fn rejects_an_unknown_status_or_an_empty_title() {
+    let mut unknown = row(1, None);
+    unknown.status = "draft".to_string();
+    assert!(validate_import(&[unknown]).is_err());
+
+    let mut untitled = row(1, None);
+    untitled.title = "  ".to_string();
+    assert!(validate_import(&[untitled]).is_err());
+  }
+
+  #[test]
+  fn quotes_the_csv_fields_with_commas_quotes_or_line_breaks() {
+    let mut row = row(1, None);
+    row.title = "Say \"hi\"".to_string();
+    row.content = "Line 1\nLine 2".to_string();
+    row.tags = Some(vec!["rust".to_string(), "web".to_string()]);
+
+    let mut buffer = Vec::new();
+    row.write_csv(&mut buffer);
+    assert_eq!(
+      String::from_utf8(buffer).unwrap(),
+      "1,\"Say \"\"hi\"\"\",\"Line 1\nLine 2\",\"rust,web\",open,text,\
+2023-04-01 10:00:00,,,,\r\n"
+    );
+  }
+
+  #[test]
+  fn parses_only_the_known_formats() {
+    assert_eq!("csv".parse::<ExportFormat>().unwrap(), ExportFormat::Csv);
+    assert_eq!("json".parse::<ExportFormat>().unwrap(), ExportFormat::Json);
+    assert!("xml".parse::<ExportFormat>().is_err());
+  }
+}
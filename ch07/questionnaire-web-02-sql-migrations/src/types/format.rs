@@ -0,0 +1,96 @@
+use serde::Serialize;
+use warp::http::header::{HeaderValue, CONTENT_TYPE};
+use warp::http::{Response, StatusCode};
+use warp::hyper::Body;
+
+/// Media type for JSON bodies.
+pub const JSON_MEDIA_TYPE: &str = "application/json";
+
+/// Media type for MessagePack bodies.
+pub const MSGPACK_MEDIA_TYPE: &str = "application/msgpack";
+
+/// Represents the format used to serialize the body of a response.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+  /// JSON text, the default format.
+  #[default]
+  Json,
+  /// MessagePack binary encoding.
+  MessagePack,
+} // end enum Format
+
+impl Format {
+  /// Gets the response format requested by the given `Accept` header.
+  ///
+  /// The first supported media type listed in the header wins. JSON is used when the
+  /// header is missing or does not list any supported media type.
+  ///
+  /// # Arguments
+  ///
+  /// * `accept`: Value of the `Accept` header of the request, if any.
+  pub fn from_accept(accept: Option<&str>) -> Self {
+    let accept = match accept {
+      Some(value) => value,
+      None => return Format::Json,
+    };
+
+    for media_range in accept.split(',') {
+      // Ignore parameters such as the quality factor.
+      let media_type = media_range.split(';').next().unwrap_or_default().trim();
+
+      if media_type.eq_ignore_ascii_case(MSGPACK_MEDIA_TYPE)
+        || media_type.eq_ignore_ascii_case("application/x-msgpack")
+      {
+        return Format::MessagePack;
+      }
+      if media_type.eq_ignore_ascii_case(JSON_MEDIA_TYPE) {
+        return Format::Json;
+      }
+    }
+
+    Format::Json
+  } // end fn from_accept()
+
+  /// Gets the media type for the `Content-Type` header of this format.
+  pub fn media_type(&self) -> &'static str {
+    match self {
+      Format::Json => JSON_MEDIA_TYPE,
+      Format::MessagePack => MSGPACK_MEDIA_TYPE,
+    }
+  } // end fn media_type()
+
+  /// Creates a reply with the given value serialized in this format.
+  ///
+  /// # Arguments
+  ///
+  /// * `value`: Value to be serialized as the body of the reply.
+  /// * `status`: HTTP status code of the reply.
+  pub fn reply<T: Serialize>(
+    &self,
+    value: &T,
+    status: StatusCode,
+  ) -> Response<Body> {
+    let body = match self {
+      Format::Json => serde_json::to_vec(value).map_err(|err| err.to_string()),
+      // Serialize structs as maps so the field names are kept, like in JSON.
+      Format::MessagePack => rmp_serde::to_vec_named(value).map_err(|err| err.to_string()),
+    };
+
+    match body {
+      Ok(body) => {
+        let mut response = Response::new(Body::from(body));
+        *response.status_mut() = status;
+        response
+          .headers_mut()
+          .insert(CONTENT_TYPE, HeaderValue::from_static(self.media_type()));
+        response
+      }
+      Err(err) => {
+        tracing::event!(tracing::Level::ERROR, "Cannot serialize the reply. {}", err);
+        let mut response = Response::new(Body::empty());
+        *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+        response
+      }
+    }
+  } // end fn reply()
+}
@@ -0,0 +1,47 @@
+use serde::{Deserialize, Serialize};
+
+/// Represents the usage of a pool of database connections.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PoolStatus {
+  /// Amount of connections open, idle or in use.
+  pub size: u32,
+  /// Amount of open connections waiting to be used.
+  pub idle: usize,
+  /// Amount of connections in use.
+  pub active: usize,
+} // end struct PoolStatus
+
+/// Represents the status of a background task, like the purge of deleted questions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskStatus {
+  /// Name of the task.
+  pub name: String,
+  /// Whether the task finished a run recently enough to be considered running.
+  pub running: bool,
+  /// Seconds between runs of the task.
+  pub interval_secs: u64,
+  /// Seconds since the task last finished a run, if it did.
+  pub last_run_secs_ago: Option<f64>,
+  /// Description of the problem of the last run, with the reference of its details in
+  /// the logs, if it failed.
+  pub last_error: Option<String>,
+} // end struct TaskStatus
+
+/// Represents the health of the application, of its database and of its background
+/// tasks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Health {
+  /// Whether the database replied to a query in time.
+  pub database_up: bool,
+  /// Milliseconds the database took to reply, when it replied in time.
+  pub latency_ms: Option<f64>,
+  /// Description of the problem with the database, with the reference of its details
+  /// in the logs, if any.
+  pub error: Option<String>,
+  /// Usage of the pool of connections to the primary database.
+  pub pool: PoolStatus,
+  /// Usage of the pool of connections to the read replica, if any.
+  pub replica_pool: Option<PoolStatus>,
+  /// Status of each background task.
+  pub tasks: Vec<TaskStatus>,
+} // end struct Health
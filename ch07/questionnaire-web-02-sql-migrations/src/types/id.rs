@@ -0,0 +1,191 @@
+use std::marker::PhantomData;
+use std::num::IntErrorKind;
+
+use handle_errors::errors::{IdParseError, QError};
+
+use serde::{Deserialize, Serialize};
+use sqlx::error::BoxDynError;
+use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef};
+use sqlx::{Decode, Encode, Postgres, Type};
+use warp::{Filter, Rejection};
+
+/// Represents the unique identifier (ID) of an entity of type `T`.
+///
+/// The type parameter only tags the ID, so an ID of a question cannot be given where
+/// an ID of an answer is expected. It is serialized and stored as a plain integer.
+#[derive(Serialize, Deserialize)]
+#[serde(transparent, bound = "")]
+pub struct Id<T> {
+  /// Integer value of the ID.
+  value: i32,
+  /// Marks the type of entity identified, without owning any value of it.
+  #[serde(skip)]
+  entity: PhantomData<fn() -> T>,
+} // end struct Id
+
+impl<T> Id<T> {
+  /// Creates an ID with the given integer value.
+  ///
+  /// # Arguments
+  ///
+  /// * `value`: Integer value of the ID.
+  pub fn new(value: i32) -> Self {
+    Id {
+      value,
+      entity: PhantomData,
+    }
+  }
+
+  /// Gets the integer value of this ID.
+  pub fn value(&self) -> i32 {
+    self.value
+  }
+}
+
+/// Creates a filter that extracts the next path segment as an ID of type `T`.
+///
+/// Unlike `warp::path::param()`, a segment that is not a valid ID is rejected with an
+/// `QError::InvalidId` error, so it is replied as a `400 Bad Request` instead of a
+/// missing route.
+pub fn path_param<T: 'static>(
+) -> impl Filter<Extract = (Id<T>,), Error = Rejection> + Copy {
+  warp::path::param::<String>().and_then(|segment: String| async move {
+    segment
+      .parse::<Id<T>>()
+      .map_err(|err| warp::reject::custom(QError::from(err)))
+  })
+}
+
+// The traits are implemented by hand because deriving them would require `T` to
+// implement them too.
+
+impl<T> Clone for Id<T> {
+  fn clone(&self) -> Self {
+    *self
+  }
+}
+
+impl<T> Copy for Id<T> {}
+
+impl<T> PartialEq for Id<T> {
+  fn eq(
+    &self,
+    other: &Self,
+  ) -> bool {
+    self.value == other.value
+  }
+}
+
+impl<T> Eq for Id<T> {}
+
+impl<T> std::hash::Hash for Id<T> {
+  fn hash<H: std::hash::Hasher>(
+    &self,
+    state: &mut H,
+  ) {
+    self.value.hash(state);
+  }
+}
+
+impl<T> std::fmt::Debug for Id<T> {
+  fn fmt(
+    &self,
+    f: &mut std::fmt::Formatter<'_>,
+  ) -> std::fmt::Result {
+    f.debug_tuple("Id").field(&self.value).finish()
+  }
+}
+
+impl<T> std::fmt::Display for Id<T> {
+  fn fmt(
+    &self,
+    f: &mut std::fmt::Formatter<'_>,
+  ) -> std::fmt::Result {
+    write!(f, "id: {}", self.value)
+  }
+}
+
+impl<T> std::str::FromStr for Id<T> {
+  type Err = IdParseError;
+
+  fn from_str(id: &str) -> Result<Self, Self::Err> {
+    if id.is_empty() {
+      return Err(IdParseError::Empty);
+    }
+
+    match id.parse::<i32>() {
+      Ok(value) if value < 0 => Err(IdParseError::Negative(value)),
+      Ok(value) => Ok(Id::new(value)),
+      Err(err) => match err.kind() {
+        IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => {
+          Err(IdParseError::Overflow(id.to_string()))
+        }
+        _ => Err(IdParseError::NotANumber(id.to_string())),
+      },
+    }
+  }
+}
+
+impl<T> Type<Postgres> for Id<T> {
+  fn type_info() -> PgTypeInfo {
+    <i32 as Type<Postgres>>::type_info()
+  }
+
+  fn compatible(ty: &PgTypeInfo) -> bool {
+    <i32 as Type<Postgres>>::compatible(ty)
+  }
+}
+
+impl<'q, T> Encode<'q, Postgres> for Id<T> {
+  fn encode_by_ref(
+    &self,
+    buf: &mut PgArgumentBuffer,
+  ) -> sqlx::encode::IsNull {
+    <i32 as Encode<Postgres>>::encode_by_ref(&self.value, buf)
+  }
+}
+
+impl<'r, T> Decode<'r, Postgres> for Id<T> {
+  fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+    Ok(Id::new(<i32 as Decode<Postgres>>::decode(value)?))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Entity tagging the IDs of the tests.
+  struct Entity;
+
+  #[test]
+  fn parses_an_id() {
+    assert_eq!("42".parse::<Id<Entity>>(), Ok(Id::new(42)));
+  }
+
+  #[test]
+  fn rejects_an_empty_id() {
+    assert_eq!("".parse::<Id<Entity>>(), Err(IdParseError::Empty));
+  }
+
+  #[test]
+  fn rejects_a_non_numeric_id() {
+    assert_eq!(
+      "abc".parse::<Id<Entity>>(),
+      Err(IdParseError::NotANumber("abc".to_string()))
+    );
+  }
+
+  #[test]
+  fn rejects_a_negative_id() {
+    assert_eq!("-3".parse::<Id<Entity>>(), Err(IdParseError::Negative(-3)));
+  }
+
+  #[test]
+  fn rejects_an_overflowing_id() {
+    assert_eq!(
+      "99999999999".parse::<Id<Entity>>(),
+      Err(IdParseError::Overflow("99999999999".to_string()))
+    );
+  }
+}
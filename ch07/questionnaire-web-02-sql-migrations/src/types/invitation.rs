@@ -0,0 +1,72 @@
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use handle_errors::errors::QError;
+
+use crate::types::survey::SurveyId;
+
+/// Amount of random bytes of an invitation token.
+const TOKEN_BYTES: usize = 32;
+
+/// Maximum amount of invitations generated by a single request.
+const MAX_INVITATIONS: i32 = 1000;
+
+/// Represents a request to generate invitations to respond to a survey.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewInvitations {
+  /// Amount of invitations to generate.
+  pub count: i32,
+} // end struct NewInvitations
+
+impl NewInvitations {
+  /// Checks that the amount of invitations is within the allowed range.
+  pub fn validate(&self) -> Result<(), QError> {
+    if !(1..=MAX_INVITATIONS).contains(&self.count) {
+      return Err(QError::InvalidParameter(format!(
+        "count must be between 1 and {}, found {}",
+        MAX_INVITATIONS, self.count
+      )));
+    }
+
+    Ok(())
+  } // end fn validate()
+}
+
+/// Represents a set of invitations generated for a survey.
+///
+/// The tokens are only known when they are generated, so they must be handed out to the
+/// respondents right away.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Invitations {
+  /// Unique identifier (ID) of the survey.
+  pub survey_id: SurveyId,
+  /// Single-use tokens to respond to the survey.
+  pub tokens: Vec<String>,
+} // end struct Invitations
+
+/// Generates a new random invitation token, encoded as hexadecimal text.
+pub fn generate_token() -> String {
+  let mut bytes = [0u8; TOKEN_BYTES];
+  rand::thread_rng().fill_bytes(&mut bytes);
+
+  to_hex(&bytes)
+} // end fn generate_token()
+
+/// Gets the hash of the given invitation token, as stored in the database.
+///
+/// # Arguments
+///
+/// * `token`: Invitation token to hash.
+pub fn hash_token(token: &str) -> String {
+  to_hex(&Sha256::digest(token.as_bytes()))
+} // end fn hash_token()
+
+/// Encodes the given bytes as lowercase hexadecimal text.
+///
+/// # Arguments
+///
+/// * `bytes`: Bytes to encode.
+fn to_hex(bytes: &[u8]) -> String {
+  bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+} // end fn to_hex()
@@ -0,0 +1,193 @@
+use std::time::Duration;
+
+/// Largest body, in bytes, of a request to a route without an override, if
+/// `REQUEST_BODY_MAX_BYTES` is not set.
+pub const DEFAULT_BODY_MAX_BYTES: u64 = 64 * 1024;
+
+/// Seconds a request to a route without an override may take to be replied, if
+/// `REQUEST_TIMEOUT_SECS` is not set.
+pub const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Path of the route to import an export of the data store.
+pub const IMPORT_PATH: &str = "/admin/import";
+
+/// Largest body, in bytes, of a request to import an export of the data store, unless
+/// `ROUTE_LIMITS` overrides it.
+const IMPORT_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Seconds a request to import an export of the data store may take to be replied,
+/// unless `ROUTE_LIMITS` overrides it.
+const IMPORT_TIMEOUT_SECS: u64 = 5 * 60;
+
+/// Represents the limits of the requests to a route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteLimit {
+  /// Largest body, in bytes, of a request.
+  pub max_bytes: u64,
+  /// Time a request may take until the response starts, so streamed bodies may take
+  /// longer to be sent.
+  pub timeout: Duration,
+} // end struct RouteLimit
+
+/// Represents the limits of the requests to the routes, with overrides for the routes
+/// that need larger bodies or more time, like the import of an export.
+#[derive(Debug, Clone)]
+pub struct RouteLimits {
+  /// Limits of the routes without an override.
+  pub default: RouteLimit,
+  /// Path patterns of the routes with their limits, in order of precedence. A `*`
+  /// segment of a pattern matches any segment, like `/surveys/*/responses`.
+  pub overrides: Vec<(String, RouteLimit)>,
+} // end struct RouteLimits
+
+impl Default for RouteLimits {
+  fn default() -> Self {
+    RouteLimits {
+      default: RouteLimit {
+        max_bytes: DEFAULT_BODY_MAX_BYTES,
+        timeout: Duration::from_secs(DEFAULT_REQUEST_TIMEOUT_SECS),
+      },
+      overrides: vec![(
+        IMPORT_PATH.to_string(),
+        RouteLimit {
+          max_bytes: IMPORT_MAX_BYTES,
+          timeout: Duration::from_secs(IMPORT_TIMEOUT_SECS),
+        },
+      )],
+    }
+  }
+}
+
+impl RouteLimits {
+  /// Gets the limits from the `REQUEST_BODY_MAX_BYTES`, `REQUEST_TIMEOUT_SECS` and
+  /// `ROUTE_LIMITS` environment variables, or the default ones when they are not set or
+  /// not valid.
+  ///
+  /// `ROUTE_LIMITS` holds comma separated overrides like
+  /// `/admin/import=52428800:300,/surveys/*/responses=1048576:60`, with the largest
+  /// body in bytes and the timeout in seconds of each path pattern. They take
+  /// precedence over the default override of the import route.
+  pub fn from_env() -> Self {
+    let read = |name: &str| {
+      std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse::<u64>().ok())
+        .filter(|value| *value >= 1)
+    };
+    let mut limits = RouteLimits::default();
+    if let Some(max_bytes) = read("REQUEST_BODY_MAX_BYTES") {
+      limits.default.max_bytes = max_bytes;
+    }
+    if let Some(secs) = read("REQUEST_TIMEOUT_SECS") {
+      limits.default.timeout = Duration::from_secs(secs);
+    }
+
+    let overrides = std::env::var("ROUTE_LIMITS")
+      .ok()
+      .and_then(|spec| Self::parse_overrides(&spec).ok())
+      .unwrap_or_default();
+    limits.overrides.retain(|(pattern, _)| {
+      !overrides.iter().any(|(other, _)| other == pattern)
+    });
+    limits.overrides.splice(0..0, overrides);
+
+    limits
+  } // end fn from_env()
+
+  /// Gets the path patterns and limits of the overrides of the given specification.
+  ///
+  /// # Arguments
+  ///
+  /// * `spec`: Comma separated overrides, like `/admin/import=52428800:300`.
+  pub fn parse_overrides(spec: &str) -> Result<Vec<(String, RouteLimit)>, String> {
+    spec
+      .split(',')
+      .map(str::trim)
+      .filter(|entry| !entry.is_empty())
+      .map(|entry| {
+        let invalid = || format!("'{}' is not like /path=bytes:seconds", entry);
+        let (pattern, limit) = entry.split_once('=').ok_or_else(invalid)?;
+        let (max_bytes, secs) = limit.split_once(':').ok_or_else(invalid)?;
+        if !pattern.starts_with('/') {
+          return Err(invalid());
+        }
+
+        Ok((
+          pattern.trim_end_matches('/').to_string(),
+          RouteLimit {
+            max_bytes: max_bytes.trim().parse().map_err(|_| invalid())?,
+            timeout: Duration::from_secs(secs.trim().parse().map_err(|_| invalid())?),
+          },
+        ))
+      })
+      .collect()
+  } // end fn parse_overrides()
+
+  /// Gets the limits of the route of the given path, from the first override whose
+  /// pattern matches it, or the default ones.
+  ///
+  /// # Arguments
+  ///
+  /// * `path`: Path of a request or route, like `/surveys/1/responses`.
+  pub fn for_route(
+    &self,
+    path: &str,
+  ) -> RouteLimit {
+    let segments: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+
+    self
+      .overrides
+      .iter()
+      .find(|(pattern, _)| {
+        let pattern: Vec<&str> = pattern.split('/').collect();
+        pattern.len() == segments.len()
+          && pattern
+            .iter()
+            .zip(&segments)
+            .all(|(expected, segment)| *expected == "*" || expected == segment)
+      })
+      .map_or(self.default, |(_, limit)| *limit)
+  } // end fn for_route()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parses_the_overrides_without_a_trailing_slash() {
+    let overrides =
+      RouteLimits::parse_overrides(" /admin/import/=1024:60, /surveys/*/responses=1:2,")
+        .unwrap();
+    assert_eq!(overrides.len(), 2);
+    assert_eq!(overrides[0].0, "/admin/import");
+    assert_eq!(
+      overrides[1].1,
+      RouteLimit {
+        max_bytes: 1,
+        timeout: Duration::from_secs(2),
+      }
+    );
+  }
+
+  #[test]
+  fn rejects_an_override_without_a_path_or_a_timeout() {
+    assert!(RouteLimits::parse_overrides("/admin/import=1024").is_err());
+    assert!(RouteLimits::parse_overrides("admin/import=1024:60").is_err());
+    assert!(RouteLimits::parse_overrides("/admin/import=big:60").is_err());
+  }
+
+  #[test]
+  fn gets_the_limits_of_the_first_matching_pattern() {
+    let mut limits = RouteLimits::default();
+    limits.overrides.extend(
+      RouteLimits::parse_overrides("/surveys/*/responses=1:2,/surveys/1/responses=3:4")
+        .unwrap(),
+    );
+
+    assert_eq!(limits.for_route("/admin/import/").max_bytes, IMPORT_MAX_BYTES);
+    assert_eq!(limits.for_route("/surveys/1/responses").max_bytes, 1);
+    assert_eq!(limits.for_route("/surveys/1").max_bytes, DEFAULT_BODY_MAX_BYTES);
+    assert_eq!(limits.for_route("/questions").max_bytes, DEFAULT_BODY_MAX_BYTES);
+  }
+}
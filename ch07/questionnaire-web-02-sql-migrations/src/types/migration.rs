@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+/// Represents the phase of a schema change made in expand and contract steps, so the
+/// builds before and after the change can run side by side during a deployment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationPhase {
+  /// The old and new columns are both written and the old one is read, so builds that
+  /// only know the old column can still run. Used while `SCHEMA_COMPAT_MODE` is on.
+  Expand,
+  /// Only the new column is written and read, so the old column can be dropped once
+  /// the builds that only know it are stopped.
+  Contract,
+} // end enum MigrationPhase
+
+impl MigrationPhase {
+  /// Gets the phase of the schema changes for the given compatibility mode.
+  ///
+  /// # Arguments
+  ///
+  /// * `compat_mode`: Whether the old columns are still written.
+  pub fn from_compat_mode(compat_mode: bool) -> Self {
+    match compat_mode {
+      true => MigrationPhase::Expand,
+      false => MigrationPhase::Contract,
+    }
+  } // end fn from_compat_mode()
+}
+
+/// Represents a column being replaced by another one in expand and contract steps.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ColumnTransition {
+  /// Name of the table of the columns.
+  pub table: String,
+  /// Name of the column being replaced.
+  pub old_column: String,
+  /// Name of the column replacing the old one.
+  pub new_column: String,
+  /// Phase of the transition.
+  pub phase: MigrationPhase,
+  /// Amount of rows without a value in the new column, written by builds that only
+  /// know the old one. They are filled in when the contract phase starts.
+  pub pending_rows: i64,
+} // end struct ColumnTransition
+
+/// Represents the state of the schema of the database and of its transitions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationStatus {
+  /// Version of the latest migration applied to the database, if any.
+  pub latest_migration: Option<i64>,
+  /// Whether the old columns of the transitions are still written.
+  pub compat_mode: bool,
+  /// Columns being replaced by other ones.
+  pub transitions: Vec<ColumnTransition>,
+} // end struct MigrationStatus
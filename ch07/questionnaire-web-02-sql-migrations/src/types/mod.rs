@@ -1,3 +1,94 @@
 pub mod answer;
+pub mod change;
+pub mod code;
+pub mod degradation;
+pub mod draft;
+pub mod export;
+pub mod format;
+pub mod health;
+pub mod id;
+pub mod invitation;
+pub mod limits;
+pub mod migration;
 pub mod pagination;
+pub mod query;
+pub mod quiz;
 pub mod question;
+pub mod recording;
+pub mod redirect;
+pub mod response;
+pub mod retention;
+pub mod revision;
+pub mod seed;
+pub mod sorting;
+pub mod suggestion;
+pub mod survey;
+pub mod tag;
+pub mod validation;
+
+/// Helpers shared by the tests of the types.
+#[cfg(test)]
+pub mod testing {
+  use crate::types::question::{Question, QuestionId};
+  use crate::types::response::NewResponse;
+  use crate::types::survey::{Survey, SurveyId, SurveyQuestionResponse};
+
+  /// Builds a question from the fields of its JSON body besides its title and content.
+  ///
+  /// # Arguments
+  ///
+  /// * `id`: Unique identifier (ID) of the question.
+  /// * `fields`: Other JSON fields of the question, like its kind and options.
+  pub fn question(
+    id: i32,
+    fields: serde_json::Value,
+  ) -> Question {
+    let mut body =
+      serde_json::json!({ "id": id, "title": "Title", "content": "Content" });
+    let fields = fields.as_object().expect("JSON object").clone();
+    body.as_object_mut().expect("JSON object").extend(fields);
+    serde_json::from_value(body).expect("valid question")
+  } // end fn question()
+
+  /// Builds an open survey with the given questions, all of them required.
+  ///
+  /// # Arguments
+  ///
+  /// * `questions`: Questions of the survey, in order.
+  pub fn survey(questions: Vec<Question>) -> Survey {
+    Survey {
+      id: SurveyId::new(1),
+      title: "Survey".to_string(),
+      opens_on: None,
+      closes_on: None,
+      max_submissions: None,
+      submission_count: 0,
+      closed_on: None,
+      invitation_only: false,
+      quiz: false,
+      embed_origins: Vec::new(),
+      questions,
+      optional_question_ids: Vec::new(),
+      rules: Vec::new(),
+    }
+  } // end fn survey()
+
+  /// Builds the response to a question of a survey with the given choices.
+  ///
+  /// # Arguments
+  ///
+  /// * `question_id`: Unique identifier (ID) of the question.
+  /// * `choices`: Positions of the chosen options.
+  pub fn choices(
+    question_id: i32,
+    choices: Vec<i32>,
+  ) -> SurveyQuestionResponse {
+    SurveyQuestionResponse {
+      question_id: QuestionId::new(question_id),
+      response: NewResponse {
+        choices: Some(choices),
+        rating: None,
+      },
+    }
+  } // end fn choices()
+}
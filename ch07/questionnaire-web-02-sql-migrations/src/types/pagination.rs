@@ -1,53 +1,235 @@
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
 
 use handle_errors::errors::QError;
 
 /// Represents the start and end index of a set of results.
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct Pagination {
   /// Start index of a set of results, i.e. offset.
   pub offset: i32,
   /// Amount of elements of the set of results. i.e. limit. End index of a set of results.
   pub limit: Option<i32>,
+  /// Identifier (ID) after which the set of results starts, for keyset pagination.
+  pub after: Option<i32>,
 } // end struct Pagination
 
-/// Gets a pagination object from the given set of parameters.
+/// Page size given to listings that do not set a limit, if `PAGE_SIZE_DEFAULT` is not
+/// set.
+pub const DEFAULT_PAGE_SIZE: i32 = 20;
+
+/// Largest page size a listing may set, if `PAGE_SIZE_MAX` is not set.
+pub const DEFAULT_MAX_PAGE_SIZE: i32 = 100;
+
+/// Represents the page sizes allowed for the listings, so a request cannot fetch a
+/// whole table at once.
+#[derive(Debug, Clone, Copy)]
+pub struct PageLimits {
+  /// Page size used when the `limit` parameter is not given.
+  pub default: i32,
+  /// Largest page size the `limit` parameter may set.
+  pub max: i32,
+} // end struct PageLimits
+
+impl Default for PageLimits {
+  fn default() -> Self {
+    PageLimits {
+      default: DEFAULT_PAGE_SIZE,
+      max: DEFAULT_MAX_PAGE_SIZE,
+    }
+  }
+}
+
+impl PageLimits {
+  /// Gets the page sizes from the `PAGE_SIZE_DEFAULT` and `PAGE_SIZE_MAX` environment
+  /// variables, or the default ones when they are not set. The default page size is
+  /// lowered to the largest one if it is larger.
+  pub fn from_env() -> Self {
+    let read = |name: &str, default: i32| {
+      std::env::var(name)
+        .ok()
+        .and_then(|size| size.parse::<i32>().ok())
+        .filter(|size| *size >= 1)
+        .unwrap_or(default)
+    };
+    let max = read("PAGE_SIZE_MAX", DEFAULT_MAX_PAGE_SIZE);
+
+    PageLimits {
+      default: read("PAGE_SIZE_DEFAULT", DEFAULT_PAGE_SIZE).min(max),
+      max,
+    }
+  } // end fn from_env()
+
+  /// Gets the page size of the given `limit` parameter, or the default one when it is
+  /// not given.
+  ///
+  /// # Arguments
+  ///
+  /// * `limit`: Value of the `limit` parameter, if any.
+  fn page_size(
+    &self,
+    limit: Option<i32>,
+  ) -> Result<i32, QError> {
+    match limit {
+      Some(limit) if (1..=self.max).contains(&limit) => Ok(limit),
+      Some(_) => Err(QError::InvalidPagination(1, self.max)),
+      None => Ok(self.default),
+    }
+  } // end fn page_size()
+}
+
+/// Gets a pagination object from the given `offset` and `limit` parameters.
+///
+/// The offset is 0 and the limit is the default page size when they are not given.
 ///
 /// # Arguments
 ///
-/// * `params`: Parameters to limit the set of results to retrieve.
+/// * `offset`: Value of the `offset` parameter, if any.
+/// * `limit`: Value of the `limit` parameter, if any.
+/// * `limits`: Page sizes allowed.
 ///
 /// # Example Usage
 ///
-/// ```rust  
-/// let mut query = HashMap::new();
-/// query.insert("offset".to_string(), "1".to_string());
-/// query.insert("limit").to_string(), "20".to_string());
-///
-/// let pagination = types::pagination::extract_pagination(query).unwrap;
+/// ```rust
+/// let pagination =
+///   types::pagination::extract_pagination(Some(1), Some(20), &PageLimits::default())
+///     .unwrap();
 /// assert_eq!(pagination.offset, 1);
-/// assert_eq!(pagination.limit, 20);
+/// assert_eq!(pagination.limit, Some(20));
 /// ```
-pub fn extract_pagination(params: HashMap<String, String>) -> Result<Pagination, QError> {
-  if params.contains_key("offset") && params.contains_key("limit") {
-    let offset_value: i32 = params
-      .get("offset")
-      .unwrap()
-      .parse::<i32>()
-      .map_err(QError::ParseError)?;
-    let limit_value: i32 = params
-      .get("limit")
-      .unwrap()
-      .parse::<i32>()
-      .map_err(QError::ParseError)?;
-
-    let pagination = Pagination {
-      offset: offset_value,
-      limit: Some(limit_value),
-    };
-
-    return Ok(pagination);
+pub fn extract_pagination(
+  offset: Option<i32>,
+  limit: Option<i32>,
+  limits: &PageLimits,
+) -> Result<Pagination, QError> {
+  let offset_value = offset.unwrap_or(0);
+  if offset_value < 0 {
+    return Err(QError::InvalidParameter(format!(
+      "offset must be at least 0, found {}",
+      offset_value
+    )));
   }
 
-  Err(QError::MissingParameters)
+  Ok(Pagination {
+    offset: offset_value,
+    limit: Some(limits.page_size(limit)?),
+    after: None,
+  })
 } // end fn extract_pagination()
+
+/// Gets a keyset pagination object from the given `after` and `limit` parameters.
+///
+/// Keyset pagination starts after the last identifier (ID) of the previous page, so
+/// rows added or removed meanwhile do not shift the next pages, and deep pages are as
+/// fast as the first one.
+///
+/// # Arguments
+///
+/// * `after`: Value of the `after` parameter.
+/// * `offset`: Value of the `offset` parameter, if any, which cannot be given along
+///   with `after`.
+/// * `limit`: Value of the `limit` parameter, if any.
+/// * `limits`: Page sizes allowed.
+///
+/// # Example Usage
+///
+/// ```rust
+/// let pagination = types::pagination::extract_keyset_pagination(
+///   40,
+///   None,
+///   Some(20),
+///   &PageLimits::default(),
+/// )
+/// .unwrap();
+/// assert_eq!(pagination.after, Some(40));
+/// assert_eq!(pagination.limit, Some(20));
+/// ```
+pub fn extract_keyset_pagination(
+  after: i32,
+  offset: Option<i32>,
+  limit: Option<i32>,
+  limits: &PageLimits,
+) -> Result<Pagination, QError> {
+  if offset.is_some() {
+    return Err(QError::InvalidParameter(
+      "after and offset cannot be given together".to_string(),
+    ));
+  }
+
+  Ok(Pagination {
+    offset: 0,
+    limit: Some(limits.page_size(limit)?),
+    after: Some(after),
+  })
+} // end fn extract_keyset_pagination()
+
+/// Represents the position of a page in a listing with offset pagination, along with
+/// the total amount of results and the links to the pages around it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageInfo {
+  /// Amount of results of the listing across all the pages.
+  pub total: i64,
+  /// Start index of the page.
+  pub offset: i32,
+  /// Amount of results a full page has, if the listing is paginated.
+  pub limit: Option<i32>,
+  /// Path and query string of the next page, if there are more results.
+  pub next: Option<String>,
+  /// Path and query string of the previous page, if this is not the first one.
+  pub prev: Option<String>,
+} // end struct PageInfo
+
+impl PageInfo {
+  /// Creates the position of a page, with links that keep the other parameters of the
+  /// listing.
+  ///
+  /// # Arguments
+  ///
+  /// * `path`: Path of the listing, like `/questions`.
+  /// * `params`: Query string parameters of the request.
+  /// * `pagination`: Pagination of the page.
+  /// * `total`: Amount of results of the listing across all the pages.
+  pub fn new(
+    path: &str,
+    params: &[(String, String)],
+    pagination: &Pagination,
+    total: i64,
+  ) -> Self {
+    let link = |offset: i32, limit: i32| {
+      let mut params: Vec<(String, String)> = params
+        .iter()
+        .filter(|(name, _)| name != "offset" && name != "limit")
+        .cloned()
+        .collect();
+      params.push(("offset".to_string(), offset.to_string()));
+      params.push(("limit".to_string(), limit.to_string()));
+      format!("{}?{}", path, serde_urlencoded::to_string(params).unwrap_or_default())
+    };
+
+    let offset = pagination.offset;
+    let (next, prev) = match pagination.limit {
+      Some(limit) => (
+        (offset as i64 + (limit as i64) < total).then(|| link(offset + limit, limit)),
+        (offset > 0).then(|| link((offset - limit).max(0), limit)),
+      ),
+      None => (None, None),
+    };
+
+    PageInfo {
+      total,
+      offset,
+      limit: pagination.limit,
+      next,
+      prev,
+    }
+  } // end fn new()
+}
+
+/// Represents a page of a listing with offset pagination.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Page<T> {
+  /// Results of the page.
+  pub items: Vec<T>,
+  /// Position of the page in the listing.
+  #[serde(flatten)]
+  pub info: PageInfo,
+} // end struct Page
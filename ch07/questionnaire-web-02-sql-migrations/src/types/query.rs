@@ -0,0 +1,512 @@
+use chrono::{DateTime, NaiveDateTime};
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Deserializer};
+use warp::{Filter, Rejection};
+
+use handle_errors::errors::{QError, QueryError};
+
+use crate::types::export::ExportFormat;
+use crate::types::pagination::{
+  extract_keyset_pagination, extract_pagination, PageLimits, Pagination,
+};
+use crate::types::question::{QuestionFilter, QuestionStatus};
+use crate::types::sorting::{SortField, SortOrder, Sorting};
+
+/// Represents how the tags given to list questions are matched.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TagMatch {
+  /// Questions must have all the given tags.
+  #[default]
+  All,
+  /// Questions must have at least one of the given tags.
+  Any,
+} // end enum TagMatch
+
+impl std::str::FromStr for TagMatch {
+  type Err = QError;
+
+  fn from_str(tag_match: &str) -> Result<Self, Self::Err> {
+    match tag_match {
+      "all" => Ok(TagMatch::All),
+      "any" => Ok(TagMatch::Any),
+      _ => Err(QError::InvalidParameter(format!(
+        "tag_match must be one of all or any, found '{}'",
+        tag_match
+      ))),
+    }
+  }
+}
+
+/// Represents the parameters to list a set of questions.
+#[derive(Debug, Default, Clone)]
+pub struct QuestionQuery {
+  /// Start index and amount of questions to list.
+  pub pagination: Pagination,
+  /// Status the questions must have, or `None` for questions in any status.
+  pub status: Option<QuestionStatus>,
+  /// Preset that selects and orders the questions, if any.
+  pub filter: Option<QuestionFilter>,
+  /// Tags the questions must have. No tags means questions with any tags.
+  pub tags: Vec<String>,
+  /// How the given tags are matched.
+  pub tag_match: TagMatch,
+  /// Sorting of the questions. It takes precedence over the ordering of the filter.
+  pub sorting: Option<Sorting>,
+  /// Date and time, in UTC, after which the questions must have been created.
+  pub created_after: Option<NaiveDateTime>,
+  /// Date and time, in UTC, before which the questions must have been created.
+  pub created_before: Option<NaiveDateTime>,
+  /// Language, in lowercase, of a code block some answer of the questions must have.
+  pub code_language: Option<String>,
+} // end struct QuestionQuery
+
+/// Represents the query string parameters of the listing of questions.
+///
+/// Unknown parameters are rejected, so a misspelled one is not silently ignored.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QuestionParams {
+  /// Start index of the page.
+  #[serde(default, deserialize_with = "number")]
+  pub offset: Option<i32>,
+  /// Amount of questions of the page.
+  #[serde(default, deserialize_with = "number")]
+  pub limit: Option<i32>,
+  /// ID after which the page starts, for keyset pagination.
+  #[serde(default, deserialize_with = "number")]
+  pub after: Option<i32>,
+  /// Status the questions must have.
+  #[serde(default, deserialize_with = "choice")]
+  pub status: Option<QuestionStatus>,
+  /// Preset that selects and orders the questions.
+  #[serde(default, deserialize_with = "choice")]
+  pub filter: Option<QuestionFilter>,
+  /// Values of the `tag` parameter, which may be repeated.
+  #[serde(skip)]
+  pub tags: Vec<String>,
+  /// How the given tags are matched.
+  #[serde(default, deserialize_with = "choice")]
+  pub tag_match: Option<TagMatch>,
+  /// Field to sort the questions by.
+  #[serde(default, deserialize_with = "choice")]
+  pub sort: Option<SortField>,
+  /// Direction of the sort.
+  #[serde(default, deserialize_with = "choice")]
+  pub order: Option<SortOrder>,
+  /// Date and time after which the questions must have been created.
+  #[serde(default, deserialize_with = "date_time")]
+  pub created_after: Option<NaiveDateTime>,
+  /// Date and time before which the questions must have been created.
+  #[serde(default, deserialize_with = "date_time")]
+  pub created_before: Option<NaiveDateTime>,
+  /// Language of a code block some answer of the questions must have, like `rust`.
+  pub code_language: Option<String>,
+  /// Parameters as given, in order, for the links to other pages.
+  #[serde(skip)]
+  pub pairs: Vec<(String, String)>,
+} // end struct QuestionParams
+
+/// Represents the query string parameters of the search of questions.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SearchParams {
+  /// Keywords to search.
+  pub q: Option<String>,
+  /// Start index of the page.
+  #[serde(default, deserialize_with = "number")]
+  pub offset: Option<i32>,
+  /// Amount of questions of the page.
+  #[serde(default, deserialize_with = "number")]
+  pub limit: Option<i32>,
+  /// Parameters as given, in order, for the links to other pages.
+  #[serde(skip)]
+  pub pairs: Vec<(String, String)>,
+} // end struct SearchParams
+
+/// Represents the query string parameters of a random question.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RandomParams {
+  /// Name of the tag the question must have.
+  pub tag: Option<String>,
+} // end struct RandomParams
+
+/// Represents the query string parameters of the changes of the questions.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChangesParams {
+  /// Cursor of a previous reply, after which the changes are listed. Without it, all
+  /// the changes are listed.
+  #[serde(default, deserialize_with = "cursor")]
+  pub since: Option<i64>,
+} // end struct ChangesParams
+
+/// Represents the query string parameters of the suggestions of titles and tags.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SuggestParams {
+  /// Text typed so far.
+  pub q: Option<String>,
+  /// Amount of titles, and of tags, to suggest.
+  #[serde(default, deserialize_with = "number")]
+  pub limit: Option<i32>,
+} // end struct SuggestParams
+
+/// Represents the query string parameters of the detail of a question.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QuestionDetailParams {
+  /// Code of the language to translate the question into, like `es`.
+  #[serde(default, deserialize_with = "language")]
+  pub translate: Option<String>,
+} // end struct QuestionDetailParams
+
+/// Represents the query string parameters of the additions of questions.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ForceParams {
+  /// Whether to add it even when similar ones exist.
+  #[serde(default, deserialize_with = "flag")]
+  pub force: Option<bool>,
+} // end struct ForceParams
+
+/// Represents the query string parameters of the export of the data store.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ExportParams {
+  /// Format of the export, JSON when not given.
+  #[serde(default, deserialize_with = "choice")]
+  pub format: Option<ExportFormat>,
+} // end struct ExportParams
+
+/// Represents the query string parameters of the import of an export.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ImportParams {
+  /// Whether to only report what would change, without changing anything.
+  #[serde(default, deserialize_with = "flag")]
+  pub dry_run: Option<bool>,
+} // end struct ImportParams
+
+/// Represents a set of query string parameters that can be parsed from their names and
+/// values.
+pub trait FromQuery: Sized {
+  /// Parses the given parameters.
+  ///
+  /// # Arguments
+  ///
+  /// * `pairs`: Names and values of the parameters, in the order they were given.
+  fn from_pairs(pairs: Vec<(String, String)>) -> Result<Self, QError>;
+}
+
+impl FromQuery for QuestionParams {
+  fn from_pairs(pairs: Vec<(String, String)>) -> Result<Self, QError> {
+    // The tag parameter is repeated, which serde_urlencoded cannot deserialize.
+    let (tags, others): (Vec<_>, Vec<_>) =
+      pairs.iter().cloned().partition(|(name, _)| name == "tag");
+    let mut params: QuestionParams = parse_pairs(&others)?;
+
+    for (_, tag) in tags {
+      if !params.tags.contains(&tag) {
+        params.tags.push(tag);
+      }
+    }
+    params.pairs = pairs;
+
+    Ok(params)
+  }
+}
+
+impl FromQuery for RandomParams {
+  fn from_pairs(pairs: Vec<(String, String)>) -> Result<Self, QError> {
+    parse_pairs(&pairs)
+  }
+}
+
+impl FromQuery for ChangesParams {
+  fn from_pairs(pairs: Vec<(String, String)>) -> Result<Self, QError> {
+    parse_pairs(&pairs)
+  }
+}
+
+impl FromQuery for SuggestParams {
+  fn from_pairs(pairs: Vec<(String, String)>) -> Result<Self, QError> {
+    parse_pairs(&pairs)
+  }
+}
+
+impl FromQuery for QuestionDetailParams {
+  fn from_pairs(pairs: Vec<(String, String)>) -> Result<Self, QError> {
+    parse_pairs(&pairs)
+  }
+}
+
+impl FromQuery for ForceParams {
+  fn from_pairs(pairs: Vec<(String, String)>) -> Result<Self, QError> {
+    parse_pairs(&pairs)
+  }
+}
+
+impl FromQuery for ExportParams {
+  fn from_pairs(pairs: Vec<(String, String)>) -> Result<Self, QError> {
+    parse_pairs(&pairs)
+  }
+}
+
+impl FromQuery for ImportParams {
+  fn from_pairs(pairs: Vec<(String, String)>) -> Result<Self, QError> {
+    parse_pairs(&pairs)
+  }
+}
+
+impl FromQuery for SearchParams {
+  fn from_pairs(pairs: Vec<(String, String)>) -> Result<Self, QError> {
+    let mut params: SearchParams = parse_pairs(&pairs)?;
+    params.pairs = pairs;
+
+    Ok(params)
+  }
+}
+
+/// Creates a filter that extracts the query string parameters as a `T`.
+///
+/// Unlike `warp::query()`, unknown or malformed parameters are rejected with an
+/// `QError::InvalidQuery` error naming each of them, so they are replied as a
+/// `400 Bad Request` with a JSON body.
+pub fn query_params<T: FromQuery + Send + 'static>(
+) -> impl Filter<Extract = (T,), Error = Rejection> + Copy {
+  warp::query::<Vec<(String, String)>>().and_then(|pairs| async move {
+    T::from_pairs(pairs).map_err(warp::reject::custom)
+  })
+}
+
+/// Deserializes the given parameters into a `T`, getting the problem of each unknown or
+/// malformed parameter when they cannot be deserialized.
+///
+/// # Arguments
+///
+/// * `pairs`: Names and values of the parameters.
+fn parse_pairs<T: DeserializeOwned>(pairs: &[(String, String)]) -> Result<T, QError> {
+  let encode = |pairs: &[(String, String)]| {
+    serde_urlencoded::to_string(pairs).unwrap_or_default()
+  };
+  let err = match serde_urlencoded::from_str::<T>(&encode(pairs)) {
+    Ok(params) => return Ok(params),
+    Err(err) => err,
+  };
+
+  // Each parameter is deserialized on its own to find the ones with problems.
+  let mut errors: Vec<QueryError> = pairs
+    .iter()
+    .filter_map(|pair| {
+      serde_urlencoded::from_str::<T>(&encode(std::slice::from_ref(pair)))
+        .err()
+        .map(|err| QueryError {
+          parameter: Some(pair.0.clone()),
+          message: err.to_string(),
+        })
+    })
+    .collect();
+  errors.dedup();
+  // The parameters are valid on their own, so one of them is repeated.
+  if errors.is_empty() {
+    let repeated = pairs.iter().enumerate().find_map(|(index, (name, _))| {
+      pairs[..index]
+        .iter()
+        .any(|(other, _)| other == name)
+        .then(|| name.clone())
+    });
+    errors.push(QueryError {
+      parameter: repeated,
+      message: err.to_string(),
+    });
+  }
+
+  Err(QError::InvalidQuery(errors))
+} // end fn parse_pairs()
+
+/// Deserializes an optional whole number parameter.
+///
+/// # Arguments
+///
+/// * `deserializer`: Deserializer of the value of the parameter.
+fn number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i32>, D::Error> {
+  let value = String::deserialize(deserializer)?;
+  value
+    .parse::<i32>()
+    .map(Some)
+    .map_err(|_| D::Error::custom(format!("must be a whole number, found '{}'", value)))
+} // end fn number()
+
+/// Deserializes an optional `true` or `false` parameter.
+///
+/// # Arguments
+///
+/// * `deserializer`: Deserializer of the value of the parameter.
+fn flag<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<bool>, D::Error> {
+  let value = String::deserialize(deserializer)?;
+  value
+    .parse::<bool>()
+    .map(Some)
+    .map_err(|_| D::Error::custom(format!("must be true or false, found '{}'", value)))
+} // end fn flag()
+
+/// Deserializes an optional change cursor parameter, a number given by a previous
+/// reply.
+///
+/// # Arguments
+///
+/// * `deserializer`: Deserializer of the value of the parameter.
+fn cursor<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i64>, D::Error> {
+  let value = String::deserialize(deserializer)?;
+  match value.parse::<i64>() {
+    Ok(cursor) if cursor >= 0 => Ok(Some(cursor)),
+    _ => Err(D::Error::custom(format!(
+      "must be the cursor of a previous reply, found '{}'",
+      value
+    ))),
+  }
+} // end fn cursor()
+
+/// Deserializes an optional RFC 3339 date and time parameter, like
+/// `2023-04-01T00:00:00Z`, as a date and time in UTC.
+///
+/// # Arguments
+///
+/// * `deserializer`: Deserializer of the value of the parameter.
+fn date_time<'de, D: Deserializer<'de>>(
+  deserializer: D,
+) -> Result<Option<NaiveDateTime>, D::Error> {
+  let value = String::deserialize(deserializer)?;
+  match DateTime::parse_from_rfc3339(&value) {
+    Ok(date_time) => Ok(Some(date_time.naive_utc())),
+    Err(_) => Err(D::Error::custom(format!(
+      "must be an RFC 3339 date and time like 2023-04-01T00:00:00Z, found '{}'",
+      value
+    ))),
+  }
+} // end fn date_time()
+
+/// Deserializes an optional language code parameter, like `es` or `pt-BR`.
+///
+/// # Arguments
+///
+/// * `deserializer`: Deserializer of the value of the parameter.
+fn language<'de, D: Deserializer<'de>>(
+  deserializer: D,
+) -> Result<Option<String>, D::Error> {
+  let value = String::deserialize(deserializer)?;
+  let (language, region) = match value.split_once('-') {
+    Some((language, region)) => (language, Some(region)),
+    None => (value.as_str(), None),
+  };
+  let valid = (2..=3).contains(&language.len())
+    && language.chars().all(|c| c.is_ascii_lowercase())
+    && region.map_or(true, |region| {
+      (2..=4).contains(&region.len()) && region.chars().all(|c| c.is_ascii_alphanumeric())
+    });
+
+  match valid {
+    true => Ok(Some(value)),
+    false => Err(D::Error::custom(format!(
+      "must be a language code like es or pt-BR, found '{}'",
+      value
+    ))),
+  }
+} // end fn language()
+
+/// Deserializes an optional parameter that takes one of the values of `T`.
+///
+/// # Arguments
+///
+/// * `deserializer`: Deserializer of the value of the parameter.
+fn choice<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+  D: Deserializer<'de>,
+  T: std::str::FromStr<Err = QError>,
+{
+  let value = String::deserialize(deserializer)?;
+  value.parse::<T>().map(Some).map_err(|err| match err {
+    QError::InvalidParameter(message) => D::Error::custom(message),
+    err => D::Error::custom(err),
+  })
+} // end fn choice()
+
+/// Gets the parameters to list questions from the given query string parameters.
+///
+/// The questions are paged with the default page size when no limit is given.
+///
+/// # Arguments
+///
+/// * `params`: Query string parameters.
+/// * `limits`: Page sizes allowed.
+///
+/// # Example Usage
+///
+/// ```rust
+/// let params = QuestionParams {
+///   tags: vec!["faq".to_string(), "rust".to_string()],
+///   tag_match: Some(TagMatch::Any),
+///   ..Default::default()
+/// };
+///
+/// let query =
+///   types::query::extract_question_query(params, &PageLimits::default()).unwrap();
+/// assert_eq!(query.tags, vec!["faq", "rust"]);
+/// assert_eq!(query.tag_match, TagMatch::Any);
+/// ```
+pub fn extract_question_query(
+  params: QuestionParams,
+  limits: &PageLimits,
+) -> Result<QuestionQuery, QError> {
+  let sorting = match (params.sort, params.order) {
+    (None, None) => None,
+    (field, order) => Some(Sorting {
+      field: field.unwrap_or_default(),
+      order: order.unwrap_or_default(),
+    }),
+  };
+
+  if let (Some(after), Some(before)) = (params.created_after, params.created_before) {
+    if after >= before {
+      return Err(QError::InvalidParameter(
+        "created_after must be earlier than created_before".to_string(),
+      ));
+    }
+  }
+
+  let code_language = match params.code_language.map(|language| language.to_lowercase()) {
+    Some(language) if language.trim().is_empty() => {
+      return Err(QError::InvalidParameter(
+        "code_language must not be empty".to_string(),
+      ));
+    }
+    language => language,
+  };
+
+  let pagination = match params.after {
+    // The pages follow the order of the IDs, so no other order can be chosen.
+    Some(_) if params.filter.is_some() || sorting.is_some() => {
+      return Err(QError::InvalidParameter(
+        "after pages by question ID, so it cannot be given with filter, sort or order"
+          .to_string(),
+      ));
+    }
+    Some(after) => {
+      extract_keyset_pagination(after, params.offset, params.limit, limits)?
+    }
+    None => extract_pagination(params.offset, params.limit, limits)?,
+  };
+
+  Ok(QuestionQuery {
+    pagination,
+    status: params.status,
+    filter: params.filter,
+    tags: params.tags,
+    tag_match: params.tag_match.unwrap_or_default(),
+    sorting,
+    created_after: params.created_after,
+    created_before: params.created_before,
+    code_language,
+  })
+} // end fn extract_question_query()
@@ -1,40 +1,158 @@
 use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
+
+use handle_errors::errors::QError;
+
+use crate::types::id::Id;
+use crate::types::validation::check_question_fields;
+
+/// Highest rating of rating questions created without a maximum rating.
+const DEFAULT_RATING_MAX: i32 = 5;
 
 /// Represents a valid identifier (ID) for a question.
-#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
-pub struct QuestionId(pub i32);
+pub type QuestionId = Id<Question>;
+
+/// Represents the stage of the lifecycle a question is in.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum QuestionStatus {
+  /// The question accepts new answers.
+  #[default]
+  Open,
+  /// The question does not accept new answers, but it can be reopened.
+  Closed,
+  /// The question is kept only for reference.
+  Archived,
+} // end enum QuestionStatus
+
+impl QuestionStatus {
+  /// Gets the name of the status as stored in the database.
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      QuestionStatus::Open => "open",
+      QuestionStatus::Closed => "closed",
+      QuestionStatus::Archived => "archived",
+    }
+  }
+}
 
-impl std::fmt::Display for QuestionId {
+impl std::fmt::Display for QuestionStatus {
   fn fmt(
     &self,
     f: &mut std::fmt::Formatter<'_>,
   ) -> std::fmt::Result {
-    write!(f, "id: {}", self.0)
+    write!(f, "{}", self.as_str())
+  }
+}
+
+impl std::str::FromStr for QuestionStatus {
+  type Err = QError;
+
+  fn from_str(status: &str) -> Result<Self, Self::Err> {
+    match status {
+      "open" => Ok(QuestionStatus::Open),
+      "closed" => Ok(QuestionStatus::Closed),
+      "archived" => Ok(QuestionStatus::Archived),
+      _ => Err(QError::InvalidParameter(format!(
+        "status must be one of open, closed or archived, found '{}'",
+        status
+      ))),
+    }
   }
 }
 
-impl std::str::FromStr for QuestionId {
-  type Err = std::io::Error;
-
-  fn from_str(id: &str) -> Result<Self, Self::Err> {
-    match id.is_empty() {
-      false => match id.parse::<i32>() {
-        Ok(value) => Ok(QuestionId(value)),
-        Err(err) => Err(std::io::Error::new(
-          std::io::ErrorKind::InvalidInput,
-          format!("ID is not an integer i32. {}", err),
-        )),
-      },
-      true => Err(std::io::Error::new(
-        std::io::ErrorKind::InvalidInput,
-        "No ID provided",
-      )),
+/// Represents the kind of answers a question takes.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum QuestionKind {
+  /// Free-text answers.
+  #[default]
+  Text,
+  /// Responses with exactly one of the options of the question.
+  SingleChoice,
+  /// Responses with one or more of the options of the question.
+  MultipleChoice,
+  /// Responses with a rating from 1 to the maximum rating of the question.
+  Rating,
+} // end enum QuestionKind
+
+impl QuestionKind {
+  /// Gets the name of the kind as stored in the database.
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      QuestionKind::Text => "text",
+      QuestionKind::SingleChoice => "single_choice",
+      QuestionKind::MultipleChoice => "multiple_choice",
+      QuestionKind::Rating => "rating",
+    }
+  }
+
+  /// Checks whether the questions of this kind have a set of options to choose from.
+  pub fn has_options(&self) -> bool {
+    matches!(self, QuestionKind::SingleChoice | QuestionKind::MultipleChoice)
+  }
+}
+
+impl std::fmt::Display for QuestionKind {
+  fn fmt(
+    &self,
+    f: &mut std::fmt::Formatter<'_>,
+  ) -> std::fmt::Result {
+    write!(f, "{}", self.as_str())
+  }
+}
+
+impl std::str::FromStr for QuestionKind {
+  type Err = QError;
+
+  fn from_str(kind: &str) -> Result<Self, Self::Err> {
+    match kind {
+      "text" => Ok(QuestionKind::Text),
+      "single_choice" => Ok(QuestionKind::SingleChoice),
+      "multiple_choice" => Ok(QuestionKind::MultipleChoice),
+      "rating" => Ok(QuestionKind::Rating),
+      _ => Err(QError::InvalidParameter(format!(
+        "kind must be one of text, single_choice, multiple_choice or rating, found '{}'",
+        kind
+      ))),
+    }
+  }
+}
+
+/// Represents a named preset for listing questions, like the tabs of a frontend.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum QuestionFilter {
+  /// Most recently created questions first.
+  Newest,
+  /// Questions without answers, most recently created first.
+  Unanswered,
+  /// Questions with the most recent activity (creation or answer) first.
+  Active,
+} // end enum QuestionFilter
+
+impl std::str::FromStr for QuestionFilter {
+  type Err = QError;
+
+  fn from_str(filter: &str) -> Result<Self, Self::Err> {
+    match filter {
+      "newest" => Ok(QuestionFilter::Newest),
+      "unanswered" => Ok(QuestionFilter::Unanswered),
+      "active" => Ok(QuestionFilter::Active),
+      _ => Err(QError::InvalidParameter(format!(
+        "filter must be one of newest, unanswered or active, found '{}'",
+        filter
+      ))),
     }
   }
 }
 
 /// Represents a question posted in the system.
+///
+/// Unknown fields are rejected, so typos like `titel` are not ignored.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Question {
   /// Identifier of the question.
   pub id: QuestionId,
@@ -44,8 +162,86 @@ pub struct Question {
   pub content: String,
   /// List of tags for the question.
   pub tags: Option<Vec<String>>,
+  /// Stage of the lifecycle of the question.
+  #[serde(default)]
+  pub status: QuestionStatus,
+  /// Kind of answers the question takes.
+  #[serde(default)]
+  pub kind: QuestionKind,
+  /// Labels of the options to choose from, in order, for choice questions.
+  pub options: Option<Vec<String>>,
+  /// Highest rating, for rating questions. Ratings start at 1.
+  pub rating_max: Option<i32>,
+  /// Points each option is worth in quiz surveys, in order, for choice questions with
+  /// options worth points. The options worth points are the correct ones.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub points: Option<Vec<i32>>,
+  /// Version of the question, which grows with each change. Versions start at 1, so 0
+  /// means that no version was given.
+  #[serde(default)]
+  pub version: i32,
 } // end Question struct
 
+/// Represents a page of questions listed with keyset pagination.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionPage {
+  /// Questions of the page, ordered by their ID.
+  pub questions: Vec<Question>,
+  /// ID to give as the `after` parameter to get the next page, or `None` when this is
+  /// the last page.
+  pub next_cursor: Option<QuestionId>,
+} // end struct QuestionPage
+
+impl QuestionPage {
+  /// Creates a page with the given questions.
+  ///
+  /// # Arguments
+  ///
+  /// * `questions`: Questions of the page, ordered by their ID.
+  /// * `limit`: Amount of questions a full page has.
+  pub fn new(
+    questions: Vec<Question>,
+    limit: i32,
+  ) -> Self {
+    let next_cursor = Self::next_cursor(
+      questions.last().map(|question| question.id),
+      questions.len(),
+      limit,
+    );
+
+    QuestionPage {
+      questions,
+      next_cursor,
+    }
+  } // end fn new()
+
+  /// Gets the cursor of the page after a page of questions. There may be a next page
+  /// only when the page is full.
+  ///
+  /// # Arguments
+  ///
+  /// * `last_id`: ID of the last question of the page, if any.
+  /// * `count`: Amount of questions of the page.
+  /// * `limit`: Amount of questions a full page has.
+  pub fn next_cursor(
+    last_id: Option<QuestionId>,
+    count: usize,
+    limit: i32,
+  ) -> Option<QuestionId> {
+    match count >= limit as usize {
+      true => last_id,
+      false => None,
+    }
+  } // end fn next_cursor()
+}
+
+impl Question {
+  /// Checks the title, content and tags sent to update the question.
+  pub fn validate(&self) -> Result<(), QError> {
+    check_question_fields(&self.title, &self.content, self.tags.as_deref())
+  } // end fn validate()
+}
+
 impl std::fmt::Display for Question {
   fn fmt(
     &self,
@@ -53,15 +249,64 @@ impl std::fmt::Display for Question {
   ) -> std::fmt::Result {
     write!(
       f,
-      "{}, title: {}, content: {}, tags: {:?}",
-      self.id, self.title, self.content, self.tags
+      "{}, title: {}, content: {}, tags: {:?}, status: {}, kind: {}",
+      self.id, self.title, self.content, self.tags, self.status, self.kind
     )
   }
 }
 
+impl<'r> FromRow<'r, PgRow> for Question {
+  /// Builds a question from a row with the `id`, `title`, `content`, `tags`, `status`,
+  /// `version`, `kind`, `options`, `rating_max`, and `points` columns.
+  ///
+  /// # Arguments
+  ///
+  /// * `row`: Row of the results of a question query.
+  fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+    Ok(Question {
+      id: row.try_get("id")?,
+      title: row.try_get("title")?,
+      content: row.try_get("content")?,
+      tags: row.try_get("tags")?,
+      status: decode_text(row, "status")?,
+      kind: decode_text(row, "kind")?,
+      options: row.try_get("options")?,
+      rating_max: row.try_get("rating_max")?,
+      points: row.try_get("points")?,
+      version: row.try_get("version")?,
+    })
+  } // end fn from_row()
+}
+
+/// Decodes a column stored as text into a value parsed from that text.
+///
+/// # Arguments
+///
+/// * `row`: Row that has the column.
+/// * `column`: Name of the column.
+fn decode_text<T>(
+  row: &PgRow,
+  column: &str,
+) -> Result<T, sqlx::Error>
+where
+  T: std::str::FromStr,
+  T::Err: std::error::Error + Send + Sync + 'static,
+{
+  row
+    .try_get::<String, _>(column)?
+    .parse()
+    .map_err(|err: T::Err| sqlx::Error::ColumnDecode {
+      index: column.to_string(),
+      source: Box::new(err),
+    })
+} // end fn decode_text()
+
 
 /// Represents a new question that will be posted in the system.
+///
+/// Unknown fields are rejected, so typos like `titel` are not ignored.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct NewQuestion {
   /// Title of the question.
   pub title: String,
@@ -69,5 +314,151 @@ pub struct NewQuestion {
   pub content: String,
   /// List of tags for the question.
   pub tags: Option<Vec<String>>,
+  /// Kind of answers the question takes.
+  #[serde(default)]
+  pub kind: QuestionKind,
+  /// Labels of the options to choose from, in order, for choice questions.
+  pub options: Option<Vec<String>>,
+  /// Highest rating, for rating questions. Ratings start at 1.
+  pub rating_max: Option<i32>,
+  /// Points each option is worth in quiz surveys, in order, for choice questions. The
+  /// options worth points are the correct ones.
+  pub points: Option<Vec<i32>>,
 } // end NewQuestion struct
 
+impl NewQuestion {
+  /// Checks the title, content and tags of the question, and that its options and
+  /// rating scale match its kind.
+  ///
+  /// Choice questions need at least two distinct, non-empty options, and one point
+  /// value per option if any, and rating questions get a scale from 1 to 5 when no
+  /// maximum rating is given.
+  pub fn validate(mut self) -> Result<Self, QError> {
+    check_question_fields(&self.title, &self.content, self.tags.as_deref())?;
+
+    if self.kind.has_options() {
+      let options = self.options.as_deref().unwrap_or_default();
+      if options.len() < 2 {
+        return Err(QError::InvalidParameter(format!(
+          "{} questions need at least two options",
+          self.kind
+        )));
+      }
+      for (index, option) in options.iter().enumerate() {
+        if option.trim().is_empty() || options[..index].contains(option) {
+          return Err(QError::InvalidParameter(format!(
+            "options must be distinct and not empty, found '{}'",
+            option
+          )));
+        }
+      }
+      if let Some(points) = &self.points {
+        if points.len() != options.len() {
+          return Err(QError::InvalidParameter(format!(
+            "points must have one value per option, found {} for {} options",
+            points.len(),
+            options.len()
+          )));
+        }
+      }
+    } else if self.options.is_some() || self.points.is_some() {
+      return Err(QError::InvalidParameter(format!(
+        "{} questions do not take options or points",
+        self.kind
+      )));
+    }
+
+    match (self.kind, self.rating_max) {
+      (QuestionKind::Rating, None) => self.rating_max = Some(DEFAULT_RATING_MAX),
+      (QuestionKind::Rating, Some(rating_max)) if !(2..=10).contains(&rating_max) => {
+        return Err(QError::InvalidParameter(format!(
+          "rating_max must be between 2 and 10, found {}",
+          rating_max
+        )));
+      }
+      (QuestionKind::Rating, Some(_)) => {}
+      (_, Some(_)) => {
+        return Err(QError::InvalidParameter(format!(
+          "{} questions do not take a rating_max",
+          self.kind
+        )));
+      }
+      (_, None) => {}
+    }
+
+    Ok(self)
+  } // end fn validate()
+}
+
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+
+  /// Builds a new question of the given kind from the other fields of its JSON body.
+  ///
+  /// # Arguments
+  ///
+  /// * `fields`: JSON fields of the question besides its title and content.
+  fn new_question(fields: serde_json::Value) -> NewQuestion {
+    let mut body = json!({ "title": "Favourite crate", "content": "Pick one" });
+    body.as_object_mut().unwrap().extend(fields.as_object().unwrap().clone());
+    serde_json::from_value(body).expect("valid question body")
+  }
+
+  #[test]
+  fn accepts_a_choice_question_with_its_points() {
+    let question = new_question(json!({
+      "kind": "single_choice",
+      "options": ["warp", "axum"],
+      "points": [1, 0],
+    }));
+
+    let question = question.validate().unwrap();
+    assert_eq!(question.options.unwrap().len(), 2);
+  }
+
+  #[test]
+  fn rejects_a_choice_question_without_distinct_options() {
+    for options in [json!(["warp"]), json!(["warp", "warp"]), json!(["warp", " "])] {
+      let question =
+        new_question(json!({ "kind": "multiple_choice", "options": options }));
+
+      assert!(matches!(question.validate(), Err(QError::InvalidParameter(_))));
+    }
+  }
+
+  #[test]
+  fn rejects_points_that_do_not_match_the_options() {
+    let question = new_question(json!({
+      "kind": "single_choice",
+      "options": ["warp", "axum"],
+      "points": [1],
+    }));
+
+    assert!(matches!(question.validate(), Err(QError::InvalidParameter(_))));
+  }
+
+  #[test]
+  fn gives_a_rating_question_the_default_scale() {
+    let question = new_question(json!({ "kind": "rating" }));
+
+    assert_eq!(question.validate().unwrap().rating_max, Some(DEFAULT_RATING_MAX));
+  }
+
+  #[test]
+  fn rejects_a_rating_scale_out_of_range() {
+    let question = new_question(json!({ "kind": "rating", "rating_max": 11 }));
+
+    assert!(matches!(question.validate(), Err(QError::InvalidParameter(_))));
+  }
+
+  #[test]
+  fn rejects_options_of_a_text_question() {
+    let question = new_question(json!({ "options": ["yes", "no"] }));
+
+    assert!(matches!(question.validate(), Err(QError::InvalidParameter(_))));
+  }
+}
@@ -0,0 +1,199 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::types::question::{Question, QuestionId, QuestionKind};
+use crate::types::survey::{Survey, SurveyId, SurveyQuestionResponse};
+
+/// Represents the score of the response to one of the questions of a quiz survey.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionScore {
+  /// Unique identifier (ID) of the question.
+  pub question_id: QuestionId,
+  /// Points given to the response, or 0 when the question has no response.
+  pub points: i32,
+  /// Highest points a response to the question can get.
+  pub max_points: i32,
+  /// Whether the response chose exactly the options worth points.
+  pub correct: bool,
+} // end struct QuestionScore
+
+/// Represents the score of the responses of a respondent to a quiz survey.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuizResult {
+  /// Unique identifier (ID) of the submission the responses were given in.
+  pub submission_id: i32,
+  /// Total points given to the responses.
+  pub score: i32,
+  /// Highest total points the responses to the survey can get.
+  pub max_score: i32,
+  /// Scores of the questions with options worth points, in order.
+  pub questions: Vec<QuestionScore>,
+} // end struct QuizResult
+
+impl QuizResult {
+  /// Computes the score of the given responses to a quiz survey.
+  ///
+  /// A response gets the sum of the points of the options it chose, so options worth
+  /// negative points take points away. Questions without options worth points are not
+  /// scored.
+  ///
+  /// # Arguments
+  ///
+  /// * `submission_id`: Unique identifier (ID) of the submission of the responses.
+  /// * `survey`: Survey the responses are given to, with the points of its questions.
+  /// * `responses`: Responses given to the questions of the survey.
+  pub fn new(
+    submission_id: i32,
+    survey: &Survey,
+    responses: &[SurveyQuestionResponse],
+  ) -> Self {
+    let questions: Vec<QuestionScore> = survey
+      .questions
+      .iter()
+      .filter_map(|question| {
+        let points = question.points.as_deref()?;
+        let choices = responses
+          .iter()
+          .find(|answer| answer.question_id == question.id)
+          .and_then(|answer| answer.response.choices.clone())
+          .unwrap_or_default();
+        let correct: Vec<i32> = (1..=points.len() as i32)
+          .filter(|position| points[*position as usize - 1] > 0)
+          .collect();
+
+        Some(QuestionScore {
+          question_id: question.id,
+          points: choices
+            .iter()
+            .filter_map(|choice| points.get((*choice - 1) as usize))
+            .sum(),
+          max_points: max_points(question),
+          correct: !correct.is_empty()
+            && choices.len() == correct.len()
+            && choices.iter().all(|choice| correct.contains(choice)),
+        })
+      })
+      .collect();
+
+    QuizResult {
+      submission_id,
+      score: questions.iter().map(|question| question.points).sum(),
+      max_score: questions.iter().map(|question| question.max_points).sum(),
+      questions,
+    }
+  } // end fn new()
+}
+
+/// Gets the highest points a response to the given question can get: the points of
+/// its best option for single choice questions, or the points of all its options worth
+/// points for multiple choice questions.
+///
+/// # Arguments
+///
+/// * `question`: Question with the points of its options.
+pub fn max_points(question: &Question) -> i32 {
+  let points = question.points.as_deref().unwrap_or_default();
+  match question.kind {
+    QuestionKind::SingleChoice => points.iter().copied().max().unwrap_or_default().max(0),
+    _ => points.iter().filter(|points| **points > 0).sum(),
+  }
+} // end fn max_points()
+
+/// Represents how many submissions to a quiz survey got a score.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScoreCount {
+  /// Total points of the submissions.
+  pub score: i32,
+  /// Amount of submissions with the score.
+  pub count: i64,
+} // end struct ScoreCount
+
+/// Represents the score of a submission to a quiz survey.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SubmissionScore {
+  /// Unique identifier (ID) of the submission.
+  pub submission_id: i32,
+  /// Total points of the submission.
+  pub score: i32,
+  /// Date and time the submission was given.
+  pub submitted_on: NaiveDateTime,
+} // end struct SubmissionScore
+
+/// Represents the scores of all the submissions to a quiz survey.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuizScores {
+  /// Unique identifier (ID) of the survey.
+  pub survey_id: SurveyId,
+  /// Highest total points a submission can get.
+  pub max_score: i32,
+  /// Amount of scored submissions.
+  pub submissions: i64,
+  /// Average score of the submissions, if there are any.
+  pub average_score: Option<f64>,
+  /// Amount of submissions with each score, from the lowest to the highest score.
+  pub distribution: Vec<ScoreCount>,
+  /// Score of each submission, in the order they were given.
+  pub scores: Vec<SubmissionScore>,
+} // end struct QuizScores
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+  use crate::types::testing::{choices, question, survey};
+
+  /// Builds a quiz survey with a single choice question, a multiple choice question and
+  /// a rating question, which is not scored.
+  fn quiz() -> Survey {
+    let options = json!(["a", "b", "c"]);
+    survey(vec![
+      question(
+        1,
+        json!({ "kind": "single_choice", "options": options, "points": [0, 3, 0] }),
+      ),
+      question(
+        2,
+        json!({ "kind": "multiple_choice", "options": options, "points": [2, 2, -1] }),
+      ),
+      question(3, json!({ "kind": "rating", "rating_max": 5 })),
+    ])
+  }
+
+  #[test]
+  fn scores_the_correct_responses() {
+    let responses = [choices(1, vec![2]), choices(2, vec![2, 1])];
+
+    let result = QuizResult::new(7, &quiz(), &responses);
+    assert_eq!(result.submission_id, 7);
+    assert_eq!((result.score, result.max_score), (7, 7));
+    assert_eq!(result.questions.len(), 2);
+    assert!(result.questions.iter().all(|question| question.correct));
+  }
+
+  #[test]
+  fn takes_points_away_for_the_wrong_choices() {
+    let responses = [choices(1, vec![1]), choices(2, vec![1, 3])];
+
+    let result = QuizResult::new(1, &quiz(), &responses);
+    assert_eq!(result.score, 1);
+    assert!(result.questions.iter().all(|question| !question.correct));
+  }
+
+  #[test]
+  fn gives_no_points_to_the_questions_without_a_response() {
+    let result = QuizResult::new(1, &quiz(), &[]);
+
+    assert_eq!(result.score, 0);
+    assert_eq!(result.max_score, 7);
+  }
+
+  #[test]
+  fn gets_the_highest_points_of_a_question() {
+    let quiz = quiz();
+
+    assert_eq!(max_points(&quiz.questions[0]), 3);
+    assert_eq!(max_points(&quiz.questions[1]), 4);
+    assert_eq!(max_points(&quiz.questions[2]), 0);
+  }
+}
@@ -0,0 +1,199 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use warp::http::{HeaderMap, Method, StatusCode, Uri};
+
+/// Text that replaces the secrets of a recorded exchange.
+pub const REDACTED: &str = "[redacted]";
+
+/// Names of the headers whose values are secrets.
+const SECRET_HEADERS: [&str; 4] =
+  ["authorization", "cookie", "proxy-authorization", "set-cookie"];
+
+/// Names of the JSON fields whose values are secrets, like invitation tokens and draft
+/// keys.
+const SECRET_FIELDS: [&str; 6] =
+  ["draft", "key", "password", "secret", "token", "tokens"];
+
+/// Path segments followed by a secret, like `drafts` in `/surveys/1/drafts/<key>`.
+const SECRET_PATH_SEGMENTS: [&str; 1] = ["drafts"];
+
+/// Represents the body of a recorded request or response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RecordedBody {
+  /// UTF-8 text, with the secrets redacted when it is JSON.
+  Text(String),
+  /// Any other data, like MessagePack, as is.
+  Binary(Vec<u8>),
+} // end enum RecordedBody
+
+impl RecordedBody {
+  /// Creates a recorded body from the bytes of a request or response body.
+  ///
+  /// # Arguments
+  ///
+  /// * `bytes`: Bytes of the body.
+  pub fn new(bytes: &[u8]) -> Self {
+    match std::str::from_utf8(bytes) {
+      Ok(text) => match serde_json::from_str::<Value>(text) {
+        Ok(mut json) => {
+          redact_json(&mut json);
+          RecordedBody::Text(json.to_string())
+        }
+        Err(_) => RecordedBody::Text(text.to_string()),
+      },
+      Err(_) => RecordedBody::Binary(bytes.to_vec()),
+    }
+  } // end fn new()
+
+  /// Gets the bytes of this body.
+  pub fn into_bytes(self) -> Vec<u8> {
+    match self {
+      RecordedBody::Text(text) => text.into_bytes(),
+      RecordedBody::Binary(bytes) => bytes,
+    }
+  } // end fn into_bytes()
+}
+
+/// Represents a request to the API along with the response it got, as recorded by the
+/// `--record` mode, with the secrets redacted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Exchange {
+  /// Date and time the request was received.
+  pub recorded_on: NaiveDateTime,
+  /// HTTP method of the request.
+  pub method: String,
+  /// Path and query string of the request.
+  pub path: String,
+  /// Names and values of the request headers.
+  pub request_headers: Vec<(String, String)>,
+  /// Body of the request.
+  pub request_body: RecordedBody,
+  /// Status code of the response.
+  pub status: u16,
+  /// Names and values of the response headers.
+  pub response_headers: Vec<(String, String)>,
+  /// Body of the response.
+  pub response_body: RecordedBody,
+} // end struct Exchange
+
+impl Exchange {
+  /// Creates a recorded exchange from a request, with no response yet.
+  ///
+  /// # Arguments
+  ///
+  /// * `method`: HTTP method of the request.
+  /// * `uri`: URI of the request.
+  /// * `headers`: Headers of the request.
+  /// * `body`: Bytes of the request body.
+  pub fn new(
+    method: &Method,
+    uri: &Uri,
+    headers: &HeaderMap,
+    body: &[u8],
+  ) -> Self {
+    let path = uri
+      .path_and_query()
+      .map_or_else(|| uri.path().to_string(), |path| path.to_string());
+
+    Exchange {
+      recorded_on: chrono::Utc::now().naive_utc(),
+      method: method.to_string(),
+      path: redact_path(&path),
+      request_headers: redact_headers(headers),
+      request_body: RecordedBody::new(body),
+      status: 0,
+      response_headers: Vec::new(),
+      response_body: RecordedBody::Text(String::new()),
+    }
+  } // end fn new()
+
+  /// Sets the response the request got.
+  ///
+  /// # Arguments
+  ///
+  /// * `status`: Status code of the response.
+  /// * `headers`: Headers of the response.
+  /// * `body`: Bytes of the response body.
+  pub fn set_response(
+    &mut self,
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &[u8],
+  ) {
+    self.status = status.as_u16();
+    self.response_headers = redact_headers(headers);
+    self.response_body = RecordedBody::new(body);
+  } // end fn set_response()
+}
+
+/// Gets the names and values of the given headers, with the values of the secret ones
+/// redacted.
+///
+/// # Arguments
+///
+/// * `headers`: Headers of a request or response.
+fn redact_headers(headers: &HeaderMap) -> Vec<(String, String)> {
+  headers
+    .iter()
+    .map(|(name, value)| {
+      let value = match SECRET_HEADERS.contains(&name.as_str()) {
+        true => REDACTED.to_string(),
+        false => String::from_utf8_lossy(value.as_bytes()).into_owned(),
+      };
+      (name.to_string(), value)
+    })
+    .collect()
+} // end fn redact_headers()
+
+/// Gets the given path with the segments after a secret path segment redacted.
+///
+/// # Arguments
+///
+/// * `path`: Path and query string of a request.
+fn redact_path(path: &str) -> String {
+  let (path, query) = match path.split_once('?') {
+    Some((path, query)) => (path, Some(query)),
+    None => (path, None),
+  };
+
+  let mut previous = "";
+  let segments: Vec<&str> = path
+    .split('/')
+    .map(|segment| {
+      let secret = SECRET_PATH_SEGMENTS.contains(&previous) && !segment.is_empty();
+      let redacted = match secret {
+        true => REDACTED,
+        false => segment,
+      };
+      previous = segment;
+      redacted
+    })
+    .collect();
+
+  match query {
+    Some(query) => format!("{}?{}", segments.join("/"), query),
+    None => segments.join("/"),
+  }
+} // end fn redact_path()
+
+/// Replaces the values of the secret fields of a JSON value, at any depth.
+///
+/// # Arguments
+///
+/// * `json`: JSON value to redact.
+fn redact_json(json: &mut Value) {
+  match json {
+    Value::Object(fields) => {
+      for (name, value) in fields.iter_mut() {
+        match SECRET_FIELDS.contains(&name.as_str()) && !value.is_null() {
+          true => *value = Value::String(REDACTED.to_string()),
+          false => redact_json(value),
+        }
+      }
+    }
+    Value::Array(values) => values.iter_mut().for_each(redact_json),
+    _ => {}
+  }
+} // end fn redact_json()
@@ -0,0 +1,28 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use crate::types::question::QuestionId;
+
+/// Longest chain of redirects followed, so redirects to redirected questions are
+/// resolved without looping forever on cycles.
+pub const REDIRECT_MAX_HOPS: i32 = 10;
+
+/// Represents the redirect of the links to a question replaced by another one, e.g.
+/// after merging duplicates.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct QuestionRedirect {
+  /// Unique identifier (ID) of the replaced question.
+  pub from_id: QuestionId,
+  /// Unique identifier (ID) of the question that replaced it.
+  pub target_id: QuestionId,
+  /// Date and time the redirect was set.
+  pub created_on: NaiveDateTime,
+} // end struct QuestionRedirect
+
+/// Represents the target of a redirect to set for a replaced question.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NewRedirect {
+  /// Unique identifier (ID) of the question that replaced it.
+  pub target_id: QuestionId,
+} // end struct NewRedirect
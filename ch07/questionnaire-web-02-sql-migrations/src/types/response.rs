@@ -0,0 +1,233 @@
+use serde::{Deserialize, Serialize};
+
+use handle_errors::errors::QError;
+
+use crate::types::question::{Question, QuestionId, QuestionKind};
+
+/// Represents a new response to a choice or rating question.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewResponse {
+  /// Positions of the chosen options, starting at 1, for choice questions.
+  pub choices: Option<Vec<i32>>,
+  /// Rating given, for rating questions.
+  pub rating: Option<i32>,
+} // end struct NewResponse
+
+impl NewResponse {
+  /// Checks that this response is valid for the given question.
+  ///
+  /// # Arguments
+  ///
+  /// * `question`: Question this response is given to.
+  pub fn validate(
+    &self,
+    question: &Question,
+  ) -> Result<(), QError> {
+    match question.kind {
+      QuestionKind::Text => Err(QError::InvalidParameter(
+        "text questions take answers, not responses".to_string(),
+      )),
+      QuestionKind::SingleChoice | QuestionKind::MultipleChoice => {
+        if self.rating.is_some() {
+          return Err(QError::InvalidParameter(format!(
+            "{} questions do not take a rating",
+            question.kind
+          )));
+        }
+
+        let choices = self.choices.as_deref().unwrap_or_default();
+        let options = question.options.as_deref().unwrap_or_default().len() as i32;
+        if choices.is_empty()
+          || (question.kind == QuestionKind::SingleChoice && choices.len() > 1)
+        {
+          return Err(QError::InvalidParameter(format!(
+            "{} questions need {} choice",
+            question.kind,
+            match question.kind {
+              QuestionKind::SingleChoice => "exactly one",
+              _ => "at least one",
+            }
+          )));
+        }
+        for (index, choice) in choices.iter().enumerate() {
+          if !(1..=options).contains(choice) || choices[..index].contains(choice) {
+            return Err(QError::InvalidParameter(format!(
+              "choices must be distinct positions from 1 to {}, found {}",
+              options, choice
+            )));
+          }
+        }
+
+        Ok(())
+      }
+      QuestionKind::Rating => {
+        if self.choices.is_some() {
+          return Err(QError::InvalidParameter(
+            "rating questions do not take choices".to_string(),
+          ));
+        }
+
+        let rating_max = question.rating_max.unwrap_or_default();
+        match self.rating {
+          Some(rating) if (1..=rating_max).contains(&rating) => Ok(()),
+          Some(rating) => Err(QError::InvalidParameter(format!(
+            "rating must be between 1 and {}, found {}",
+            rating_max, rating
+          ))),
+          None => Err(QError::InvalidParameter(
+            "rating questions need a rating".to_string(),
+          )),
+        }
+      }
+    }
+  } // end fn validate()
+
+  /// Gets the choices or the rating of this response.
+  pub fn values(&self) -> Vec<i32> {
+    let mut values = self.choices.clone().unwrap_or_default();
+    values.extend(self.rating);
+    values
+  } // end fn values()
+}
+
+/// Represents how many times an option of a choice question was chosen.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OptionResult {
+  /// Position of the option, starting at 1.
+  pub position: i32,
+  /// Label of the option.
+  pub label: String,
+  /// Amount of responses that chose the option.
+  pub count: i64,
+  /// Percentage, from 0 to 100, of the responses that chose the option.
+  #[sqlx(default)]
+  pub percentage: f64,
+} // end struct OptionResult
+
+/// Represents how many times a rating was given to a rating question.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RatingResult {
+  /// Rating given.
+  pub rating: i32,
+  /// Amount of responses with the rating.
+  pub count: i64,
+  /// Percentage, from 0 to 100, of the responses with the rating.
+  #[sqlx(default)]
+  pub percentage: f64,
+} // end struct RatingResult
+
+/// Represents the aggregated responses to a choice or rating question.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuestionResults {
+  /// Unique identifier (ID) of the question.
+  pub question_id: QuestionId,
+  /// Kind of answers the question takes.
+  pub kind: QuestionKind,
+  /// Amount of responses to the question.
+  pub responses: i64,
+  /// Results of each option, in order, for choice questions.
+  pub options: Option<Vec<OptionResult>>,
+  /// Results of each rating, from 1 to the highest rating, for rating questions.
+  pub ratings: Option<Vec<RatingResult>>,
+  /// Average rating, for rating questions with responses.
+  pub average_rating: Option<f64>,
+} // end struct QuestionResults
+
+/// Gets the percentage, from 0 to 100, that the given count is of the given total, or
+/// 0 when the total is 0.
+///
+/// # Arguments
+///
+/// * `count`: Amount to get the percentage of.
+/// * `total`: Amount that makes up 100%.
+pub fn percentage(
+  count: i64,
+  total: i64,
+) -> f64 {
+  match total {
+    0 => 0.0,
+    _ => count as f64 * 100.0 / total as f64,
+  }
+} // end fn percentage()
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+  use crate::types::testing;
+
+  /// Builds a question with the given JSON fields besides its title and content.
+  ///
+  /// # Arguments
+  ///
+  /// * `fields`: JSON fields of the question, like its kind and options.
+  fn question(fields: serde_json::Value) -> Question {
+    testing::question(1, fields)
+  }
+
+  /// Builds a response with the given choices and rating.
+  ///
+  /// # Arguments
+  ///
+  /// * `choices`: Positions of the chosen options.
+  /// * `rating`: Rating given.
+  fn response(
+    choices: Option<Vec<i32>>,
+    rating: Option<i32>,
+  ) -> NewResponse {
+    NewResponse { choices, rating }
+  }
+
+  #[test]
+  fn accepts_the_choices_of_the_options() {
+    let single = question(json!({ "kind": "single_choice", "options": ["a", "b"] }));
+    let multiple = question(json!({ "kind": "multiple_choice", "options": ["a", "b"] }));
+
+    assert!(response(Some(vec![2]), None).validate(&single).is_ok());
+    assert!(response(Some(vec![1, 2]), None).validate(&multiple).is_ok());
+  }
+
+  #[test]
+  fn rejects_invalid_choices() {
+    let single = question(json!({ "kind": "single_choice", "options": ["a", "b"] }));
+    let multiple = question(json!({ "kind": "multiple_choice", "options": ["a", "b"] }));
+
+    for (choices, question) in [
+      (vec![], &single),
+      (vec![1, 2], &single),
+      (vec![3], &multiple),
+      (vec![1, 1], &multiple),
+    ] {
+      let response = response(Some(choices), None);
+
+      assert!(matches!(
+        response.validate(question),
+        Err(QError::InvalidParameter(_))
+      ));
+    }
+  }
+
+  #[test]
+  fn checks_the_rating_against_the_scale() {
+    let rating = question(json!({ "kind": "rating", "rating_max": 5 }));
+
+    assert!(response(None, Some(5)).validate(&rating).is_ok());
+    assert!(response(None, Some(6)).validate(&rating).is_err());
+    assert!(response(None, None).validate(&rating).is_err());
+    assert!(response(Some(vec![1]), Some(3)).validate(&rating).is_err());
+  }
+
+  #[test]
+  fn rejects_a_response_to_a_text_question() {
+    let text = question(json!({}));
+
+    assert!(response(None, Some(1)).validate(&text).is_err());
+  }
+
+  #[test]
+  fn gets_the_percentage_of_a_total() {
+    assert_eq!(percentage(1, 4), 25.0);
+    assert_eq!(percentage(3, 0), 0.0);
+  }
+}
@@ -0,0 +1,255 @@
+use serde::Serialize;
+
+/// Days a deleted question can be restored before it is purged, if neither
+/// `RETENTION_RULES` nor `QUESTIONS_PURGE_DAYS` set them.
+pub const DEFAULT_DELETED_QUESTIONS_DAYS: i32 = 30;
+
+/// Days a draft is kept since it was last saved, if `RETENTION_RULES` does not set them.
+pub const DEFAULT_DRAFTS_DAYS: i32 = 30;
+
+/// Days the changes of the questions are kept, if `RETENTION_RULES` does not set them.
+pub const DEFAULT_QUESTION_CHANGES_DAYS: i32 = 365;
+
+/// Represents a kind of data removed once it is older than its retention period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionRule {
+  /// Questions deleted longer ago than the period, along with their answers, unless
+  /// surveys link them.
+  DeletedQuestions,
+  /// Drafts of anonymous respondents last saved longer ago than the period, or past
+  /// their expiry date.
+  Drafts,
+  /// Changes of the questions made longer ago than the period. Clients syncing with a
+  /// cursor older than the period miss them, so they must sync everything again.
+  QuestionChanges,
+} // end enum RetentionRule
+
+impl RetentionRule {
+  /// Every rule, in the order they are applied.
+  pub const ALL: [RetentionRule; 3] = [
+    RetentionRule::DeletedQuestions,
+    RetentionRule::Drafts,
+    RetentionRule::QuestionChanges,
+  ];
+
+  /// Gets the name of the rule, as given in `RETENTION_RULES`.
+  pub fn name(&self) -> &'static str {
+    match self {
+      RetentionRule::DeletedQuestions => "deleted_questions",
+      RetentionRule::Drafts => "drafts",
+      RetentionRule::QuestionChanges => "question_changes",
+    }
+  } // end fn name()
+}
+
+/// Represents the retention period of each rule, in days, or `None` for the rules
+/// turned off, whose data is kept forever.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicies {
+  /// Retention of the deleted questions.
+  pub deleted_questions: Option<i32>,
+  /// Retention of the drafts.
+  pub drafts: Option<i32>,
+  /// Retention of the changes of the questions.
+  pub question_changes: Option<i32>,
+} // end struct RetentionPolicies
+
+impl Default for RetentionPolicies {
+  fn default() -> Self {
+    RetentionPolicies {
+      deleted_questions: Some(DEFAULT_DELETED_QUESTIONS_DAYS),
+      drafts: Some(DEFAULT_DRAFTS_DAYS),
+      question_changes: Some(DEFAULT_QUESTION_CHANGES_DAYS),
+    }
+  }
+}
+
+impl RetentionPolicies {
+  /// Gets the policies from the `RETENTION_RULES` environment variable, or the default
+  /// ones when it is not set or not valid. The deleted questions default to
+  /// `QUESTIONS_PURGE_DAYS`, if set.
+  ///
+  /// `RETENTION_RULES` holds comma separated periods in days like
+  /// `deleted_questions=30,drafts=7,question_changes=off`, where `off` keeps the data
+  /// forever. The rules not given keep their default.
+  pub fn from_env() -> Self {
+    let mut defaults = RetentionPolicies::default();
+    if let Some(days) = std::env::var("QUESTIONS_PURGE_DAYS")
+      .ok()
+      .and_then(|days| days.parse::<i32>().ok())
+      .filter(|days| *days >= 0)
+    {
+      defaults.deleted_questions = Some(days);
+    }
+
+    std::env::var("RETENTION_RULES")
+      .ok()
+      .and_then(|spec| Self::parse(&spec, defaults).ok())
+      .unwrap_or(defaults)
+  } // end fn from_env()
+
+  /// Gets the policies of the given specification.
+  ///
+  /// # Arguments
+  ///
+  /// * `spec`: Comma separated periods, like `drafts=7,question_changes=off`.
+  /// * `defaults`: Policies of the rules not given.
+  pub fn parse(
+    spec: &str,
+    defaults: RetentionPolicies,
+  ) -> Result<Self, String> {
+    let mut policies = defaults;
+
+    for entry in spec.split(',').map(str::trim).filter(|entry| !entry.is_empty()) {
+      let (name, days) = entry
+        .split_once('=')
+        .ok_or_else(|| format!("'{}' is not like rule=days", entry))?;
+      let rule = RetentionRule::ALL
+        .into_iter()
+        .find(|rule| rule.name() == name.trim())
+        .ok_or_else(|| {
+          format!(
+            "rule must be one of {}, found '{}'",
+            "deleted_questions, drafts or question_changes",
+            name.trim()
+          )
+        })?;
+      let days = match days.trim() {
+        "off" => None,
+        days => match days.parse::<i32>() {
+          Ok(days) if days >= 0 => Some(days),
+          _ => {
+            return Err(format!(
+              "days of {} must be a whole number or off, found '{}'",
+              rule.name(),
+              days
+            ))
+          }
+        },
+      };
+
+      match rule {
+        RetentionRule::DeletedQuestions => policies.deleted_questions = days,
+        RetentionRule::Drafts => policies.drafts = days,
+        RetentionRule::QuestionChanges => policies.question_changes = days,
+      }
+    }
+
+    Ok(policies)
+  } // end fn parse()
+
+  /// Gets the retention period of the given rule, in days, or `None` when it is off.
+  ///
+  /// # Arguments
+  ///
+  /// * `rule`: Rule whose period is wanted.
+  pub fn days(
+    &self,
+    rule: RetentionRule,
+  ) -> Option<i32> {
+    match rule {
+      RetentionRule::DeletedQuestions => self.deleted_questions,
+      RetentionRule::Drafts => self.drafts,
+      RetentionRule::QuestionChanges => self.question_changes,
+    }
+  } // end fn days()
+}
+
+/// Represents the outcome of the scheduled runs of a retention rule since the start.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RuleMetrics {
+  /// Amount of runs of the rule.
+  pub runs: u64,
+  /// Amount of runs that failed.
+  pub failures: u64,
+  /// Rows removed by the last run, or that it would have removed in dry-run mode.
+  pub last_rows: u64,
+  /// Rows removed by all the runs, or that they would have removed in dry-run mode.
+  pub total_rows: u64,
+  /// Milliseconds the last run took.
+  pub last_duration_ms: f64,
+  /// Description of the problem of the last run, with the reference of its details in
+  /// the logs, if it failed.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub last_error: Option<String>,
+} // end struct RuleMetrics
+
+/// Represents a retention rule along with the rows it would remove now and the
+/// metrics of its scheduled runs.
+#[derive(Debug, Clone, Serialize)]
+pub struct RuleReport {
+  /// Rule reported.
+  pub rule: RetentionRule,
+  /// Retention period in days, or `None` when the rule is off.
+  pub retention_days: Option<i32>,
+  /// Rows the rule would remove now, or `None` when it is off or the count failed.
+  pub pending_rows: Option<u64>,
+  /// Metrics of the scheduled runs of the rule.
+  pub metrics: RuleMetrics,
+} // end struct RuleReport
+
+/// Represents the dry-run report of the retention rules.
+#[derive(Debug, Clone, Serialize)]
+pub struct RetentionReport {
+  /// Whether the scheduled runs only count the rows instead of removing them.
+  pub dry_run: bool,
+  /// Report of each rule, in the order they are applied.
+  pub rules: Vec<RuleReport>,
+} // end struct RetentionReport
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn keeps_the_defaults_of_the_rules_not_given() {
+    let policies =
+      RetentionPolicies::parse("drafts=7", RetentionPolicies::default()).unwrap();
+    assert_eq!(policies.drafts, Some(7));
+    assert_eq!(policies.deleted_questions, Some(DEFAULT_DELETED_QUESTIONS_DAYS));
+    assert_eq!(policies.question_changes, Some(DEFAULT_QUESTION_CHANGES_DAYS));
+  }
+
+  #[test]
+  fn turns_off_a_rule_and_ignores_spaces_and_empty_entries() {
+    let spec = " deleted_questions = 0 , question_changes=off,";
+    let policies = RetentionPolicies::parse(spec, RetentionPolicies::default()).unwrap();
+    assert_eq!(policies.days(RetentionRule::DeletedQuestions), Some(0));
+    assert_eq!(policies.days(RetentionRule::QuestionChanges), None);
+    assert_eq!(policies.days(RetentionRule::Drafts), Some(DEFAULT_DRAFTS_DAYS));
+  }
+
+  #[test]
+  fn rejects_unknown_rules_and_invalid_days() {
+    let defaults = RetentionPolicies::default();
+    assert_eq!(
+      RetentionPolicies::parse("drafts", defaults).unwrap_err(),
+      "'drafts' is not like rule=days"
+    );
+    assert_eq!(
+      RetentionPolicies::parse("answers=7", defaults).unwrap_err(),
+      "rule must be one of deleted_questions, drafts or question_changes, found 'answers'"
+    );
+    assert_eq!(
+      RetentionPolicies::parse("drafts=-1", defaults).unwrap_err(),
+      "days of drafts must be a whole number or off, found '-1'"
+    );
+  }
+
+  #[test]
+  fn reports_the_rules_by_name() {
+    let report = RetentionReport {
+      dry_run: true,
+      rules: vec![RuleReport {
+        rule: RetentionRule::DeletedQuestions,
+        retention_days: None,
+        pending_rows: None,
+        metrics: RuleMetrics::default(),
+      }],
+    };
+    let report = serde_json::to_value(report).unwrap();
+    assert_eq!(report["rules"][0]["rule"], "deleted_questions");
+    assert!(report["rules"][0]["metrics"].get("last_error").is_none());
+  }
+}
@@ -0,0 +1,118 @@
+use handle_errors::errors::{MergeHint, QuestionFields};
+
+use crate::types::question::Question;
+
+/// Gets the fields of the given question that an update changes, with the tags in
+/// order and without repetitions.
+///
+/// # Arguments
+///
+/// * `question`: Question, as stored or as sent by an update.
+pub fn question_fields(question: &Question) -> QuestionFields {
+  let mut tags = question.tags.clone().unwrap_or_default();
+  tags.sort();
+  tags.dedup();
+
+  QuestionFields {
+    title: question.title.clone(),
+    content: question.content.clone(),
+    tags,
+  }
+} // end fn question_fields()
+
+/// Gets the hint to merge an update based on an older version of a question with its
+/// current version.
+///
+/// Each field takes the change of the side that changed it. The title and the content
+/// conflict when both sides changed them differently, while the tags added and removed
+/// by each side are merged. Without the fields at the base version, every field that
+/// differs conflicts.
+///
+/// # Arguments
+///
+/// * `base_version`: Version the update was based on.
+/// * `base`: Fields at the version the update was based on, if known.
+/// * `current`: Question at its current version.
+/// * `update`: Question sent by the update.
+pub fn merge_hint(
+  base_version: i32,
+  base: Option<QuestionFields>,
+  current: &Question,
+  update: &Question,
+) -> MergeHint {
+  let theirs = question_fields(current);
+  let ours = question_fields(update);
+  let mut conflicts = Vec::new();
+
+  let title = merge_field(
+    "title",
+    base.as_ref().map(|base| &base.title),
+    &theirs.title,
+    &ours.title,
+    &mut conflicts,
+  );
+  let content = merge_field(
+    "content",
+    base.as_ref().map(|base| &base.content),
+    &theirs.content,
+    &ours.content,
+    &mut conflicts,
+  );
+  let tags = match &base {
+    Some(base) => {
+      let mut tags: Vec<String> = theirs
+        .tags
+        .iter()
+        .filter(|tag| ours.tags.contains(tag) || !base.tags.contains(tag))
+        .chain(ours.tags.iter().filter(|tag| !base.tags.contains(tag)))
+        .cloned()
+        .collect();
+      tags.sort();
+      tags.dedup();
+      tags
+    }
+    None => merge_field("tags", None, &theirs.tags, &ours.tags, &mut conflicts),
+  };
+
+  MergeHint {
+    current_version: current.version,
+    base_version,
+    base,
+    merged: QuestionFields {
+      title,
+      content,
+      tags,
+    },
+    theirs,
+    ours,
+    conflicts,
+  }
+} // end fn merge_hint()
+
+/// Gets the merged value of a field, or the current one when both sides changed it
+/// differently, in which case the field is added to the conflicts.
+///
+/// # Arguments
+///
+/// * `name`: Name of the field.
+/// * `base`: Value at the version the update was based on, if known.
+/// * `theirs`: Value at the current version.
+/// * `ours`: Value of the update.
+/// * `conflicts`: Names of the conflicting fields.
+fn merge_field<T: Clone + PartialEq>(
+  name: &str,
+  base: Option<&T>,
+  theirs: &T,
+  ours: &T,
+  conflicts: &mut Vec<String>,
+) -> T {
+  match base {
+    _ if theirs == ours => theirs.clone(),
+    Some(base) if base == ours => theirs.clone(),
+    Some(base) if base == theirs => ours.clone(),
+    _ => {
+      conflicts.push(name.to_string());
+      theirs.clone()
+    }
+  }
+} // end fn merge_field()
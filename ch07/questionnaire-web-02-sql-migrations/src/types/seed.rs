@@ -0,0 +1,41 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Represents a question of a fixture file, in the format of the `questions.json` file
+/// of the in-memory chapters.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeedQuestion {
+  /// Unique identifier (ID) of the question, as text.
+  pub id: String,
+  /// Title of the question.
+  pub title: String,
+  /// Text contents of the question.
+  pub content: String,
+  /// Tag names of the question, if any.
+  pub tags: Option<Vec<String>>,
+  /// Answers to the question.
+  #[serde(default)]
+  pub answers: Vec<SeedAnswer>,
+} // end struct SeedQuestion
+
+/// Represents an answer of a fixture file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeedAnswer {
+  /// Unique identifier (ID) of the answer, as text.
+  pub id: String,
+  /// Text contents of the answer.
+  pub content: String,
+} // end struct SeedAnswer
+
+/// Represents the questions of a fixture file, by their ID.
+pub type SeedQuestions = HashMap<String, SeedQuestion>;
+
+/// Represents how many rows a fixture file loaded.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SeedSummary {
+  /// Amount of questions added or updated.
+  pub questions: usize,
+  /// Amount of answers added or updated.
+  pub answers: usize,
+} // end struct SeedSummary
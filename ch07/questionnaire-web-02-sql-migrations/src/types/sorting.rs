@@ -0,0 +1,104 @@
+use handle_errors::errors::QError;
+
+/// Represents a field the questions can be sorted by.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SortField {
+  /// Unique identifier (ID) of the question.
+  #[default]
+  Id,
+  /// Date and time the question was created.
+  CreatedAt,
+  /// Title of the question.
+  Title,
+} // end enum SortField
+
+impl SortField {
+  /// Gets the SQL column of this field, for the questions table aliased as `q`.
+  pub fn column(&self) -> &'static str {
+    match self {
+      SortField::Id => "q.id",
+      SortField::CreatedAt => "q.created_on",
+      SortField::Title => "q.title",
+    }
+  }
+}
+
+impl std::str::FromStr for SortField {
+  type Err = QError;
+
+  fn from_str(field: &str) -> Result<Self, Self::Err> {
+    match field {
+      "id" => Ok(SortField::Id),
+      "created_at" => Ok(SortField::CreatedAt),
+      "title" => Ok(SortField::Title),
+      _ => Err(QError::InvalidParameter(format!(
+        "sort must be one of id, created_at or title, found '{}'",
+        field
+      ))),
+    }
+  }
+}
+
+/// Represents the direction of a sort.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+  /// From the lowest to the highest value.
+  #[default]
+  Asc,
+  /// From the highest to the lowest value.
+  Desc,
+} // end enum SortOrder
+
+impl SortOrder {
+  /// Gets the SQL keyword of this direction.
+  pub fn keyword(&self) -> &'static str {
+    match self {
+      SortOrder::Asc => "ASC",
+      SortOrder::Desc => "DESC",
+    }
+  }
+}
+
+impl std::str::FromStr for SortOrder {
+  type Err = QError;
+
+  fn from_str(order: &str) -> Result<Self, Self::Err> {
+    match order {
+      "asc" => Ok(SortOrder::Asc),
+      "desc" => Ok(SortOrder::Desc),
+      _ => Err(QError::InvalidParameter(format!(
+        "order must be one of asc or desc, found '{}'",
+        order
+      ))),
+    }
+  }
+}
+
+/// Represents how a set of questions is sorted.
+///
+/// Only the fields of `SortField` can be used, so the names given by clients never
+/// reach the SQL text.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Sorting {
+  /// Field to sort by.
+  pub field: SortField,
+  /// Direction of the sort.
+  pub order: SortOrder,
+} // end struct Sorting
+
+impl Sorting {
+  /// Gets the SQL `ORDER BY` clause of this sorting.
+  ///
+  /// The question ID breaks ties, so pages are stable between requests.
+  pub fn order_by(&self) -> String {
+    match self.field {
+      SortField::Id => format!("ORDER BY q.id {}", self.order.keyword()),
+      _ => format!(
+        "ORDER BY {} {}, q.id {}",
+        self.field.column(),
+        self.order.keyword(),
+        self.order.keyword()
+      ),
+    }
+  }
+}
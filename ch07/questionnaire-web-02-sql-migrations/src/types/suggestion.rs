@@ -0,0 +1,21 @@
+use serde::Serialize;
+
+use crate::types::question::QuestionId;
+
+/// Represents the title of a question suggested while typing.
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct TitleSuggestion {
+  /// Unique identifier (ID) of the question.
+  pub id: QuestionId,
+  /// Title of the question.
+  pub title: String,
+} // end struct TitleSuggestion
+
+/// Represents the titles and tags suggested for some typed text.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Suggestions {
+  /// Titles of questions, those starting with the text first.
+  pub titles: Vec<TitleSuggestion>,
+  /// Names of tags, those starting with the text first.
+  pub tags: Vec<String>,
+} // end struct Suggestions
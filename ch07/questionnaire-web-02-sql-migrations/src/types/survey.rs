@@ -0,0 +1,599 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+
+use handle_errors::errors::{QError, ResponseError};
+
+use crate::types::id::Id;
+use crate::types::question::{Question, QuestionId, QuestionKind};
+use crate::types::response::{NewResponse, QuestionResults};
+
+/// Represents the unique identifier (ID) of a survey.
+pub type SurveyId = Id<Survey>;
+
+/// Represents a survey, an ordered group of choice and rating questions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Survey {
+  /// Unique identifier (ID) of the survey.
+  pub id: SurveyId,
+  /// Title of the survey.
+  pub title: String,
+  /// Date and time the survey starts accepting responses, if any.
+  pub opens_on: Option<NaiveDateTime>,
+  /// Date and time the survey stops accepting responses, if any.
+  pub closes_on: Option<NaiveDateTime>,
+  /// Highest amount of submissions the survey accepts, if any.
+  pub max_submissions: Option<i32>,
+  /// Amount of submissions given to the survey.
+  pub submission_count: i32,
+  /// Date and time the survey was closed, after reaching its closing date or its
+  /// highest amount of submissions, if it was.
+  pub closed_on: Option<NaiveDateTime>,
+  /// Whether each submission needs a single-use invitation token.
+  pub invitation_only: bool,
+  /// Whether the submissions are scored with the points of the options chosen.
+  pub quiz: bool,
+  /// Origins of the sites allowed to embed the survey widget, e.g.
+  /// `https://example.com`.
+  pub embed_origins: Vec<String>,
+  /// Questions of the survey, in order.
+  pub questions: Vec<Question>,
+  /// Unique identifiers (IDs) of the questions that may be left without a response.
+  pub optional_question_ids: Vec<QuestionId>,
+  /// Rules that show some questions only after given responses.
+  pub rules: Vec<DisplayRule>,
+} // end struct Survey
+
+impl Survey {
+  /// Removes the points of the options of the questions, so the respondents cannot
+  /// tell the correct options of a quiz.
+  pub fn hide_points(mut self) -> Self {
+    for question in self.questions.iter_mut() {
+      question.points = None;
+    }
+    self
+  } // end fn hide_points()
+
+  /// Checks whether the given question is shown to a respondent who gave the given
+  /// responses.
+  ///
+  /// A question without a rule is always shown. A question with a rule is shown when
+  /// the question it depends on is shown and its response includes any of the choices
+  /// or ratings of the rule.
+  ///
+  /// # Arguments
+  ///
+  /// * `question_id`: Unique identifier (ID) of the question.
+  /// * `responses`: Responses given to the questions of the survey.
+  pub fn is_shown(
+    &self,
+    question_id: QuestionId,
+    responses: &[SurveyQuestionResponse],
+  ) -> bool {
+    let rule = match self.rules.iter().find(|rule| rule.question_id == question_id) {
+      Some(rule) => rule,
+      None => return true,
+    };
+
+    // Rules only depend on earlier questions, so the recursion always ends.
+    self.is_shown(rule.depends_on, responses)
+      && responses
+        .iter()
+        .find(|answer| answer.question_id == rule.depends_on)
+        .map_or(false, |answer| {
+          answer.response.values().iter().any(|value| rule.any_of.contains(value))
+        })
+  } // end fn is_shown()
+
+  /// Gets the problems of the given responses that do not depend on the other
+  /// responses: responses to questions not in the survey, more than one response to a
+  /// question, and invalid responses. Only the first problem of each question is listed.
+  ///
+  /// # Arguments
+  ///
+  /// * `responses`: Responses given to the questions of the survey.
+  pub fn response_errors(
+    &self,
+    responses: &[SurveyQuestionResponse],
+  ) -> Vec<ResponseError> {
+    let mut errors = Vec::new();
+
+    for (index, answer) in responses.iter().enumerate() {
+      let question_id = answer.question_id.value();
+      if errors
+        .iter()
+        .any(|error: &ResponseError| error.question_id == question_id)
+      {
+        continue;
+      }
+      let message = match self
+        .questions
+        .iter()
+        .find(|question| question.id == answer.question_id)
+      {
+        None => "the question is not part of the survey".to_string(),
+        Some(_)
+          if responses[..index]
+            .iter()
+            .any(|previous| previous.question_id == answer.question_id) =>
+        {
+          "the question has more than one response".to_string()
+        }
+        Some(question) => match answer.response.validate(question) {
+          Ok(()) => continue,
+          Err(QError::InvalidParameter(message)) => message,
+          Err(err) => err.to_string(),
+        },
+      };
+      errors.push(ResponseError {
+        question_id,
+        message,
+      });
+    }
+
+    errors
+  } // end fn response_errors()
+}
+
+/// Represents a rule that shows a question of a survey only after given responses to an
+/// earlier question, e.g. show question 5 only when the response to question 2 is
+/// "yes".
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DisplayRule {
+  /// Unique identifier (ID) of the question shown by the rule.
+  pub question_id: QuestionId,
+  /// Unique identifier (ID) of the earlier question whose response is checked.
+  pub depends_on: QuestionId,
+  /// Choices, for choice questions, or ratings, for rating questions, that show the
+  /// question when any of them is in the response.
+  pub any_of: Vec<i32>,
+} // end struct DisplayRule
+
+impl DisplayRule {
+  /// Checks that the choices or ratings of the rule are valid for the question it
+  /// depends on.
+  ///
+  /// # Arguments
+  ///
+  /// * `depends_on`: Question the rule depends on.
+  pub fn validate(
+    &self,
+    depends_on: &Question,
+  ) -> Result<(), QError> {
+    let highest = match depends_on.kind {
+      QuestionKind::Rating => depends_on.rating_max.unwrap_or_default(),
+      _ => depends_on.options.as_deref().unwrap_or_default().len() as i32,
+    };
+    match self.any_of.iter().find(|value| !(1..=highest).contains(*value)) {
+      Some(value) => Err(QError::InvalidParameter(format!(
+        "the rule of question {} takes values from 1 to {} for question {}, found {}",
+        self.question_id.value(),
+        highest,
+        depends_on.id.value(),
+        value
+      ))),
+      None => Ok(()),
+    }
+  } // end fn validate()
+}
+
+/// Represents a survey that links a question, along with the settings the question has
+/// in that survey.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct QuestionSurvey {
+  /// Unique identifier (ID) of the survey.
+  pub survey_id: SurveyId,
+  /// Title of the survey.
+  pub title: String,
+  /// Position of the question in the survey, starting at 1.
+  pub position: i32,
+  /// Whether the question needs a response in the survey.
+  pub required: bool,
+  /// Whether the survey no longer accepts responses.
+  pub closed: bool,
+} // end struct QuestionSurvey
+
+/// Represents a new survey to be added to the system.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewSurvey {
+  /// Title of the survey.
+  pub title: String,
+  /// Date and time the survey starts accepting responses, if any.
+  pub opens_on: Option<NaiveDateTime>,
+  /// Date and time the survey stops accepting responses, if any.
+  pub closes_on: Option<NaiveDateTime>,
+  /// Highest amount of submissions the survey accepts, if any.
+  pub max_submissions: Option<i32>,
+  /// Whether each submission needs a single-use invitation token.
+  #[serde(default)]
+  pub invitation_only: bool,
+  /// Whether the submissions are scored with the points of the options chosen.
+  #[serde(default)]
+  pub quiz: bool,
+  /// Origins of the sites allowed to embed the survey widget, e.g.
+  /// `https://example.com`.
+  #[serde(default)]
+  pub embed_origins: Vec<String>,
+  /// Unique identifiers (IDs) of the questions of the survey, in order.
+  pub question_ids: Vec<QuestionId>,
+  /// Unique identifiers (IDs) of the questions that may be left without a response.
+  /// The other questions are required.
+  #[serde(default)]
+  pub optional_question_ids: Vec<QuestionId>,
+  /// Rules that show some questions only after given responses.
+  #[serde(default)]
+  pub rules: Vec<DisplayRule>,
+} // end struct NewSurvey
+
+impl NewSurvey {
+  /// Checks that the survey has distinct questions, optional questions among them, a
+  /// valid response period and highest amount of submissions, valid embed origins, and
+  /// rules that each show a question after responses to an earlier one.
+  pub fn validate(&self) -> Result<(), QError> {
+    if self.question_ids.is_empty() {
+      return Err(QError::InvalidParameter(
+        "surveys need at least one question".to_string(),
+      ));
+    }
+    for (index, id) in self.question_ids.iter().enumerate() {
+      if self.question_ids[..index].contains(id) {
+        return Err(QError::InvalidParameter(format!(
+          "question_ids must be distinct, found {} twice",
+          id.value()
+        )));
+      }
+    }
+    if let (Some(opens_on), Some(closes_on)) = (self.opens_on, self.closes_on) {
+      if closes_on <= opens_on {
+        return Err(QError::InvalidParameter(
+          "closes_on must be later than opens_on".to_string(),
+        ));
+      }
+    }
+    if let Some(max_submissions) = self.max_submissions {
+      if max_submissions < 1 {
+        return Err(QError::InvalidParameter(format!(
+          "max_submissions must be at least 1, found {}",
+          max_submissions
+        )));
+      }
+    }
+
+    if let Some(origin) = self.embed_origins.iter().find(|origin| !is_origin(origin)) {
+      return Err(QError::InvalidParameter(format!(
+        "embed_origins must be origins such as https://example.com, found '{}'",
+        origin
+      )));
+    }
+
+    for (index, id) in self.optional_question_ids.iter().enumerate() {
+      if !self.question_ids.contains(id) || self.optional_question_ids[..index].contains(id)
+      {
+        return Err(QError::InvalidParameter(format!(
+          "optional_question_ids must be distinct questions of the survey, found {}",
+          id.value()
+        )));
+      }
+    }
+
+    for (index, rule) in self.rules.iter().enumerate() {
+      let question_id = rule.question_id.value();
+      let position = self.question_ids.iter().position(|id| *id == rule.question_id);
+      let depends_on = self.question_ids.iter().position(|id| *id == rule.depends_on);
+      match (position, depends_on) {
+        (None, _) => {
+          return Err(QError::InvalidParameter(format!(
+            "the rule of question {} is for a question not in the survey",
+            question_id
+          )));
+        }
+        (_, None) => {
+          return Err(QError::InvalidParameter(format!(
+            "the rule of question {} depends on question {}, which is not in the survey",
+            question_id,
+            rule.depends_on.value()
+          )));
+        }
+        (Some(position), Some(depends_on)) if depends_on >= position => {
+          return Err(QError::InvalidParameter(format!(
+            "the rule of question {} must depend on an earlier question",
+            question_id
+          )));
+        }
+        _ => (),
+      }
+      if self.rules[..index]
+        .iter()
+        .any(|previous| previous.question_id == rule.question_id)
+      {
+        return Err(QError::InvalidParameter(format!(
+          "question {} has more than one rule",
+          question_id
+        )));
+      }
+      if rule.any_of.is_empty() {
+        return Err(QError::InvalidParameter(format!(
+          "the rule of question {} needs at least one value in any_of",
+          question_id
+        )));
+      }
+    }
+
+    Ok(())
+  } // end fn validate()
+}
+
+/// Represents the response to one of the questions of a survey.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SurveyQuestionResponse {
+  /// Unique identifier (ID) of the question.
+  pub question_id: QuestionId,
+  /// Choices or rating given to the question.
+  #[serde(flatten)]
+  pub response: NewResponse,
+} // end struct SurveyQuestionResponse
+
+/// Represents the responses of a respondent to all the questions of a survey.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NewSubmission {
+  /// Responses to the questions of the survey, one per question.
+  pub responses: Vec<SurveyQuestionResponse>,
+  /// Single-use invitation token, for invitation-only surveys.
+  pub token: Option<String>,
+  /// Key of the draft the responses were saved in, if any. The draft is deleted once
+  /// the responses are added.
+  pub draft: Option<String>,
+} // end struct NewSubmission
+
+impl NewSubmission {
+  /// Checks that this submission responds to each required question of the given survey
+  /// that is shown, with valid responses, and does not respond to the questions hidden
+  /// by its rules.
+  ///
+  /// The problems found are listed per question.
+  ///
+  /// # Arguments
+  ///
+  /// * `survey`: Survey the submission is given to.
+  pub fn validate(
+    &self,
+    survey: &Survey,
+  ) -> Result<(), QError> {
+    let mut errors = survey.response_errors(&self.responses);
+
+    for question in survey.questions.iter() {
+      // Only the first problem of each question is listed.
+      if errors.iter().any(|error| error.question_id == question.id.value()) {
+        continue;
+      }
+      let shown = survey.is_shown(question.id, &self.responses);
+      let responded = self
+        .responses
+        .iter()
+        .any(|answer| answer.question_id == question.id);
+      let required = !survey.optional_question_ids.contains(&question.id);
+      match (shown, responded) {
+        (true, false) if required => errors.push(ResponseError {
+          question_id: question.id.value(),
+          message: "the question is required and needs a response".to_string(),
+        }),
+        (false, true) => {
+          let depends_on = survey
+            .rules
+            .iter()
+            .find(|rule| rule.question_id == question.id)
+            .map_or(0, |rule| rule.depends_on.value());
+          errors.push(ResponseError {
+            question_id: question.id.value(),
+            message: format!(
+              "the question is hidden by the response to question {}, so it takes no \
+               response",
+              depends_on
+            ),
+          });
+        }
+        _ => (),
+      }
+    }
+
+    match errors.is_empty() {
+      true => Ok(()),
+      false => Err(QError::InvalidResponses(errors)),
+    }
+  } // end fn validate()
+}
+
+/// Represents the aggregated responses to all the questions of a survey.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SurveyResults {
+  /// Unique identifier (ID) of the survey.
+  pub survey_id: SurveyId,
+  /// Amount of submissions to the survey.
+  pub submissions: i64,
+  /// Results of each question of the survey, in order.
+  pub questions: Vec<QuestionResults>,
+} // end struct SurveyResults
+
+/// Checks whether the given text is a web origin: an `http` or `https` scheme and a
+/// host, with an optional port, but no path.
+///
+/// # Arguments
+///
+/// * `origin`: Text to check.
+fn is_origin(origin: &str) -> bool {
+  let host = match origin
+    .strip_prefix("https://")
+    .or_else(|| origin.strip_prefix("http://"))
+  {
+    Some(host) => host,
+    None => return false,
+  };
+  let (name, port) = match host.split_once(':') {
+    Some((name, port)) => (name, Some(port)),
+    None => (host, None),
+  };
+
+  !name.is_empty()
+    && name
+      .chars()
+      .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+    && port.map_or(true, |port| port.parse::<u16>().is_ok())
+} // end fn is_origin()
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::*;
+  use crate::types::testing::{choices, question, survey};
+
+  /// Builds a new survey with the given questions and no other settings.
+  ///
+  /// # Arguments
+  ///
+  /// * `question_ids`: Unique identifiers (IDs) of the questions, in order.
+  fn new_survey(question_ids: &[i32]) -> NewSurvey {
+    serde_json::from_value(json!({ "title": "Survey", "question_ids": question_ids }))
+      .expect("valid survey")
+  }
+
+  /// Builds a rule that shows a question after the given choices of another one.
+  ///
+  /// # Arguments
+  ///
+  /// * `question_id`: Unique identifier (ID) of the question shown.
+  /// * `depends_on`: Unique identifier (ID) of the question whose response is checked.
+  /// * `any_of`: Choices that show the question.
+  fn rule(
+    question_id: i32,
+    depends_on: i32,
+    any_of: Vec<i32>,
+  ) -> DisplayRule {
+    DisplayRule {
+      question_id: QuestionId::new(question_id),
+      depends_on: QuestionId::new(depends_on),
+      any_of,
+    }
+  }
+
+  #[test]
+  fn accepts_a_survey_of_distinct_questions() {
+    let mut survey = new_survey(&[1, 2]);
+    survey.embed_origins = vec!["https://example.com:8443".to_string()];
+    survey.rules = vec![rule(2, 1, vec![1])];
+
+    assert!(survey.validate().is_ok());
+  }
+
+  #[test]
+  fn rejects_a_survey_without_distinct_questions() {
+    assert!(new_survey(&[]).validate().is_err());
+    assert!(new_survey(&[1, 1]).validate().is_err());
+  }
+
+  #[test]
+  fn rejects_a_survey_that_closes_before_it_opens() {
+    let mut survey = new_survey(&[1]);
+    let opens_on = chrono::Utc::now().naive_utc();
+    survey.opens_on = Some(opens_on);
+    survey.closes_on = Some(opens_on - chrono::Duration::days(1));
+
+    assert!(survey.validate().is_err());
+  }
+
+  #[test]
+  fn rejects_invalid_limits_and_origins() {
+    let mut survey = new_survey(&[1]);
+    survey.max_submissions = Some(0);
+    assert!(survey.validate().is_err());
+
+    let mut survey = new_survey(&[1]);
+    survey.embed_origins = vec!["https://example.com/path".to_string()];
+    assert!(survey.validate().is_err());
+  }
+
+  #[test]
+  fn rejects_a_rule_on_a_later_question() {
+    let mut survey = new_survey(&[1, 2]);
+    survey.rules = vec![rule(1, 2, vec![1])];
+
+    assert!(survey.validate().is_err());
+  }
+
+  #[test]
+  fn lists_the_first_problem_of_each_response() {
+    let survey = survey(vec![question(
+      1,
+      json!({ "kind": "single_choice", "options": ["a", "b"] }),
+    )]);
+    let responses = [choices(1, vec![3]), choices(1, vec![1]), choices(9, vec![1])];
+
+    let errors = survey.response_errors(&responses);
+    let question_ids: Vec<i32> = errors.iter().map(|error| error.question_id).collect();
+    assert_eq!(question_ids, vec![1, 9]);
+  }
+
+  #[test]
+  fn shows_a_question_after_the_choices_of_its_rule() {
+    let options = json!({ "kind": "single_choice", "options": ["yes", "no"] });
+    let mut survey = survey(vec![question(1, options.clone()), question(2, options)]);
+    survey.rules = vec![rule(2, 1, vec![1])];
+
+    assert!(survey.is_shown(QuestionId::new(2), &[choices(1, vec![1])]));
+    assert!(!survey.is_shown(QuestionId::new(2), &[choices(1, vec![2])]));
+  }
+
+  #[test]
+  fn hides_the_points_of_a_quiz() {
+    let survey = survey(vec![question(
+      1,
+      json!({ "kind": "single_choice", "options": ["a", "b"], "points": [1, 0] }),
+    )]);
+
+    assert!(survey.hide_points().questions[0].points.is_none());
+  }
+  /// Builds a submission with the given responses.
+  ///
+  /// # Arguments
+  ///
+  /// * `responses`: Responses to the questions of the survey.
+  fn submission(responses: Vec<SurveyQuestionResponse>) -> NewSubmission {
+    NewSubmission {
+      responses,
+      token: None,
+      draft: None,
+    }
+  }
+
+  #[test]
+  fn lists_the_required_questions_without_a_response() {
+    let options = json!({ "kind": "single_choice", "options": ["a", "b"] });
+    let mut survey = survey((1..=3).map(|id| question(id, options.clone())).collect());
+    survey.optional_question_ids = vec![QuestionId::new(3)];
+
+    let result = submission(vec![choices(1, vec![1])]).validate(&survey);
+    match result {
+      Err(QError::InvalidResponses(errors)) => {
+        let question_ids: Vec<i32> =
+          errors.iter().map(|error| error.question_id).collect();
+        assert_eq!(question_ids, vec![2]);
+      }
+      other => panic!("expected the missing responses, found {:?}", other),
+    }
+    let complete = submission(vec![choices(1, vec![1]), choices(2, vec![2])]);
+    assert!(complete.validate(&survey).is_ok());
+  }
+
+  #[test]
+  fn rejects_a_response_to_a_hidden_question() {
+    let options = json!({ "kind": "single_choice", "options": ["yes", "no"] });
+    let mut survey = survey(vec![question(1, options.clone()), question(2, options)]);
+    survey.rules = vec![rule(2, 1, vec![1])];
+
+    assert!(submission(vec![choices(1, vec![2])]).validate(&survey).is_ok());
+    let hidden = submission(vec![choices(1, vec![2]), choices(2, vec![1])]);
+    assert!(matches!(
+      hidden.validate(&survey),
+      Err(QError::InvalidResponses(errors)) if errors[0].question_id == 2
+    ));
+  }
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// Represents a tag used to classify questions.
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Tag {
+  /// Unique name of the tag.
+  pub name: String,
+  /// Amount of questions that have this tag.
+  pub usage_count: i64,
+} // end struct Tag
@@ -0,0 +1,112 @@
+use handle_errors::errors::{FieldError, QError};
+
+/// Longest title of a question, in characters, as its column allows.
+pub const TITLE_MAX_CHARS: usize = 255;
+
+/// Longest content of a question or an answer, in characters.
+pub const CONTENT_MAX_CHARS: usize = 10_000;
+
+/// Largest amount of tags of a question.
+pub const TAGS_MAX: usize = 10;
+
+/// Longest name of a tag, in characters, as its column allows.
+pub const TAG_MAX_CHARS: usize = 64;
+
+/// Represents the problems found so far with the fields of a request body.
+#[derive(Debug, Default)]
+pub struct FieldErrors {
+  /// Problems found, in the order the fields were checked.
+  errors: Vec<FieldError>,
+} // end struct FieldErrors
+
+impl FieldErrors {
+  /// Checks that a text field is not blank and not longer than the given length.
+  ///
+  /// # Arguments
+  ///
+  /// * `field`: Name of the field.
+  /// * `value`: Value of the field.
+  /// * `max_chars`: Longest value allowed, in characters.
+  pub fn check_text(
+    &mut self,
+    field: &str,
+    value: &str,
+    max_chars: usize,
+  ) {
+    let chars = value.chars().count();
+    if value.trim().is_empty() {
+      self.push(field, "must not be empty".to_string());
+    } else if chars > max_chars {
+      self.push(
+        field,
+        format!("must have at most {} characters, found {}", max_chars, chars),
+      );
+    }
+  } // end fn check_text()
+
+  /// Checks that there are not too many tags, and that each one is a valid name.
+  ///
+  /// # Arguments
+  ///
+  /// * `tags`: Names of the tags, if any.
+  pub fn check_tags(
+    &mut self,
+    tags: Option<&[String]>,
+  ) {
+    let tags = tags.unwrap_or_default();
+    if tags.len() > TAGS_MAX {
+      self.push(
+        "tags",
+        format!("must have at most {} tags, found {}", TAGS_MAX, tags.len()),
+      );
+    }
+    for (index, tag) in tags.iter().enumerate() {
+      self.check_text(&format!("tags[{}]", index), tag, TAG_MAX_CHARS);
+    }
+  } // end fn check_tags()
+
+  /// Adds a problem with a field.
+  ///
+  /// # Arguments
+  ///
+  /// * `field`: Name of the field.
+  /// * `message`: Description of the problem.
+  pub fn push(
+    &mut self,
+    field: &str,
+    message: String,
+  ) {
+    self.errors.push(FieldError {
+      field: field.to_string(),
+      message,
+    });
+  } // end fn push()
+
+  /// Gets an `InvalidFields` error with the problems found, if any.
+  pub fn into_result(self) -> Result<(), QError> {
+    match self.errors.is_empty() {
+      true => Ok(()),
+      false => Err(QError::InvalidFields(self.errors)),
+    }
+  } // end fn into_result()
+}
+
+/// Checks the title, content and tags of a question, as sent to add or update it.
+///
+/// # Arguments
+///
+/// * `title`: Title of the question.
+/// * `content`: Content of the question.
+/// * `tags`: Names of the tags of the question, if any.
+pub fn check_question_fields(
+  title: &str,
+  content: &str,
+  tags: Option<&[String]>,
+) -> Result<(), QError> {
+  let mut errors = FieldErrors::default();
+  errors.check_text("title", title, TITLE_MAX_CHARS);
+  errors.check_text("content", content, CONTENT_MAX_CHARS);
+  errors.check_tags(tags);
+
+  errors.into_result()
+} // end fn check_question_fields()
@@ -0,0 +1,196 @@
+// Renders a survey inside the embed page and posts the responses to the API.
+// Everything is built with DOM methods and `textContent`, so the page needs no inline
+// scripts or styles and works under a strict Content Security Policy.
+(function () {
+  'use strict';
+
+  var root = document.getElementById('survey');
+  var surveyUrl = '/surveys/' + root.getAttribute('data-survey-id');
+  var token = new URLSearchParams(window.location.search).get('token');
+
+  // Replaces the contents of the root element with a status message.
+  function showStatus(message) {
+    var status = document.createElement('p');
+    status.className = 'survey-status';
+    status.textContent = message;
+    root.replaceChildren(status);
+  }
+
+  // Creates a labelled radio button or checkbox.
+  function createChoice(type, name, value, text) {
+    var label = document.createElement('label');
+    var input = document.createElement('input');
+    input.type = type;
+    input.name = name;
+    input.value = String(value);
+    label.appendChild(input);
+    label.appendChild(document.createTextNode(' ' + text));
+    return label;
+  }
+
+  // Creates the fieldset for a question, with its choices or ratings.
+  function createQuestion(question, optional) {
+    var fieldset = document.createElement('fieldset');
+    fieldset.className = 'survey-question';
+    fieldset.setAttribute('data-question-id', String(question.id));
+
+    var legend = document.createElement('legend');
+    legend.textContent = question.title;
+    if (optional) {
+      var note = document.createElement('span');
+      note.className = 'survey-optional';
+      note.textContent = ' (optional)';
+      legend.appendChild(note);
+    }
+    fieldset.appendChild(legend);
+
+    var name = 'question-' + question.id;
+    if (question.kind === 'rating') {
+      for (var rating = 1; rating <= question.rating_max; rating++) {
+        fieldset.appendChild(createChoice('radio', name, rating, String(rating)));
+      }
+    } else {
+      var type = question.kind === 'multiple_choice' ? 'checkbox' : 'radio';
+      question.options.forEach(function (label, index) {
+        fieldset.appendChild(createChoice(type, name, index + 1, label));
+      });
+    }
+
+    var error = document.createElement('p');
+    error.className = 'survey-error';
+    error.hidden = true;
+    fieldset.appendChild(error);
+
+    return fieldset;
+  }
+
+  // Gets the choices or ratings selected for each question, by question ID.
+  function selectedValues(form) {
+    var values = {};
+    form.querySelectorAll('input:checked').forEach(function (input) {
+      var id = input.name.replace('question-', '');
+      values[id] = (values[id] || []).concat(Number(input.value));
+    });
+    return values;
+  }
+
+  // Checks whether a question is shown, following the display rules of the survey.
+  function isShown(survey, questionId, values) {
+    var rule = survey.rules.find(function (rule) {
+      return rule.question_id === questionId;
+    });
+    if (!rule) {
+      return true;
+    }
+    return isShown(survey, rule.depends_on, values)
+      && (values[rule.depends_on] || []).some(function (value) {
+        return rule.any_of.indexOf(value) !== -1;
+      });
+  }
+
+  // Shows and hides the questions after the responses selected so far.
+  function applyRules(survey, form) {
+    var values = selectedValues(form);
+    survey.questions.forEach(function (question) {
+      var fieldset = form.querySelector('[data-question-id="' + question.id + '"]');
+      fieldset.hidden = !isShown(survey, question.id, values);
+    });
+  }
+
+  // Shows the problems found by the API next to their questions.
+  function showErrors(form, errors) {
+    form.querySelectorAll('.survey-error').forEach(function (error) {
+      error.hidden = true;
+      error.textContent = '';
+    });
+    errors.forEach(function (problem) {
+      var fieldset = form.querySelector('[data-question-id="' + problem.question_id + '"]');
+      if (fieldset) {
+        var error = fieldset.querySelector('.survey-error');
+        error.textContent = problem.message;
+        error.hidden = false;
+      }
+    });
+  }
+
+  // Posts the responses to the questions shown.
+  function submit(survey, form, button) {
+    var values = selectedValues(form);
+    var responses = [];
+    survey.questions.forEach(function (question) {
+      var selected = values[question.id];
+      if (!selected || !isShown(survey, question.id, values)) {
+        return;
+      }
+      responses.push(question.kind === 'rating'
+        ? { question_id: question.id, rating: selected[0] }
+        : { question_id: question.id, choices: selected });
+    });
+
+    button.disabled = true;
+    fetch(surveyUrl + '/responses', {
+      method: 'POST',
+      headers: { 'content-type': 'application/json' },
+      body: JSON.stringify({ responses: responses, token: token }),
+    })
+      .then(function (response) {
+        if (response.ok) {
+          showStatus('Thank you, your responses were sent.');
+        } else {
+          return response.json().then(function (body) {
+            if (body.error.code === 'INVALID_RESPONSES') {
+              showErrors(form, body.error.details);
+              button.disabled = false;
+            } else {
+              showStatus(body.error.message);
+            }
+          });
+        }
+      })
+      .catch(function () {
+        showStatus('The responses could not be sent. Please try again later.');
+      });
+  }
+
+  // Renders the survey as a form.
+  function render(survey) {
+    document.title = survey.title;
+
+    var heading = document.createElement('h1');
+    heading.textContent = survey.title;
+
+    var form = document.createElement('form');
+    survey.questions.forEach(function (question) {
+      var optional = survey.optional_question_ids.indexOf(question.id) !== -1;
+      form.appendChild(createQuestion(question, optional));
+    });
+
+    var button = document.createElement('button');
+    button.type = 'submit';
+    button.textContent = 'Send';
+    form.appendChild(button);
+
+    form.addEventListener('change', function () {
+      applyRules(survey, form);
+    });
+    form.addEventListener('submit', function (event) {
+      event.preventDefault();
+      submit(survey, form, button);
+    });
+
+    root.replaceChildren(heading, form);
+    applyRules(survey, form);
+  }
+
+  fetch(surveyUrl, { headers: { accept: 'application/json' } })
+    .then(function (response) {
+      if (!response.ok) {
+        throw new Error(response.statusText);
+      }
+      return response.json();
+    })
+    .then(render)
+    .catch(function () {
+      showStatus('The survey could not be loaded.');
+    });
+})();